@@ -0,0 +1,64 @@
+//! Compares decoding a large aggregates response with the `decoder` crate and with `Streamed`
+//!
+//! Run with `cargo bench --bench decode`.
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use polygon::processor::ResultsParser;
+use polygon::response::aggs::Agg;
+use polygon::rest::decoded::aggs::decode;
+
+/// A response body with `bars` minute bars, like a full 50,000 bar aggregates page
+fn body(bars: usize) -> String {
+    let results: Vec<String> = (0..bars)
+        .map(|i| {
+            let open = 180.0 + (i % 500) as f64 / 100.0;
+            format!(
+                r#"{{"v":{},"vw":{open:.4},"o":{open:.2},"c":{:.2},"h":{:.2},"l":{:.2},"t":{},"n":{}}}"#,
+                1000 + i % 7919,
+                open + 0.05,
+                open + 0.12,
+                open - 0.09,
+                1704205800000i64 + i as i64 * 60_000,
+                10 + i % 97,
+            )
+        })
+        .collect();
+    format!(
+        r#"{{"ticker":"AAPL","queryCount":{bars},"resultsCount":{bars},"adjusted":true,"results":[{}],"status":"OK","request_id":"6a7e466379af0a71039d60cc78e72282","count":{bars}}}"#,
+        results.join(",")
+    )
+}
+
+fn aggregates(c: &mut Criterion) {
+    let mut group = c.benchmark_group("aggregates");
+    for bars in [1_000, 50_000] {
+        let body = body(bars);
+        group.throughput(Throughput::Bytes(body.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("decoder", bars), &body, |b, body| {
+            b.iter(|| decoder::run(serde_json::from_str, decode::aggregates, black_box(body)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("streamed", bars), &body, |b, body| {
+            b.iter(|| {
+                let mut parser = ResultsParser::<Agg>::new();
+                parser.feed(black_box(body).as_bytes()).unwrap();
+                parser.finish().unwrap()
+            })
+        });
+        // As read from the network, in 16 KiB chunks
+        group.bench_with_input(BenchmarkId::new("streamed_chunks", bars), &body, |b, body| {
+            b.iter(|| {
+                let mut parser = ResultsParser::<Agg>::new();
+                for chunk in black_box(body).as_bytes().chunks(16 * 1024) {
+                    parser.feed(chunk).unwrap();
+                }
+                parser.finish().unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, aggregates);
+criterion_main!(benches);
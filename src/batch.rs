@@ -0,0 +1,199 @@
+//! Concurrent execution of many requests
+//!
+//! [`Polygon::batch`] runs any endpoint builders with a bound on the number of requests in flight
+//! and returns each result under its key, so one failing ticker doesn't fail the batch.
+//! [`Polygon::batch_tickers`] builds the requests from a ticker list and a template:
+//!
+//! ```no_run
+//! use polygon::Polygon;
+//! use polygon::layer::RateLimit;
+//! use polygon::request::common::Timespan;
+//! use polygon::rest;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Polygon::default().with_key("your_api_key").layer(RateLimit::per_minute(100));
+//! let tickers = ["AAPL", "MSFT", "NVDA"];
+//!
+//! let bars = client
+//!     .batch_tickers(tickers, 8, |client, ticker| {
+//!         rest::aggs::aggregates(client, ticker, 1, Timespan::Day, "2024-01-01", "2024-12-31").decoded()
+//!     })
+//!     .await;
+//! for (ticker, result) in &bars {
+//!     match result {
+//!         Ok(bars) => println!("{ticker}: {} bars", bars.len()),
+//!         Err(e) => eprintln!("{ticker}: {e}"),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Requests go through the client's layers as usual, so a [`RateLimit`](crate::layer::RateLimit)
+//! layer paces the whole batch and the concurrency only bounds how many wait at once.
+use std::collections::BTreeMap;
+use std::future::{Future, poll_fn};
+use std::pin::Pin;
+use std::task::Poll;
+
+use crate::client::Polygon;
+use crate::error::Result;
+use crate::execute::Execute;
+use crate::request::Request;
+
+impl<Client: Request> Polygon<Client> {
+    /// Execute `requests` with at most `concurrency` in flight, returning each result under its key
+    ///
+    /// Builders are only created as earlier requests finish, and keys should be unique (a later
+    /// result replaces an earlier one with the same key). A `concurrency` of 0 is treated as 1.
+    pub async fn batch<K, B>(
+        &self,
+        requests: impl IntoIterator<Item = (K, B)>,
+        concurrency: usize,
+    ) -> BTreeMap<K, Result<B::Output>>
+    where
+        K: Ord,
+        B: Execute,
+    {
+        let requests = requests.into_iter().map(|(key, builder)| (key, builder.get()));
+        bounded(requests, concurrency).await.into_iter().collect()
+    }
+
+    /// Execute the request built by `template` for each ticker, with at most `concurrency` in flight
+    ///
+    /// Results are keyed by ticker, see [`batch`](Self::batch).
+    pub async fn batch_tickers<'a, B>(
+        &'a self,
+        tickers: impl IntoIterator<Item = impl Into<String>>,
+        concurrency: usize,
+        template: impl Fn(&'a Self, &str) -> B,
+    ) -> BTreeMap<String, Result<B::Output>>
+    where
+        B: Execute,
+    {
+        let requests = tickers.into_iter().map(|ticker| {
+            let ticker = ticker.into();
+            let builder = template(self, &ticker);
+            (ticker, builder)
+        });
+        self.batch(requests, concurrency).await
+    }
+}
+
+/// Poll the futures of `tasks` with at most `concurrency` running, in completion order
+pub(crate) async fn bounded<K, F: Future>(
+    mut tasks: impl Iterator<Item = (K, F)>,
+    concurrency: usize,
+) -> Vec<(K, F::Output)> {
+    let concurrency = concurrency.max(1);
+    let mut running: Vec<(K, Pin<Box<F>>)> = Vec::with_capacity(concurrency);
+    let mut done = Vec::new();
+    poll_fn(|cx| {
+        loop {
+            while running.len() < concurrency {
+                match tasks.next() {
+                    Some((key, task)) => running.push((key, Box::pin(task))),
+                    None => break,
+                }
+            }
+            if running.is_empty() {
+                return Poll::Ready(());
+            }
+
+            let before = running.len();
+            let mut i = 0;
+            while i < running.len() {
+                match running[i].1.as_mut().poll(cx) {
+                    Poll::Ready(output) => {
+                        let (key, _) = running.swap_remove(i);
+                        done.push((key, output));
+                    }
+                    Poll::Pending => i += 1,
+                }
+            }
+            // Start new tasks in the slots that freed up, or wait for a wake-up
+            if running.len() == before {
+                return Poll::Pending;
+            }
+        }
+    })
+    .await;
+    done
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use crate::client::Polygon;
+    use crate::error::{Error, Result};
+    use crate::request::{HttpRequest, Request};
+    use crate::rest;
+    use crate::testing::{Mock, MockClient, MockResponse};
+
+    /// Mock client recording the most requests it had in flight at once
+    #[derive(Debug, Clone, Default)]
+    struct Peak {
+        mock: MockClient,
+        in_flight: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    }
+
+    impl Request for Peak {
+        type Response = MockResponse;
+
+        fn new() -> Self {
+            Self::default()
+        }
+
+        async fn send(&self, request: HttpRequest) -> Result<MockResponse> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(in_flight, Ordering::SeqCst);
+            let response = self.mock.send(request).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            response
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_tickers() {
+        let http = Peak::default();
+        let latency = Duration::from_millis(10);
+        http.mock
+            .register(
+                Mock::get("/v1/related-companies/AAPL")
+                    .respond(200, "aapl")
+                    .latency(latency),
+            )
+            .register(
+                Mock::get("/v1/related-companies/GOOG")
+                    .respond(200, "goog")
+                    .latency(latency),
+            )
+            .register(
+                Mock::get("/v1/related-companies/MSFT")
+                    .respond(200, "msft")
+                    .latency(2 * latency),
+            )
+            .register(
+                Mock::get("/v1/related-companies/NONE")
+                    .respond(404, "not found")
+                    .latency(latency),
+            );
+        let client = Polygon::default().with_client(http.clone()).with_key("key");
+
+        let results = client
+            .batch_tickers(["MSFT", "NONE", "AAPL", "GOOG"], 2, rest::tickers::related)
+            .await;
+        assert_eq!(results.keys().collect::<Vec<_>>(), ["AAPL", "GOOG", "MSFT", "NONE"]);
+        assert_eq!(results["AAPL"].as_deref().unwrap(), "aapl");
+        assert_eq!(results["MSFT"].as_deref().unwrap(), "msft");
+        assert!(matches!(results["NONE"], Err(Error::ApiError { status: 404, .. })));
+        assert_eq!(http.mock.requests().len(), 4);
+        // Two at a time, never more
+        assert_eq!(http.peak.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,582 @@
+//! Response caching
+//!
+//! [`Cached`] wraps any [`Request`] implementation and serves repeated GET requests from a
+//! pluggable [`Storage`] backend. Entries are keyed on the normalized URL with the API key
+//! stripped, so the same cache can be shared between keys. How long a response is kept is
+//! decided per endpoint by a [`Policy`]:
+//!
+//! - Aggregates whose date range ended before today (in New York) are kept forever. Telling
+//!   needs the `chrono` feature; without it they expire like ranges that include today
+//! - `previous_close` and ranges that include today expire after a few minutes
+//! - Reference data (tickers, financials, news) expires after a day
+//!
+//! Only successful (`200`) responses are stored. Call `.bypass_cache()` on any request builder
+//! to force a network round trip, which also refreshes the stored entry.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use polygon::cache::{Cached, storage};
+//! use polygon::client::Polygon;
+//! use polygon::rest;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let http = Cached::new(reqwest::Client::new()).with_storage(storage::Disk::new(".polygon-cache")?);
+//! let client = Polygon::<Cached<reqwest::Client>>::default()
+//!     .with_client(http)
+//!     .with_key("your_api_key");
+//!
+//! // Served from disk after the first run
+//! let json = rest::aggs::aggregates(&client, "AAPL", 1, "day".parse()?, "2024-01-01", "2024-01-31")
+//!     .get()
+//!     .await?;
+//!
+//! // Always fetched
+//! let prev = rest::aggs::previous_close(&client, "AAPL").bypass_cache().get().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod storage;
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::error::Result;
+use crate::layer::Layer;
+use crate::request::{HttpRequest, Method, Request};
+use crate::telemetry;
+#[cfg(feature = "chrono")]
+use crate::time;
+
+pub use storage::{Entry, Storage};
+
+/// How long a response is cached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ttl {
+    /// Not cached
+    Never,
+    /// Cached for a fixed duration
+    For(Duration),
+    /// Cached until evicted
+    Forever,
+}
+
+/// Per-endpoint cache lifetimes
+///
+/// Rules are matched in order against the URL path; `*` matches any single path segment and a
+/// pattern matches every path it is a prefix of. Paths without a matching rule use the built-in
+/// defaults described in the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct Policy {
+    rules: Vec<(String, Ttl)>,
+    /// Lifetime of aggregates whose range includes today, and of `previous_close`
+    pub recent: Ttl,
+    /// Lifetime of reference data (tickers, financials, news)
+    pub reference: Ttl,
+    /// Lifetime of everything else
+    pub fallback: Ttl,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            recent: Ttl::For(Duration::from_secs(5 * 60)),
+            reference: Ttl::For(Duration::from_secs(24 * 60 * 60)),
+            fallback: Ttl::Never,
+        }
+    }
+}
+
+impl Policy {
+    /// Add a rule for paths matching `pattern` (e.g., `/v3/reference/tickers/*`)
+    pub fn rule(mut self, pattern: impl Into<String>, ttl: Ttl) -> Self {
+        self.rules.push((pattern.into(), ttl));
+        self
+    }
+
+    /// Lifetime of the response to `url`
+    pub fn ttl(&self, url: &str) -> Ttl {
+        let path = path(url);
+        if let Some((_, ttl)) = self.rules.iter().find(|(pattern, _)| matches(pattern, path)) {
+            return *ttl;
+        }
+
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match segments[..] {
+            ["v2", "aggs", "ticker", _, "prev"] => self.recent,
+            ["v2", "aggs", "ticker", _, "range", _, _, _, to] => self.closed_after(to),
+            ["v2", "aggs", "grouped", "locale", _, "market", _, date] => self.closed_after(date),
+            ["v1", "open-close", _, date] => self.closed_after(date),
+            ["v3", "reference", ..]
+            | ["vX", "reference", ..]
+            | ["v2", "reference", ..]
+            | ["v1", "related-companies", ..] => self.reference,
+            _ => self.fallback,
+        }
+    }
+
+    /// Forever if `date` is before today in New York, otherwise [`recent`](Self::recent)
+    #[cfg(feature = "chrono")]
+    fn closed_after(&self, date: &str) -> Ttl {
+        self.closed_at(date, chrono::Utc::now())
+    }
+
+    /// Without the `chrono` feature the New York date is unknown, so every range is taken as recent
+    #[cfg(not(feature = "chrono"))]
+    fn closed_after(&self, _date: &str) -> Ttl {
+        self.recent
+    }
+
+    /// [`closed_after`](Self::closed_after) at `now`
+    #[cfg(feature = "chrono")]
+    fn closed_at(&self, date: &str, now: chrono::DateTime<chrono::Utc>) -> Ttl {
+        match new_york_date(date) {
+            Some(day) if day < time::to_new_york(&now).date_naive() => Ttl::Forever,
+            _ => self.recent,
+        }
+    }
+}
+
+/// A `YYYY-MM-DD` date, or the New York date of a millisecond timestamp
+#[cfg(feature = "chrono")]
+fn new_york_date(date: &str) -> Option<chrono::NaiveDate> {
+    match date.parse::<i64>() {
+        Ok(ms) => time::from_millis(ms).map(|dt| time::to_new_york(&dt).date_naive()),
+        Err(_) => time::parse_date(date),
+    }
+}
+
+fn path(url: &str) -> &str {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = without_scheme.find('/').map_or("/", |i| &without_scheme[i..]);
+    path.split('?').next().unwrap_or(path)
+}
+
+fn matches(pattern: &str, path: &str) -> bool {
+    let mut path = path.trim_matches('/').split('/');
+    pattern
+        .trim_matches('/')
+        .split('/')
+        .all(|p| path.next().is_some_and(|s| p == "*" || p == s))
+}
+
+/// Normalized cache key: lowercase scheme and host, `apiKey` removed and query params sorted
+pub fn cache_key(url: &str) -> String {
+    let (base, query) = url.split_once('?').unwrap_or((url, ""));
+    let (origin, path) = match base.split_once("://") {
+        Some((scheme, rest)) => {
+            let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            (format!("{}://{}", scheme.to_lowercase(), host.to_lowercase()), path)
+        }
+        None => (String::new(), base),
+    };
+
+    let mut params: Vec<&str> = query
+        .split('&')
+        .filter(|p| !p.is_empty() && !p.starts_with("apiKey="))
+        .collect();
+    params.sort_unstable();
+
+    if params.is_empty() {
+        format!("{origin}{path}")
+    } else {
+        format!("{origin}{path}?{}", params.join("&"))
+    }
+}
+
+/// [`Layer`] wrapping a client in [`Cached`]
+///
+/// Clients wrapped by clones of the same layer share its storage.
+#[derive(Clone)]
+pub struct Cache {
+    storage: Arc<dyn Storage>,
+    policy: Arc<Policy>,
+}
+
+impl Cache {
+    /// Cache in `storage` with the default [`Policy`]
+    pub fn new(storage: impl Storage + 'static) -> Self {
+        Self {
+            storage: Arc::new(storage),
+            policy: Arc::new(Policy::default()),
+        }
+    }
+
+    /// Set the cache policy
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy = Arc::new(policy);
+        self
+    }
+}
+
+impl Default for Cache {
+    /// In-memory LRU cache with the default [`Policy`]
+    fn default() -> Self {
+        Self::new(storage::Memory::default())
+    }
+}
+
+impl std::fmt::Debug for Cache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cache")
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Request> Layer<C> for Cache {
+    type Client = Cached<C>;
+
+    fn layer(self, inner: C) -> Cached<C> {
+        Cached {
+            inner,
+            storage: self.storage,
+            policy: self.policy,
+        }
+    }
+}
+
+/// A [`Request`] wrapper that caches GET responses
+pub struct Cached<C: Request> {
+    inner: C,
+    storage: Arc<dyn Storage>,
+    policy: Arc<Policy>,
+}
+
+impl<C: Request> Cached<C> {
+    /// Wrap a client with an in-memory LRU cache and the default [`Policy`]
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            storage: Arc::new(storage::Memory::default()),
+            policy: Arc::new(Policy::default()),
+        }
+    }
+
+    /// Set the storage backend
+    pub fn with_storage(mut self, storage: impl Storage + 'static) -> Self {
+        self.storage = Arc::new(storage);
+        self
+    }
+
+    /// Set the cache policy
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy = Arc::new(policy);
+        self
+    }
+
+    /// The wrapped client
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// The storage backend
+    pub fn storage(&self) -> &dyn Storage {
+        self.storage.as_ref()
+    }
+
+    /// Fetch from the network and store the response if the policy allows
+    async fn fetch(&self, request: HttpRequest, key: String) -> Result<Entry> {
+        let ttl = self.policy.ttl(&request.url);
+        let response = self.inner.send(request).await?;
+        let entry = Entry {
+            expires: match ttl {
+                Ttl::For(ttl) => Some(SystemTime::now() + ttl),
+                _ => None,
+            },
+            ..Entry::from_response(&response)
+        };
+
+        if entry.status == 200 && ttl != Ttl::Never {
+            let stored = Entry {
+                latency: None,
+                ..entry.clone()
+            };
+            run(&self.storage, move |storage| storage.put(&key, stored)).await;
+        }
+        Ok(entry)
+    }
+}
+
+impl<C: Request + Clone> Clone for Cached<C> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            storage: self.storage.clone(),
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+impl<C: Request + std::fmt::Debug> std::fmt::Debug for Cached<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cached")
+            .field("inner", &self.inner)
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Request> Request for Cached<C> {
+    type Response = Entry;
+
+    fn new() -> Self {
+        Self::new(C::new())
+    }
+
+    async fn send(&self, request: HttpRequest) -> Result<Entry> {
+        if request.method != Method::Get {
+            return Ok(Entry::from_response(&self.inner.send(request).await?));
+        }
+
+        let key = cache_key(&request.url);
+        if request.bypass_cache {
+            telemetry::cache(request.endpoint, "bypass");
+        } else {
+            let lookup = key.clone();
+            if let Some(entry) = run(&self.storage, move |storage| storage.get(&lookup)).await {
+                if !entry.is_expired(SystemTime::now()) {
+                    telemetry::cache(request.endpoint, "hit");
+                    return Ok(entry);
+                }
+                let expired = key.clone();
+                run(&self.storage, move |storage| storage.remove(&expired)).await;
+            }
+        }
+        if !request.bypass_cache {
+            telemetry::cache(request.endpoint, "miss");
+        }
+        self.fetch(request, key).await
+    }
+}
+
+/// Run a storage operation, on the Tokio blocking thread pool if the storage does blocking I/O
+async fn run<T: Send + 'static>(storage: &Arc<dyn Storage>, op: impl FnOnce(&dyn Storage) -> T + Send + 'static) -> T {
+    #[cfg(feature = "tokio")]
+    if storage.is_blocking()
+        && let Ok(runtime) = tokio::runtime::Handle::try_current()
+    {
+        let storage = storage.clone();
+        return match runtime.spawn_blocking(move || op(storage.as_ref())).await {
+            Ok(output) => output,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        };
+    }
+    op(storage.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::Response;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::UNIX_EPOCH;
+
+    /// Client that counts network calls and echoes the URL
+    #[derive(Clone, Default)]
+    struct Counting(Arc<AtomicUsize>);
+
+    impl Request for Counting {
+        type Response = Entry;
+
+        fn new() -> Self {
+            Self::default()
+        }
+
+        async fn send(&self, request: HttpRequest) -> Result<Entry> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(Entry {
+                status: if request.url.contains("missing") { 404 } else { 200 },
+                body: request.url,
+                request_id: None,
+                headers: vec![("content-type".to_string(), "application/json".to_string())],
+                expires: None,
+                latency: Some(Duration::from_millis(5)),
+            })
+        }
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(
+            cache_key("HTTPS://API.polygon.io/v3/reference/tickers?limit=10&apiKey=secret&active=true"),
+            "https://api.polygon.io/v3/reference/tickers?active=true&limit=10"
+        );
+        assert_eq!(
+            cache_key("https://api.polygon.io/v1/x?apiKey=a"),
+            "https://api.polygon.io/v1/x"
+        );
+    }
+
+    #[test]
+    fn test_policy_ttl() {
+        let policy = Policy::default().rule("/v3/reference/tickers/types", Ttl::Forever);
+        let api = |path: &str| format!("https://api.polygon.io{path}?apiKey=k");
+        // Past ranges are only known to be closed with `chrono`
+        let closed = if cfg!(feature = "chrono") {
+            Ttl::Forever
+        } else {
+            policy.recent
+        };
+
+        assert_eq!(
+            policy.ttl(&api("/v2/aggs/ticker/AAPL/range/1/day/2024-01-01/2024-01-31")),
+            closed
+        );
+        assert_eq!(
+            policy.ttl(&api("/v2/aggs/ticker/AAPL/range/1/day/1704067200000/1706659200000")),
+            closed
+        );
+        assert_eq!(
+            policy.ttl(&api("/v2/aggs/ticker/AAPL/range/1/day/2024-01-01/9999-12-31")),
+            policy.recent
+        );
+        assert_eq!(policy.ttl(&api("/v2/aggs/ticker/AAPL/prev")), policy.recent);
+        assert_eq!(
+            policy.ttl(&api("/v2/aggs/grouped/locale/us/market/stocks/2024-01-02")),
+            closed
+        );
+        assert_eq!(policy.ttl(&api("/v3/reference/tickers")), policy.reference);
+        assert_eq!(policy.ttl(&api("/v3/reference/tickers/types")), Ttl::Forever);
+        assert_eq!(policy.ttl(&api("/v2/last/trade/AAPL")), Ttl::Never);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_closed_after_uses_new_york_date() {
+        let policy = Policy::default();
+        let at = |rfc3339_utc: &str| time::parse_datetime(rfc3339_utc).unwrap();
+
+        // 19:30 EST on the 7th, after-hours still trading although it's the 8th in UTC
+        assert_eq!(
+            policy.closed_at("2024-03-07", at("2024-03-08T00:30:00Z")),
+            policy.recent
+        );
+        assert_eq!(
+            policy.closed_at("2024-03-07", at("2024-03-08T04:59:00Z")),
+            policy.recent
+        );
+        assert_eq!(policy.closed_at("2024-03-07", at("2024-03-08T05:00:00Z")), Ttl::Forever);
+        // EDT is UTC-4
+        assert_eq!(
+            policy.closed_at("2024-07-01", at("2024-07-02T03:59:00Z")),
+            policy.recent
+        );
+        assert_eq!(policy.closed_at("2024-07-01", at("2024-07-02T04:00:00Z")), Ttl::Forever);
+        // Timestamps are taken as New York dates too (2024-07-01 23:00 EDT)
+        assert_eq!(
+            policy.closed_at("1719889200000", at("2024-07-02T03:30:00Z")),
+            policy.recent
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cached_get() {
+        let inner = Counting::default();
+        let calls = inner.0.clone();
+        let cached = Cached::new(inner);
+        let url = "https://api.polygon.io/v2/aggs/ticker/AAPL/range/1/day/2024-01-01/2024-01-31";
+
+        let get = |url: &str| cached.send(HttpRequest::get(url));
+
+        let miss = get(&format!("{url}?apiKey=a")).await.unwrap();
+        assert_eq!(miss.latency(), Some(Duration::from_millis(5)));
+        let hit = get(&format!("{url}?apiKey=b")).await.unwrap();
+        assert_eq!(hit.body, format!("{url}?apiKey=a"));
+        assert_eq!(hit.header("Content-Type"), Some("application/json"));
+        assert_eq!(hit.latency(), None);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let fresh = HttpRequest {
+            bypass_cache: true,
+            ..HttpRequest::get(format!("{url}?apiKey=b"))
+        };
+        cached.send(fresh).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(get(url).await.unwrap().body, format!("{url}?apiKey=b"));
+
+        // Errors and uncacheable endpoints always go to the network
+        let missing = "https://api.polygon.io/v3/reference/tickers/missing";
+        get(missing).await.unwrap();
+        get(missing).await.unwrap();
+        get("https://api.polygon.io/v2/last/trade/AAPL").await.unwrap();
+        get("https://api.polygon.io/v2/last/trade/AAPL").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+    }
+
+    #[test]
+    fn test_memory_lru() {
+        let memory = storage::Memory::new(2);
+        let entry = |body: &str| Entry {
+            status: 200,
+            body: body.to_string(),
+            request_id: None,
+            headers: Vec::new(),
+            expires: None,
+            latency: None,
+        };
+        memory.put("a", entry("a"));
+        memory.put("b", entry("b"));
+        memory.get("a");
+        memory.put("c", entry("c"));
+        assert_eq!(memory.len(), 2);
+        assert!(memory.get("b").is_none());
+        assert!(memory.get("a").is_some());
+    }
+
+    #[test]
+    fn test_disk_round_trip() {
+        let dir = std::env::temp_dir().join(format!("polygon-cache-test-{}", std::process::id()));
+        let disk = storage::Disk::new(&dir).unwrap();
+        let entry = Entry {
+            status: 200,
+            body: "{\"results\": []}\nsecond line".to_string(),
+            request_id: Some("abc".to_string()),
+            headers: vec![("x-ratelimit-remaining".to_string(), "4".to_string())],
+            expires: Some(UNIX_EPOCH + Duration::from_secs(2_000_000_000)),
+            latency: None,
+        };
+
+        disk.put("https://api.polygon.io/v1/x", entry.clone());
+        assert_eq!(disk.get("https://api.polygon.io/v1/x"), Some(entry.clone()));
+        assert_eq!(disk.get("https://api.polygon.io/v1/y"), None);
+        disk.remove("https://api.polygon.io/v1/x");
+        assert_eq!(disk.get("https://api.polygon.io/v1/x"), None);
+
+        // Concurrent writers of one key each leave a complete entry, and no temporary files
+        let entries: Vec<_> = (0..8)
+            .map(|i| Entry {
+                body: format!("{i}").repeat(64 * 1024),
+                ..entry.clone()
+            })
+            .collect();
+        std::thread::scope(|scope| {
+            for entry in &entries {
+                scope.spawn(|| disk.put("https://api.polygon.io/v1/x", entry.clone()));
+            }
+        });
+        assert!(entries.contains(&disk.get("https://api.polygon.io/v1/x").unwrap()));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_cached_disk() {
+        let dir = std::env::temp_dir().join(format!("polygon-cache-disk-test-{}", std::process::id()));
+        let inner = Counting::default();
+        let calls = inner.0.clone();
+        let cached = Cached::new(inner).with_storage(storage::Disk::new(&dir).unwrap());
+        let url = "https://api.polygon.io/v2/aggs/ticker/AAPL/range/1/day/2024-01-01/2024-01-31";
+
+        let miss = cached.send(HttpRequest::get(url)).await.unwrap();
+        let hit = cached.send(HttpRequest::get(url)).await.unwrap();
+        assert_eq!(hit.body, miss.body);
+        assert_eq!(hit.latency(), None);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,293 @@
+//! Storage backends for cached responses
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::response::Response;
+
+/// A cached response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// HTTP status code
+    pub status: u16,
+    /// Response body
+    pub body: String,
+    /// Request ID of the original response
+    pub request_id: Option<String>,
+    /// Headers of the original response
+    pub headers: Vec<(String, String)>,
+    /// When the entry expires (`None` never expires)
+    pub expires: Option<SystemTime>,
+    /// Latency of the network round trip, `None` when served from storage
+    pub latency: Option<Duration>,
+}
+
+impl Entry {
+    /// Copy a response, with no expiry
+    pub fn from_response(response: &impl Response) -> Self {
+        Self {
+            status: response.status(),
+            body: response.body().to_string(),
+            request_id: response.request_id().clone(),
+            headers: response.headers().to_vec(),
+            expires: None,
+            latency: response.latency(),
+        }
+    }
+
+    /// Whether the entry has expired at `now`
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+}
+
+impl Response for Entry {
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn body(&self) -> &str {
+        &self.body
+    }
+
+    fn request_id(&self) -> &Option<String> {
+        &self.request_id
+    }
+
+    fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+}
+
+/// Storage backend for [`Cached`](super::Cached)
+///
+/// Keys are normalized request URLs with the API key removed.
+pub trait Storage: Send + Sync {
+    /// Look up an entry
+    fn get(&self, key: &str) -> Option<Entry>;
+
+    /// Store an entry, replacing any previous one
+    fn put(&self, key: &str, entry: Entry);
+
+    /// Remove an entry
+    fn remove(&self, key: &str);
+
+    /// Whether the methods do blocking I/O, in which case [`Cached`](super::Cached) calls them on
+    /// the Tokio blocking thread pool instead of the async worker (default: false)
+    fn is_blocking(&self) -> bool {
+        false
+    }
+}
+
+/// In-memory storage evicting the least recently used entry once full
+#[derive(Debug)]
+pub struct Memory {
+    capacity: usize,
+    state: Mutex<Lru>,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    tick: u64,
+    entries: HashMap<String, (u64, Entry)>,
+    order: BTreeMap<u64, String>,
+}
+
+impl Lru {
+    fn touch(&mut self, key: &str) -> Option<&Entry> {
+        self.tick += 1;
+        let (used, entry) = self.entries.get_mut(key)?;
+        self.order.remove(used);
+        self.order.insert(self.tick, key.to_string());
+        *used = self.tick;
+        Some(entry)
+    }
+}
+
+impl Memory {
+    /// Create storage holding at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(Lru::default()),
+        }
+    }
+
+    /// Number of stored entries
+    pub fn len(&self) -> usize {
+        self.state.lock().expect("cache lock poisoned").entries.len()
+    }
+
+    /// Whether no entries are stored
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl Storage for Memory {
+    fn get(&self, key: &str) -> Option<Entry> {
+        let mut lru = self.state.lock().expect("cache lock poisoned");
+        lru.touch(key).cloned()
+    }
+
+    fn put(&self, key: &str, entry: Entry) {
+        let mut lru = self.state.lock().expect("cache lock poisoned");
+        lru.tick += 1;
+        let tick = lru.tick;
+        if let Some((used, _)) = lru.entries.insert(key.to_string(), (tick, entry)) {
+            lru.order.remove(&used);
+        }
+        lru.order.insert(tick, key.to_string());
+
+        while lru.entries.len() > self.capacity {
+            let Some((_, oldest)) = lru.order.pop_first() else {
+                break;
+            };
+            lru.entries.remove(&oldest);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        let mut lru = self.state.lock().expect("cache lock poisoned");
+        if let Some((used, _)) = lru.entries.remove(key) {
+            lru.order.remove(&used);
+        }
+    }
+}
+
+/// On-disk storage, one file per entry in a directory
+///
+/// Entries survive restarts, which suits historical data that never changes. I/O errors are
+/// treated as cache misses. Reads and writes are blocking, so [`Cached`](super::Cached) runs them
+/// on the Tokio blocking thread pool.
+#[derive(Debug, Clone)]
+pub struct Disk {
+    dir: PathBuf,
+}
+
+impl Disk {
+    /// Create storage in `dir`, creating the directory if needed
+    pub fn new(dir: impl Into<PathBuf>) -> crate::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| crate::Error::Custom(format!("Failed to create cache directory: {e}")))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        // FNV-1a, stable across runs and platforms
+        let hash = key
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+        self.dir.join(format!("{hash:016x}.cache"))
+    }
+
+    fn read(&self, key: &str) -> std::io::Result<Option<Entry>> {
+        let mut reader = BufReader::new(std::fs::File::open(self.path(key))?);
+        let line = |reader: &mut BufReader<std::fs::File>| -> std::io::Result<String> {
+            let mut line = String::new();
+            reader.read_line(&mut line)?;
+            Ok(line.trim_end_matches('\n').to_string())
+        };
+
+        // Hash collisions are detected by storing the full key
+        if line(&mut reader)? != key {
+            return Ok(None);
+        }
+        let header = line(&mut reader)?;
+        let mut fields = header.split(' ');
+        let (Some(status), Some(expires), Some(request_id), Some(count)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Ok(None);
+        };
+        let Ok(count) = count.parse() else {
+            return Ok(None);
+        };
+        let mut headers = Vec::with_capacity(count);
+        for _ in 0..count {
+            let header = line(&mut reader)?;
+            let Some((name, value)) = header.split_once(": ") else {
+                return Ok(None);
+            };
+            headers.push((name.to_string(), value.to_string()));
+        }
+        let mut body = String::new();
+        reader.read_to_string(&mut body)?;
+
+        Ok(Some(Entry {
+            status: status.parse().unwrap_or_default(),
+            body,
+            request_id: (request_id != "-").then(|| request_id.to_string()),
+            headers,
+            expires: expires.parse().ok().map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
+            latency: None,
+        }))
+    }
+
+    fn write(&self, key: &str, entry: &Entry) -> std::io::Result<()> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+
+        // Each writer gets its own temporary file, so concurrent writes of a key can't interleave
+        let path = self.path(key);
+        let tmp = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut file = BufWriter::new(std::fs::File::create(&tmp)?);
+        let expires = entry
+            .expires
+            .and_then(|e| e.duration_since(UNIX_EPOCH).ok())
+            .map_or("-".to_string(), |d| d.as_secs().to_string());
+        writeln!(file, "{key}")?;
+        writeln!(
+            file,
+            "{} {expires} {} {}",
+            entry.status,
+            entry.request_id.as_deref().unwrap_or("-"),
+            entry.headers.len()
+        )?;
+        for (name, value) in &entry.headers {
+            writeln!(file, "{name}: {value}")?;
+        }
+        file.write_all(entry.body.as_bytes())?;
+        file.flush()?;
+        drop(file);
+        std::fs::rename(&tmp, path).inspect_err(|_| {
+            let _ = std::fs::remove_file(&tmp);
+        })
+    }
+}
+
+impl Storage for Disk {
+    fn get(&self, key: &str) -> Option<Entry> {
+        self.read(key).ok().flatten()
+    }
+
+    fn put(&self, key: &str, entry: Entry) {
+        let _ = self.write(key, &entry);
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = std::fs::remove_file(self.path(key));
+    }
+
+    fn is_blocking(&self) -> bool {
+        true
+    }
+}
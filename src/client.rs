@@ -1,15 +1,22 @@
 //! Main polygon.io API client
-use crate::request::Request;
+use std::time::{Duration, Instant};
+
+use crate::layer::Layer;
+use crate::processor::Processor;
+use crate::request::{HttpRequest, Request};
+use crate::response::Response;
+use crate::telemetry;
 
 /// The main polygon.io API client.
 ///
 /// When the `reqwest` feature is enabled, this uses `reqwest::Client` as the default HTTP client.
 /// When the `reqwest` feature is disabled, you must provide your own HTTP client that implements [`Request`].
 #[cfg(feature = "reqwest")]
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Polygon<Client: Request = reqwest::Client> {
     client: Client,
     api_key: Option<String>,
+    timeout: Option<Duration>,
 }
 
 /// The main polygon.io API client.
@@ -17,10 +24,11 @@ pub struct Polygon<Client: Request = reqwest::Client> {
 /// When the `reqwest` feature is enabled, this uses `reqwest::Client` as the default HTTP client.
 /// When the `reqwest` feature is disabled, you must provide your own HTTP client that implements [`Request`].
 #[cfg(not(feature = "reqwest"))]
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Polygon<Client: Request> {
     client: Client,
     api_key: Option<String>,
+    timeout: Option<Duration>,
 }
 
 // Implementation for any Client that implements Request
@@ -42,6 +50,7 @@ impl<Client: Request> Polygon<Client> {
         Ok(Self {
             client: Client::new(),
             api_key: Some(api_key),
+            timeout: None,
         })
     }
 
@@ -53,6 +62,7 @@ impl<Client: Request> Polygon<Client> {
         Self {
             client: Client::new(),
             api_key: None,
+            timeout: None,
         }
     }
 
@@ -81,25 +91,95 @@ impl<Client: Request> Polygon<Client> {
         self.api_key.as_deref()
     }
 
+    /// Set the default timeout for requests.
+    ///
+    /// Individual requests can override it with `.timeout()` on the endpoint builder.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Get the default request timeout for this instance.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Build a GET request for an API path, authorized with the API key.
+    pub(crate) fn request(&self, endpoint: &'static str, path: &str) -> crate::Result<HttpRequest> {
+        let api_key = self.api_key.as_deref().ok_or(crate::Error::MissingApiKey)?;
+        let mut request = HttpRequest::get(format!("https://api.polygon.io{path}"))
+            .with_header("Authorization", format!("Bearer {api_key}"));
+        request.timeout = self.timeout;
+        request.endpoint = Some(endpoint);
+        Ok(request)
+    }
+
+    /// Send a request and convert the response with `processor`, streaming the body into its sink if it has one.
+    pub(crate) async fn execute<P: Processor>(&self, request: HttpRequest, processor: &P) -> crate::Result<P::Output> {
+        #[cfg(feature = "tracing")]
+        let span = telemetry::span(&request);
+        let endpoint = request.endpoint;
+        let execute = async {
+            let started = Instant::now();
+            let mut sink = processor.sink();
+            let response = match &mut sink {
+                Some(sink) => self.client.send_streaming(request, |chunk| sink.feed(chunk)).await,
+                None => self.client.send(request).await,
+            };
+            telemetry::response(endpoint, &response, started.elapsed());
+
+            let started = Instant::now();
+            let output = match (sink, response) {
+                (Some(sink), Ok(response)) if response.status() == 200 => sink.finish(),
+                (_, response) => processor.process(response),
+            };
+            telemetry::decoded(endpoint, &output, started.elapsed());
+            output
+        };
+        #[cfg(feature = "tracing")]
+        let execute = tracing::Instrument::instrument(execute, span);
+        execute.await
+    }
+
     /// Get a reference to the underlying HTTP client.
     pub fn client(&self) -> &Client {
         &self.client
     }
-}
 
-// Default implementation
-#[cfg(feature = "reqwest")]
-impl Default for Polygon<reqwest::Client> {
-    fn default() -> Self {
-        Self {
-            client: reqwest::Client::new(),
-            api_key: None,
+    /// Wrap the HTTP client in a middleware [`Layer`].
+    ///
+    /// The layer added last is the outermost and sees each request first.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use polygon::Polygon;
+    /// use polygon::cache::Cache;
+    /// use polygon::layer::Retry;
+    ///
+    /// let client = Polygon::default().with_key("my_api_key").layer(Retry::new(3)).layer(Cache::default());
+    /// ```
+    pub fn layer<L: Layer<Client>>(self, layer: L) -> Polygon<L::Client> {
+        Polygon {
+            client: layer.layer(self.client),
+            api_key: self.api_key,
+            timeout: self.timeout,
         }
     }
 }
 
+// The API key is redacted so clients can be logged safely
+impl<Client: Request + std::fmt::Debug> std::fmt::Debug for Polygon<Client> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Polygon")
+            .field("client", &self.client)
+            .field("api_key", &self.api_key.as_ref().map(|_| "[REDACTED]"))
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
 // Default implementation
-#[cfg(not(feature = "reqwest"))]
 impl<Client: Request> Default for Polygon<Client> {
     /// Create a default polygon.io client with no API key set.
     ///
@@ -108,6 +188,32 @@ impl<Client: Request> Default for Polygon<Client> {
         Self {
             client: Client::new(),
             api_key: None,
+            timeout: None,
         }
     }
 }
+
+#[cfg(all(test, feature = "reqwest"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_redacts_api_key() {
+        let client = Polygon::<reqwest::Client>::default().with_key("secret");
+        let debug = format!("{client:?}");
+        assert!(!debug.contains("secret"));
+        assert!(debug.contains("[REDACTED]"));
+        let request = client.request("test", "/v1/x").unwrap();
+        assert_eq!(request.header("authorization"), Some("Bearer secret"));
+        assert!(!format!("{request:?}").contains("secret"));
+    }
+
+    #[test]
+    fn test_missing_api_key() {
+        let client = Polygon::<reqwest::Client>::default();
+        assert!(matches!(
+            client.request("test", "/v1/x"),
+            Err(crate::Error::MissingApiKey)
+        ));
+    }
+}
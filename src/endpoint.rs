@@ -28,7 +28,7 @@ pub enum Endpoint {
 pub enum Tickers {
     /// List all tickers
     #[serde(rename = "all")]
-    All(tickers::all::Params),
+    All(Box<tickers::all::Params>),
 
     /// Get detailed information about a specific ticker
     #[serde(rename = "details")]
@@ -11,6 +11,9 @@ pub enum Error {
     /// HTTP request error
     #[cfg(feature = "reqwest")]
     Reqwest(std::sync::Arc<reqwest::Error>),
+    /// WebSocket error
+    #[cfg(feature = "stream")]
+    WebSocket(std::sync::Arc<tokio_tungstenite::tungstenite::Error>),
     /// Environment variable error
     #[cfg(feature = "dotenvy")]
     Env(std::sync::Arc<dotenvy::Error>),
@@ -35,6 +38,8 @@ impl fmt::Display for Error {
             Error::VarError(e) => write!(f, "Environment variable error: {e}"),
             #[cfg(feature = "reqwest")]
             Error::Reqwest(e) => write!(f, "HTTP request error: {e}"),
+            #[cfg(feature = "stream")]
+            Error::WebSocket(e) => write!(f, "WebSocket error: {e}"),
             #[cfg(feature = "dotenvy")]
             Error::Env(e) => write!(f, "Environment variable error: {e}"),
             Error::MissingApiKey => write!(f, "Missing API key"),
@@ -59,6 +64,8 @@ impl StdError for Error {
         match self {
             #[cfg(feature = "reqwest")]
             Error::Reqwest(e) => Some(e.as_ref()),
+            #[cfg(feature = "stream")]
+            Error::WebSocket(e) => Some(e.as_ref()),
             #[cfg(feature = "dotenvy")]
             Error::Env(e) => Some(e.as_ref()),
             _ => None,
@@ -73,6 +80,13 @@ impl From<reqwest::Error> for Error {
     }
 }
 
+#[cfg(feature = "stream")]
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+    fn from(e: tokio_tungstenite::tungstenite::Error) -> Self {
+        Error::WebSocket(std::sync::Arc::new(e))
+    }
+}
+
 #[cfg(feature = "dotenvy")]
 impl From<dotenvy::Error> for Error {
     fn from(e: dotenvy::Error) -> Self {
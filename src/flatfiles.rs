@@ -0,0 +1,407 @@
+//! Flat Files: bulk daily CSV downloads over S3
+//!
+//! Polygon publishes day aggregates, minute aggregates, trades and quotes as one gzipped CSV per
+//! day in an S3-compatible bucket. [`FlatFiles`] lists and downloads these objects, and [`rows`]
+//! streams a downloaded file through decompression into typed records
+//! ([`GroupedDailyAgg`](crate::response::aggs::GroupedDailyAgg), [`Trade`], [`Quote`]) or, with the
+//! `table` feature, `dataframe` loads it straight into a Polars DataFrame.
+//!
+//! Flat Files use their own S3 credentials (from the Polygon dashboard), not the REST API key.
+//! The endpoint is configurable, so any S3-compatible server such as MinIO can stand in for tests.
+//!
+//! This module is only available when the `flatfiles` feature is enabled.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use polygon::flatfiles::{self, Asset, Dataset, FlatFiles};
+//! use polygon::response::aggs::GroupedDailyAgg;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let files = FlatFiles::new("access_key_id", "secret_access_key");
+//!
+//! for object in files.list(Dataset::DayAggs.prefix(Asset::Stocks)).await?.iter().take(5) {
+//!     println!("{} ({} bytes)", object.key, object.size);
+//! }
+//!
+//! let key = Dataset::DayAggs.key(Asset::Stocks, "2024-03-07")?;
+//! let bytes = files.download(&key).await?;
+//! for agg in flatfiles::rows::<GroupedDailyAgg, _>(bytes.as_slice())? {
+//!     let agg = agg?;
+//!     println!("{:?} {:?}", agg.ticker, agg.close);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod rows;
+mod sigv4;
+
+use std::path::Path;
+
+use reqwest::Url;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::error::{Error, Result};
+use crate::request::common::DateParam;
+use sigv4::Signer;
+
+#[cfg(feature = "table")]
+pub use rows::dataframe;
+pub use rows::{Quote, Record, Row, Rows, Trade, rows};
+
+/// Default Flat Files endpoint
+pub const DEFAULT_ENDPOINT: &str = "https://files.polygon.io";
+/// Default Flat Files bucket
+pub const DEFAULT_BUCKET: &str = "flatfiles";
+
+/// Asset class of a flat file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Asset {
+    /// US stocks (`us_stocks_sip`)
+    Stocks,
+    /// US options (`us_options_opra`)
+    Options,
+    /// US indices (`us_indices`)
+    Indices,
+    /// Forex (`global_forex`)
+    Forex,
+    /// Crypto (`global_crypto`)
+    Crypto,
+}
+
+impl AsRef<str> for Asset {
+    /// Key prefix (e.g., `us_stocks_sip`)
+    fn as_ref(&self) -> &str {
+        match self {
+            Asset::Stocks => "us_stocks_sip",
+            Asset::Options => "us_options_opra",
+            Asset::Indices => "us_indices",
+            Asset::Forex => "global_forex",
+            Asset::Crypto => "global_crypto",
+        }
+    }
+}
+
+/// Kind of flat file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dataset {
+    /// Daily aggregate bars (`day_aggs_v1`)
+    DayAggs,
+    /// Minute aggregate bars (`minute_aggs_v1`)
+    MinuteAggs,
+    /// Trades (`trades_v1`)
+    Trades,
+    /// Quotes (`quotes_v1`)
+    Quotes,
+}
+
+impl AsRef<str> for Dataset {
+    /// Key component (e.g., `day_aggs_v1`)
+    fn as_ref(&self) -> &str {
+        match self {
+            Dataset::DayAggs => "day_aggs_v1",
+            Dataset::MinuteAggs => "minute_aggs_v1",
+            Dataset::Trades => "trades_v1",
+            Dataset::Quotes => "quotes_v1",
+        }
+    }
+}
+
+impl Dataset {
+    /// Key prefix of all files of this dataset (e.g., `us_stocks_sip/day_aggs_v1/`)
+    pub fn prefix(&self, asset: Asset) -> String {
+        format!("{}/{}/", asset.as_ref(), self.as_ref())
+    }
+
+    /// Object key of the file for a date (e.g., `us_stocks_sip/day_aggs_v1/2024/03/2024-03-07.csv.gz`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `date` is not a `YYYY-MM-DD` date.
+    pub fn key(&self, asset: Asset, date: impl DateParam) -> Result<String> {
+        let date = date.into_date();
+        let parts: Vec<&str> = date.split('-').collect();
+        match parts[..] {
+            [year, month, day] if year.len() == 4 && month.len() == 2 && day.len() == 2 => {
+                Ok(format!("{}{year}/{month}/{date}.csv.gz", self.prefix(asset)))
+            }
+            _ => Err(Error::Custom(format!(
+                "Invalid flat file date (expected YYYY-MM-DD): {date}"
+            ))),
+        }
+    }
+}
+
+/// An object in the Flat Files bucket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object {
+    /// Object key
+    pub key: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Last modified time (RFC 3339)
+    pub last_modified: Option<String>,
+    /// Entity tag
+    pub etag: Option<String>,
+}
+
+/// Flat Files S3 client
+#[derive(Debug, Clone)]
+pub struct FlatFiles {
+    client: reqwest::Client,
+    signer: Signer,
+    endpoint: String,
+    bucket: String,
+}
+
+impl FlatFiles {
+    /// Create a client for the default endpoint with Flat Files S3 credentials
+    pub fn new(access_key_id: impl Into<String>, secret_access_key: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            signer: Signer {
+                access_key_id: access_key_id.into(),
+                secret_access_key: secret_access_key.into(),
+                region: "us-east-1".to_string(),
+            },
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            bucket: DEFAULT_BUCKET.to_string(),
+        }
+    }
+
+    /// Set the S3 endpoint (e.g., `http://localhost:9000` for MinIO)
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Set the bucket
+    pub fn bucket(mut self, bucket: impl Into<String>) -> Self {
+        self.bucket = bucket.into();
+        self
+    }
+
+    /// Set the signing region (default: `us-east-1`)
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.signer.region = region.into();
+        self
+    }
+
+    /// Set the HTTP client
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// List all objects whose key starts with `prefix`, following pagination
+    pub async fn list(&self, prefix: impl AsRef<str>) -> Result<Vec<Object>> {
+        let mut objects = Vec::new();
+        let mut continuation: Option<String> = None;
+        loop {
+            let mut url = self.url("")?;
+            {
+                let mut query = url.query_pairs_mut();
+                query
+                    .append_pair("list-type", "2")
+                    .append_pair("prefix", prefix.as_ref());
+                if let Some(token) = &continuation {
+                    query.append_pair("continuation-token", token);
+                }
+            }
+
+            let body = self.send(&url).await?.text().await?;
+            let page = parse_list(&body)?;
+            objects.extend(page.objects);
+            match page.next {
+                Some(token) => continuation = Some(token),
+                None => return Ok(objects),
+            }
+        }
+    }
+
+    /// Download an object into memory (still gzip compressed)
+    ///
+    /// Fine for day aggregates, but trades and quotes files run to gigabytes: stream those with
+    /// [`download_to`](Self::download_to) or [`download_into`](Self::download_into) instead.
+    pub async fn download(&self, key: &str) -> Result<Vec<u8>> {
+        let response = self.send(&self.url(key)?).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Stream an object to a file (still gzip compressed), returning the number of bytes written
+    pub async fn download_to(&self, key: &str, path: impl AsRef<Path>) -> Result<u64> {
+        let mut file = tokio::fs::File::create(path).await.map_err(write_error)?;
+        self.download_into(key, &mut file).await
+    }
+
+    /// Stream an object to `writer` chunk by chunk (still gzip compressed), returning the number of
+    /// bytes written
+    pub async fn download_into<W: AsyncWrite + Unpin>(&self, key: &str, writer: &mut W) -> Result<u64> {
+        let mut response = self.send(&self.url(key)?).await?;
+        let mut written = 0;
+        while let Some(chunk) = response.chunk().await? {
+            writer.write_all(&chunk).await.map_err(write_error)?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await.map_err(write_error)?;
+        Ok(written)
+    }
+
+    fn url(&self, key: &str) -> Result<Url> {
+        let key = sigv4::uri_encode(key, false);
+        Url::parse(&format!("{}/{}/{key}", self.endpoint, self.bucket))
+            .map_err(|e| Error::Custom(format!("Invalid flat files URL: {e}")))
+    }
+
+    /// Send a signed GET request, turning non-success statuses into [`Error::ApiError`]
+    async fn send(&self, url: &Url) -> Result<reqwest::Response> {
+        let mut request = self.client.get(url.clone());
+        for (name, value) in self.signer.sign("GET", url, &[], chrono::Utc::now()) {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().await?;
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        let status = response.status().as_u16();
+        let request_id = response
+            .headers()
+            .get("x-amz-request-id")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        Err(Error::ApiError {
+            status,
+            message: response.text().await.unwrap_or_default(),
+            request_id,
+        })
+    }
+}
+
+fn write_error(e: std::io::Error) -> Error {
+    Error::Custom(format!("Failed to write flat file: {e}"))
+}
+
+/// One page of a ListObjectsV2 response
+struct ListPage {
+    objects: Vec<Object>,
+    next: Option<String>,
+}
+
+fn parse_list(xml: &str) -> Result<ListPage> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| Error::Custom(format!("Invalid S3 listing: {e}")))?;
+    let child = |node: roxmltree::Node, name: &str| {
+        node.children()
+            .find(|n| n.has_tag_name(name))
+            .and_then(|n| n.text())
+            .map(str::to_string)
+    };
+
+    let root = doc.root_element();
+    let objects = root
+        .children()
+        .filter(|n| n.has_tag_name("Contents"))
+        .map(|n| Object {
+            key: child(n, "Key").unwrap_or_default(),
+            size: child(n, "Size").and_then(|s| s.parse().ok()).unwrap_or_default(),
+            last_modified: child(n, "LastModified"),
+            etag: child(n, "ETag").map(|e| e.trim_matches('"').to_string()),
+        })
+        .collect();
+    let truncated = child(root, "IsTruncated").is_some_and(|t| t == "true");
+    let next = child(root, "NextContinuationToken").filter(|_| truncated);
+
+    Ok(ListPage { objects, next })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_dataset_key() {
+        assert_eq!(
+            Dataset::MinuteAggs.key(Asset::Stocks, "2024-03-07").unwrap(),
+            "us_stocks_sip/minute_aggs_v1/2024/03/2024-03-07.csv.gz"
+        );
+        assert_eq!(Dataset::Trades.prefix(Asset::Options), "us_options_opra/trades_v1/");
+        assert!(Dataset::DayAggs.key(Asset::Stocks, "1709802000000").is_err());
+    }
+
+    /// Serve canned responses to consecutive requests, returning the request heads
+    async fn serve(listener: TcpListener, responses: Vec<Vec<u8>>) -> Vec<String> {
+        let mut requests = Vec::new();
+        for body in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut head = Vec::new();
+            while !head.ends_with(b"\r\n\r\n") {
+                head.push(socket.read_u8().await.unwrap());
+            }
+            requests.push(String::from_utf8(head).unwrap());
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            socket.write_all(header.as_bytes()).await.unwrap();
+            socket.write_all(&body).await.unwrap();
+        }
+        requests
+    }
+
+    #[tokio::test]
+    async fn test_list_and_download() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+        let page = |keys: &[&str], next: Option<&str>| {
+            let contents: String = keys
+                .iter()
+                .map(|k| format!("<Contents><Key>{k}</Key><Size>42</Size><ETag>\"abc\"</ETag></Contents>"))
+                .collect();
+            let truncated = match next {
+                Some(token) => {
+                    format!("<IsTruncated>true</IsTruncated><NextContinuationToken>{token}</NextContinuationToken>")
+                }
+                None => "<IsTruncated>false</IsTruncated>".to_string(),
+            };
+            format!(r#"<?xml version="1.0" encoding="UTF-8"?><ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">{truncated}{contents}</ListBucketResult>"#).into_bytes()
+        };
+        let server = tokio::spawn(serve(
+            listener,
+            vec![
+                page(&["us_stocks_sip/day_aggs_v1/2024/03/2024-03-07.csv.gz"], Some("t+1")),
+                page(&["us_stocks_sip/day_aggs_v1/2024/03/2024-03-08.csv.gz"], None),
+                b"gzipped".to_vec(),
+                b"streamed".to_vec(),
+                b"to disk".to_vec(),
+            ],
+        ));
+
+        let files = FlatFiles::new("key", "secret").endpoint(format!("{endpoint}/"));
+        let objects = files.list(Dataset::DayAggs.prefix(Asset::Stocks)).await.unwrap();
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[1].key, "us_stocks_sip/day_aggs_v1/2024/03/2024-03-08.csv.gz");
+        assert_eq!(objects[1].size, 42);
+        assert_eq!(objects[1].etag.as_deref(), Some("abc"));
+
+        let bytes = files.download(&objects[0].key).await.unwrap();
+        assert_eq!(bytes, b"gzipped");
+        let mut sink = Vec::new();
+        assert_eq!(files.download_into(&objects[0].key, &mut sink).await.unwrap(), 8);
+        assert_eq!(sink, b"streamed");
+        let path = std::env::temp_dir().join(format!("polygon-flatfiles-{}.csv.gz", std::process::id()));
+        assert_eq!(files.download_to(&objects[1].key, &path).await.unwrap(), 7);
+        assert_eq!(tokio::fs::read(&path).await.unwrap(), b"to disk");
+        tokio::fs::remove_file(&path).await.unwrap();
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /flatfiles/?list-type=2&prefix=us_stocks_sip%2Fday_aggs_v1%2F "));
+        assert!(requests[1].contains("continuation-token=t%2B1"));
+        assert!(requests[2].starts_with("GET /flatfiles/us_stocks_sip/day_aggs_v1/2024/03/2024-03-07.csv.gz "));
+        assert!(requests[2].contains("authorization: AWS4-HMAC-SHA256 Credential=key/"));
+    }
+}
@@ -0,0 +1,333 @@
+//! Parsing of gzipped flat file CSVs into typed rows
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::str::FromStr;
+
+use flate2::read::MultiGzDecoder;
+
+#[cfg(feature = "serde")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::response::aggs::GroupedDailyAgg;
+
+/// A type that can be parsed from a flat file CSV row
+pub trait Record: Sized {
+    /// Parse a row, looking up columns by header name
+    fn from_row(row: &Row<'_>) -> Result<Self>;
+}
+
+/// A CSV row with access to its columns by header name
+pub struct Row<'a> {
+    columns: &'a HashMap<String, usize>,
+    record: &'a csv::StringRecord,
+}
+
+impl Row<'_> {
+    /// Raw value of a column, `None` if the column is missing or empty
+    pub fn str(&self, column: &str) -> Option<&str> {
+        let index = *self.columns.get(column)?;
+        self.record.get(index).filter(|v| !v.is_empty())
+    }
+
+    /// Parsed value of a column, `None` if the column is missing or empty
+    pub fn get<T: FromStr>(&self, column: &str) -> Result<Option<T>> {
+        self.str(column)
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| Error::Custom(format!("Invalid value for column {column}: {v}")))
+            })
+            .transpose()
+    }
+
+    /// Comma-separated list of integers (e.g., trade conditions)
+    pub fn list(&self, column: &str) -> Result<Option<Vec<i64>>> {
+        self.str(column)
+            .map(|v| {
+                v.split(',')
+                    .map(|c| {
+                        c.trim()
+                            .parse()
+                            .map_err(|_| Error::Custom(format!("Invalid value for column {column}: {v}")))
+                    })
+                    .collect()
+            })
+            .transpose()
+    }
+}
+
+/// Trade from the trades flat files
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct Trade {
+    /// Ticker symbol
+    pub ticker: Option<String>,
+    /// Trade conditions
+    pub conditions: Option<Vec<i64>>,
+    /// Trade correction indicator
+    pub correction: Option<i64>,
+    /// Exchange ID
+    pub exchange: Option<i64>,
+    /// Trade ID
+    pub id: Option<String>,
+    /// Participant timestamp (Unix nanoseconds)
+    pub participant_timestamp: Option<i64>,
+    /// Trade price
+    pub price: Option<f64>,
+    /// Sequence number
+    pub sequence_number: Option<i64>,
+    /// SIP timestamp (Unix nanoseconds)
+    pub sip_timestamp: Option<i64>,
+    /// Trade size
+    pub size: Option<f64>,
+    /// Tape (1 = NYSE, 2 = AMEX, 3 = Nasdaq)
+    pub tape: Option<i64>,
+    /// Trade reporting facility ID
+    pub trf_id: Option<i64>,
+    /// Trade reporting facility timestamp (Unix nanoseconds)
+    pub trf_timestamp: Option<i64>,
+}
+
+/// Quote from the quotes flat files
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct Quote {
+    /// Ticker symbol
+    pub ticker: Option<String>,
+    /// Ask exchange ID
+    pub ask_exchange: Option<i64>,
+    /// Ask price
+    pub ask_price: Option<f64>,
+    /// Ask size
+    pub ask_size: Option<f64>,
+    /// Bid exchange ID
+    pub bid_exchange: Option<i64>,
+    /// Bid price
+    pub bid_price: Option<f64>,
+    /// Bid size
+    pub bid_size: Option<f64>,
+    /// Quote conditions
+    pub conditions: Option<Vec<i64>>,
+    /// Quote indicators
+    pub indicators: Option<Vec<i64>>,
+    /// Participant timestamp (Unix nanoseconds)
+    pub participant_timestamp: Option<i64>,
+    /// Sequence number
+    pub sequence_number: Option<i64>,
+    /// SIP timestamp (Unix nanoseconds)
+    pub sip_timestamp: Option<i64>,
+    /// Tape (1 = NYSE, 2 = AMEX, 3 = Nasdaq)
+    pub tape: Option<i64>,
+    /// Trade reporting facility timestamp (Unix nanoseconds)
+    pub trf_timestamp: Option<i64>,
+}
+
+/// Day and minute aggregate files carry a ticker per row and a nanosecond `window_start`,
+/// which is converted to the millisecond `timestamp` used by the REST types.
+impl Record for GroupedDailyAgg {
+    fn from_row(row: &Row<'_>) -> Result<Self> {
+        Ok(GroupedDailyAgg {
+            ticker: row.get("ticker")?,
+            open: row.get("open")?,
+            high: row.get("high")?,
+            low: row.get("low")?,
+            close: row.get("close")?,
+            volume: row.get("volume")?,
+            vwap: row.get("vwap")?,
+            timestamp: row.get::<i64>("window_start")?.map(|ns| ns / 1_000_000),
+            transactions: row.get("transactions")?,
+            otc: row.get("otc")?,
+        })
+    }
+}
+
+impl Record for Trade {
+    fn from_row(row: &Row<'_>) -> Result<Self> {
+        Ok(Trade {
+            ticker: row.get("ticker")?,
+            conditions: row.list("conditions")?,
+            correction: row.get("correction")?,
+            exchange: row.get("exchange")?,
+            id: row.get("id")?,
+            participant_timestamp: row.get("participant_timestamp")?,
+            price: row.get("price")?,
+            sequence_number: row.get("sequence_number")?,
+            sip_timestamp: row.get("sip_timestamp")?,
+            size: row.get("size")?,
+            tape: row.get("tape")?,
+            trf_id: row.get("trf_id")?,
+            trf_timestamp: row.get("trf_timestamp")?,
+        })
+    }
+}
+
+impl Record for Quote {
+    fn from_row(row: &Row<'_>) -> Result<Self> {
+        Ok(Quote {
+            ticker: row.get("ticker")?,
+            ask_exchange: row.get("ask_exchange")?,
+            ask_price: row.get("ask_price")?,
+            ask_size: row.get("ask_size")?,
+            bid_exchange: row.get("bid_exchange")?,
+            bid_price: row.get("bid_price")?,
+            bid_size: row.get("bid_size")?,
+            conditions: row.list("conditions")?,
+            indicators: row.list("indicators")?,
+            participant_timestamp: row.get("participant_timestamp")?,
+            sequence_number: row.get("sequence_number")?,
+            sip_timestamp: row.get("sip_timestamp")?,
+            tape: row.get("tape")?,
+            trf_timestamp: row.get("trf_timestamp")?,
+        })
+    }
+}
+
+/// Iterator over typed rows of a gzipped CSV, decompressing as it goes
+pub struct Rows<R: Read, T> {
+    reader: csv::Reader<MultiGzDecoder<R>>,
+    /// Column index by header name, resolved once from the header row
+    columns: HashMap<String, usize>,
+    record: csv::StringRecord,
+    _record: std::marker::PhantomData<T>,
+}
+
+impl<R: Read, T: Record> Iterator for Rows<R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        match self.reader.read_record(&mut self.record) {
+            Ok(true) => Some(T::from_row(&Row {
+                columns: &self.columns,
+                record: &self.record,
+            })),
+            Ok(false) => None,
+            Err(e) => Some(Err(csv_error(e))),
+        }
+    }
+}
+
+/// Parse a gzipped flat file into typed rows
+///
+/// `reader` yields the compressed bytes, e.g. a [`std::fs::File`] written by
+/// [`FlatFiles::download_to`](super::FlatFiles::download_to) or the bytes returned by
+/// [`FlatFiles::download`](super::FlatFiles::download).
+pub fn rows<T: Record, R: Read>(reader: R) -> Result<Rows<R, T>> {
+    let mut reader = csv::Reader::from_reader(MultiGzDecoder::new(reader));
+    let columns = reader
+        .headers()
+        .map_err(csv_error)?
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (name.to_string(), index))
+        .collect();
+    Ok(Rows {
+        reader,
+        columns,
+        record: csv::StringRecord::new(),
+        _record: std::marker::PhantomData,
+    })
+}
+
+/// Parse a gzipped flat file directly into a DataFrame
+///
+/// The CSV is decompressed as a stream into a temporary file that Polars memory-maps, so only the
+/// frame itself is held in memory, not the decompressed text. This is blocking, so call it from
+/// [`tokio::task::spawn_blocking`] in async code.
+#[cfg(feature = "table")]
+pub fn dataframe<R: Read>(reader: R) -> Result<polars_core::prelude::DataFrame> {
+    use polars_io::prelude::*;
+
+    let spool = Spool::new()?;
+    let decompress_error = |e: std::io::Error| Error::Custom(format!("Failed to decompress flat file: {e}"));
+    let mut file = std::fs::File::options()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&spool.0)
+        .map_err(decompress_error)?;
+    std::io::copy(&mut MultiGzDecoder::new(reader), &mut file).map_err(decompress_error)?;
+    std::io::Seek::rewind(&mut file).map_err(decompress_error)?;
+
+    CsvReader::new(file)
+        .finish()
+        .map_err(|e| Error::Custom(format!("Polars error: {e}")))
+}
+
+/// Temporary file holding a decompressed CSV, removed on drop
+#[cfg(feature = "table")]
+struct Spool(std::path::PathBuf);
+
+#[cfg(feature = "table")]
+impl Spool {
+    fn new() -> Result<Self> {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let name = format!(
+            "polygon-flatfile-{}-{}.csv",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
+        Ok(Self(std::env::temp_dir().join(name)))
+    }
+}
+
+#[cfg(feature = "table")]
+impl Drop for Spool {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn csv_error(e: csv::Error) -> Error {
+    Error::Custom(format!("Failed to read flat file: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+
+    fn gzip(csv: &str) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(csv.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_rows() {
+        let aggs = gzip(
+            "ticker,volume,open,close,high,low,window_start,transactions\n\
+             A,1035,129.69,129.69,129.69,129.69,1709802000000000000,31\n",
+        );
+        let aggs: Vec<GroupedDailyAgg> = rows(aggs.as_slice()).unwrap().collect::<Result<_>>().unwrap();
+        assert_eq!(aggs[0].ticker.as_deref(), Some("A"));
+        assert_eq!(aggs[0].volume, Some(1035.0));
+        assert_eq!(aggs[0].timestamp, Some(1709802000000));
+        assert_eq!(aggs[0].vwap, None);
+
+        let trades = gzip(
+            "ticker,conditions,correction,exchange,id,participant_timestamp,price,sequence_number,sip_timestamp,size,tape,trf_id,trf_timestamp\n\
+             A,\"12,37\",,21,1,1709814600001094080,129.64,1073,1709814600001380352,1,1,,\n",
+        );
+        let trade = rows::<Trade, _>(trades.as_slice()).unwrap().next().unwrap().unwrap();
+        assert_eq!(trade.conditions, Some(vec![12, 37]));
+        assert_eq!(trade.price, Some(129.64));
+        assert_eq!(trade.correction, None);
+        assert_eq!(trade.id.as_deref(), Some("1"));
+
+        #[cfg(feature = "table")]
+        {
+            let df = dataframe(trades.as_slice()).unwrap();
+            assert_eq!(df.height(), 1);
+            assert_eq!(df.width(), 13);
+        }
+
+        let bad = gzip("ticker,price\nA,abc\n");
+        assert!(rows::<Trade, _>(bad.as_slice()).unwrap().next().unwrap().is_err());
+    }
+}
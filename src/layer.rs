@@ -0,0 +1,153 @@
+//! Middleware layers for the HTTP client
+//!
+//! A [`Layer`] wraps a [`Request`] implementation in another one, so behaviours such as retries,
+//! caching, rate limiting and header injection compose without touching the endpoint builders.
+//! [`Polygon::layer`](crate::client::Polygon::layer) wraps the current client, so the layer added
+//! last is the outermost and sees each request first:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use polygon::Polygon;
+//! use polygon::cache::Cache;
+//! use polygon::layer::{Headers, RateLimit, Retry};
+//! use polygon::rest;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // cache -> retry -> rate limit -> headers -> reqwest
+//! let client = Polygon::default()
+//!     .with_key("your_api_key")
+//!     .layer(Headers::new().header("User-Agent", "my-app/1.0"))
+//!     .layer(RateLimit::per_minute(5))
+//!     .layer(Retry::new(3))
+//!     .layer(Cache::default());
+//!
+//! let json = rest::aggs::previous_close(&client, "AAPL").get().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Implement [`Layer`] together with [`Request`] to write your own middleware.
+
+use crate::error::Result;
+use crate::request::{HttpRequest, Request};
+
+#[cfg(feature = "reqwest")]
+mod rate_limit;
+#[cfg(feature = "reqwest")]
+mod retry;
+
+#[cfg(feature = "reqwest")]
+pub use rate_limit::{RateLimit, RateLimited};
+#[cfg(feature = "reqwest")]
+pub use retry::{Retry, Retrying};
+
+/// Wraps a client in middleware
+pub trait Layer<C: Request> {
+    /// The wrapped client
+    type Client: Request;
+
+    /// Wrap `inner`
+    fn layer(self, inner: C) -> Self::Client;
+}
+
+/// [`Layer`] adding headers to every request
+#[derive(Debug, Clone, Default)]
+pub struct Headers {
+    headers: Vec<(String, String)>,
+}
+
+impl Headers {
+    /// Create a layer adding no headers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a header
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+impl<C: Request> Layer<C> for Headers {
+    type Client = WithHeaders<C>;
+
+    fn layer(self, inner: C) -> WithHeaders<C> {
+        WithHeaders {
+            inner,
+            headers: self.headers,
+        }
+    }
+}
+
+/// A client adding headers to every request, created by [`Headers`]
+#[derive(Debug, Clone)]
+pub struct WithHeaders<C> {
+    inner: C,
+    headers: Vec<(String, String)>,
+}
+
+impl<C: Request> WithHeaders<C> {
+    /// The wrapped client
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    fn with_headers(&self, mut request: HttpRequest) -> HttpRequest {
+        // Headers set by the request itself take precedence
+        for (name, value) in &self.headers {
+            if request.header(name).is_none() {
+                request.headers.push((name.clone(), value.clone()));
+            }
+        }
+        request
+    }
+}
+
+impl<C: Request> Request for WithHeaders<C> {
+    type Response = C::Response;
+
+    fn new() -> Self {
+        Headers::new().layer(C::new())
+    }
+
+    async fn send(&self, request: HttpRequest) -> Result<C::Response> {
+        self.inner.send(self.with_headers(request)).await
+    }
+
+    async fn send_streaming<F>(&self, request: HttpRequest, sink: F) -> Result<C::Response>
+    where
+        F: FnMut(&[u8]) -> Result<()> + Send,
+    {
+        self.inner.send_streaming(self.with_headers(request), sink).await
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::client::Polygon;
+    use crate::rest;
+    use crate::testing::{Mock, MockClient};
+
+    #[tokio::test]
+    async fn test_headers() {
+        let mock = MockClient::new();
+        mock.register(Mock::any().respond(200, "{}"));
+        let client = Polygon::<MockClient>::default()
+            .with_client(mock.clone())
+            .with_key("test")
+            .layer(
+                Headers::new()
+                    .header("User-Agent", "test/1.0")
+                    .header("Authorization", "ignored"),
+            );
+
+        rest::tickers::types(&client).get().await.unwrap();
+        let request = &mock.requests()[0];
+        assert_eq!(request.header("user-agent"), Some("test/1.0"));
+        assert_eq!(request.header("authorization"), Some("Bearer test"));
+    }
+}
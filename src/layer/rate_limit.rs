@@ -0,0 +1,139 @@
+//! Client-side rate limiting
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+use crate::request::{HttpRequest, Request};
+use crate::telemetry;
+
+use super::Layer;
+
+/// [`Layer`] sending at most `requests` requests in any window of `period`
+///
+/// Requests over the limit wait for a slot instead of failing, so a job stays within the plan's
+/// quota (e.g., 5 requests per minute on the free plan). Clones of the limited client share the
+/// same budget. Add [`Retry`](super::Retry) after this layer so that retries are limited too.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    requests: usize,
+    period: Duration,
+}
+
+impl RateLimit {
+    /// Allow `requests` requests per `period`
+    pub fn new(requests: usize, period: Duration) -> Self {
+        Self {
+            requests: requests.max(1),
+            period,
+        }
+    }
+
+    /// Allow `requests` requests per second
+    pub fn per_second(requests: usize) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    /// Allow `requests` requests per minute
+    pub fn per_minute(requests: usize) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+}
+
+impl<C: Request> Layer<C> for RateLimit {
+    type Client = RateLimited<C>;
+
+    fn layer(self, inner: C) -> RateLimited<C> {
+        RateLimited {
+            inner,
+            limit: self,
+            slots: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+}
+
+/// A rate limited client, created by [`RateLimit`]
+#[derive(Debug, Clone)]
+pub struct RateLimited<C> {
+    inner: C,
+    limit: RateLimit,
+    /// Start times of recent and scheduled requests, oldest first
+    slots: Arc<Mutex<VecDeque<Instant>>>,
+}
+
+impl<C: Request> RateLimited<C> {
+    /// The wrapped client
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Reserve the next free slot and return how long to wait for it
+    fn reserve(&self, now: Instant) -> Duration {
+        let RateLimit { requests, period } = self.limit;
+        let mut slots = self.slots.lock().expect("rate limit lock poisoned");
+        while slots
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) >= period)
+        {
+            slots.pop_front();
+        }
+        let slot = match slots.len().checked_sub(requests) {
+            Some(i) => slots[i] + period,
+            None => now,
+        };
+        slots.push_back(slot);
+        slot.saturating_duration_since(now)
+    }
+
+    /// Wait for a slot to send `request`
+    async fn wait(&self, request: &HttpRequest) {
+        let wait = self.reserve(Instant::now());
+        telemetry::rate_limit_wait(request.endpoint, wait);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl<C: Request> Request for RateLimited<C> {
+    type Response = C::Response;
+
+    fn new() -> Self {
+        RateLimit::per_minute(5).layer(C::new())
+    }
+
+    async fn send(&self, request: HttpRequest) -> Result<C::Response> {
+        self.wait(&request).await;
+        self.inner.send(request).await
+    }
+
+    async fn send_streaming<F>(&self, request: HttpRequest, sink: F) -> Result<C::Response>
+    where
+        F: FnMut(&[u8]) -> Result<()> + Send,
+    {
+        self.wait(&request).await;
+        self.inner.send_streaming(request, sink).await
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::MockClient;
+
+    #[test]
+    fn test_reserve() {
+        let limited = RateLimit::new(2, Duration::from_secs(10)).layer(MockClient::new());
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        assert_eq!(limited.reserve(at(0)), Duration::ZERO);
+        assert_eq!(limited.reserve(at(1)), Duration::ZERO);
+        // Third request waits for the first to leave the window
+        assert_eq!(limited.reserve(at(2)), Duration::from_secs(8));
+        assert_eq!(limited.reserve(at(2)), Duration::from_secs(9));
+        // Window has passed the first two requests
+        assert_eq!(limited.reserve(at(20)), Duration::ZERO);
+    }
+}
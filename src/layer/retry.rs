@@ -0,0 +1,210 @@
+//! Retries with exponential backoff
+
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::request::{HttpRequest, Method, Request};
+use crate::response::Response;
+use crate::telemetry;
+
+use super::Layer;
+
+/// [`Layer`] retrying failed requests with exponential backoff
+///
+/// Transport errors, timeouts and `429`, `500`, `502`, `503` and `504` responses to `GET` requests
+/// are retried; other errors and `POST` requests are returned as they are. The delay
+/// starts at [`backoff`](Self::backoff) and doubles on every attempt up to
+/// [`max_backoff`](Self::max_backoff), unless the response carries a `Retry-After` header.
+#[derive(Debug, Clone, Copy)]
+pub struct Retry {
+    max_retries: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Retry {
+    /// Retry up to `max_retries` times, starting with a 500ms delay
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    /// Set the delay before the first retry
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Set the longest delay between retries
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| self.backoff.saturating_mul(2u32.saturating_pow(retry)))
+            .min(self.max_backoff)
+    }
+}
+
+impl<C: Request> Layer<C> for Retry {
+    type Client = Retrying<C>;
+
+    fn layer(self, inner: C) -> Retrying<C> {
+        Retrying { inner, retry: self }
+    }
+}
+
+/// A client retrying failed requests, created by [`Retry`]
+#[derive(Debug, Clone)]
+pub struct Retrying<C> {
+    inner: C,
+    retry: Retry,
+}
+
+impl<C: Request> Retrying<C> {
+    /// The wrapped client
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+fn is_retryable(status: u16) -> bool {
+    matches!(status, 429 | 500 | 502 | 503 | 504)
+}
+
+/// Connection failures and timeouts, after which the request can safely be sent again
+fn is_transient(error: &Error) -> bool {
+    match error {
+        Error::Reqwest(e) => e.is_connect() || e.is_timeout() || e.is_request() || e.is_body(),
+        _ => false,
+    }
+}
+
+impl<C: Request> Request for Retrying<C> {
+    type Response = C::Response;
+
+    fn new() -> Self {
+        Retry::new(3).layer(C::new())
+    }
+
+    async fn send(&self, request: HttpRequest) -> Result<C::Response> {
+        if request.method != Method::Get {
+            return self.inner.send(request).await;
+        }
+        let mut retry = 0;
+        loop {
+            let result = self.inner.send(request.clone()).await;
+            let retry_after = match &result {
+                Ok(response) if is_retryable(response.status()) => response.retry_after(),
+                Err(e) if is_transient(e) => None,
+                _ => return result,
+            };
+            if retry >= self.retry.max_retries {
+                return result;
+            }
+            let delay = self.retry.delay(retry, retry_after);
+            retry += 1;
+            telemetry::retry(request.endpoint, retry, delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn send_streaming<F>(&self, request: HttpRequest, mut sink: F) -> Result<C::Response>
+    where
+        F: FnMut(&[u8]) -> Result<()> + Send,
+    {
+        if request.method != Method::Get {
+            return self.inner.send_streaming(request, sink).await;
+        }
+        let mut retry = 0;
+        loop {
+            // Part of the body may already be consumed when an error interrupts it, so only
+            // errors before the first chunk are retried
+            let mut streamed = false;
+            let result = self
+                .inner
+                .send_streaming(request.clone(), |chunk: &[u8]| {
+                    streamed = true;
+                    sink(chunk)
+                })
+                .await;
+            let retry_after = match &result {
+                Ok(response) if is_retryable(response.status()) => response.retry_after(),
+                Err(e) if !streamed && is_transient(e) => None,
+                _ => return result,
+            };
+            if retry >= self.retry.max_retries {
+                return result;
+            }
+            let delay = self.retry.delay(retry, retry_after);
+            retry += 1;
+            telemetry::retry(request.endpoint, retry, delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing", feature = "reqwest"))]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use crate::testing::{Mock, MockClient};
+
+    #[test]
+    fn test_delay() {
+        let retry = Retry::new(5)
+            .backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_secs(1));
+        assert_eq!(retry.delay(0, None), Duration::from_millis(100));
+        assert_eq!(retry.delay(2, None), Duration::from_millis(400));
+        assert_eq!(retry.delay(10, None), Duration::from_secs(1));
+        assert_eq!(retry.delay(0, Some(Duration::ZERO)), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_retrying() {
+        // Nothing listens on port 1, so this is a real connection error
+        let refused = reqwest::Client::new()
+            .get("http://127.0.0.1:1/")
+            .send()
+            .await
+            .unwrap_err();
+        let mock = MockClient::new();
+        mock.register(Mock::get("/v1/flaky").error(refused.into()).times(1))
+            .register(
+                Mock::get("/v1/flaky")
+                    .respond(503, "")
+                    .header("Retry-After", "0")
+                    .times(1),
+            )
+            .register(Mock::get("/v1/flaky").respond(200, "ok"))
+            .register(Mock::get("/v1/missing").respond(404, "not found"))
+            .register(Mock::get("/v1/down").respond(500, "down"))
+            .register(Mock::get("/v1/invalid").error(Error::Custom("invalid".to_string())))
+            .register(Mock::post("/v1/create").respond(503, ""));
+        let client = Retry::new(2).backoff(Duration::from_millis(1)).layer(mock.clone());
+        let get = |path: &str| client.send(HttpRequest::get(format!("https://api.polygon.io{path}")));
+
+        assert_eq!(get("/v1/flaky").await.unwrap().body, "ok");
+        assert_eq!(mock.received("/v1/flaky"), 3);
+
+        assert_eq!(get("/v1/missing").await.unwrap().status, 404);
+        assert_eq!(mock.received("/v1/missing"), 1);
+
+        assert_eq!(get("/v1/down").await.unwrap().status, 500);
+        assert_eq!(mock.received("/v1/down"), 3);
+
+        assert!(get("/v1/invalid").await.is_err());
+        assert_eq!(mock.received("/v1/invalid"), 1);
+
+        let post = HttpRequest::post("https://api.polygon.io/v1/create", "{}");
+        assert_eq!(client.send(post).await.unwrap().status, 503);
+        assert_eq!(mock.received("/v1/create"), 1);
+    }
+}
@@ -39,6 +39,9 @@
 //!     .await?;
 //!
 //! println!("{:?} {:?}", data[0].ticker, data[0].name);
+//!
+//! // CSV text with nested fields flattened into dotted columns (e.g. `address.city`)
+//! let csv = tickers::details(&client, "AAPL").as_csv().get().await?;
 //! # Ok(())
 //! # }
 //! ```
@@ -47,17 +50,54 @@
 //!
 //! - **`reqwest`** (default) - Uses [`reqwest`](https://docs.rs/reqwest) as the HTTP client.
 //!   Disable this if you want to provide your own HTTP client implementation.
+//!   Also provides the [`Retry`](layer::Retry) and [`RateLimit`](layer::RateLimit) middleware, which use tokio's timer.
 //!
 //! - **`decoder`** (default) - Enables typed response decoding using the [`decoder`](https://docs.rs/decoder) crate.
 //!   Provides `rest::tickers`, `rest::aggs`, etc. that return strongly-typed Rust structs.
 //!   Raw JSON endpoints in `rest::raw::*` (re-exported to `rest::*`) are always available regardless of this feature.
 //!
+//! - **`serde`** (enabled by `decoder`) - Derives `Serialize`, `Deserialize` and `JsonSchema` for every type in
+//!   [`response`], so decoded results can be cached, sent over a message bus or returned from your own API.
+//!
 //! - **`dotenvy`** - Enables loading API keys from environment variables via [`dotenvy`](https://docs.rs/dotenvy).
 //!   Adds `Polygon::new()` which loads `POLYGON_API_KEY` from `.env` or environment.
 //!   Without this feature, use `Polygon::default().with_key("your_key")` instead.
 //!
 //! - **`table`** - Enables Polars DataFrame output via [`polars`](https://docs.rs/polars).
-//!   Provides `rest::table::*` modules that return DataFrames instead of JSON or structs.
+//!   Provides `rest::table::*` modules that return DataFrames instead of JSON or structs, with a
+//!   fixed column schema per endpoint. Also enables `chrono`.
+//!
+//! - **`arrow`** - Enables Arrow output via [`arrow`](https://docs.rs/arrow), with the same column schemas as `table`.
+//!   Provides `rest::arrow::*` modules that return `RecordBatch`es, and
+//!   [`ParquetWriter`](processor::ParquetWriter) to stream them to Hive-partitioned Parquet files
+//!   (e.g. `ticker=AAPL/date=2024-01-02/part-0.parquet`). Also enables `chrono`.
+//!
+//! - **`chrono`** - Enables typed dates via [`chrono`](https://docs.rs/chrono).
+//!   Date parameters accept `NaiveDate`/`DateTime` values, decoded types gain typed timestamp accessors,
+//!   and the `time` module provides America/New_York session helpers. The response cache needs it to
+//!   keep closed aggregate ranges forever.
+//!
+//! - **`tokio`** (enabled by `reqwest`, `stream` and `testing`) - Runs blocking cache storage such as
+//!   [`storage::Disk`](cache::storage::Disk) on Tokio's blocking thread pool.
+//!
+//! - **`stream`** - Enables the [`stream`] module, a WebSocket client for Polygon's real-time and delayed feeds
+//!   built on [`tokio-tungstenite`](https://docs.rs/tokio-tungstenite). Events are decoded into typed structs
+//!   and delivered as an async `Stream`, with automatic reconnect and resubscription.
+//!
+//! - **`flatfiles`** - Enables the [`flatfiles`] module for Polygon's bulk daily CSV files over S3.
+//!   Lists and downloads objects from any S3-compatible endpoint and parses the gzipped CSVs into typed rows,
+//!   or into DataFrames when combined with `table`.
+//!
+//! - **`tracing`** - Emits a [`tracing`](https://docs.rs/tracing) span for every request with the endpoint name,
+//!   redacted URL, status, request ID, latency, response size, retry count and decode time.
+//!
+//! - **`metrics`** - Records request counts by endpoint and status class, latency and decode time histograms,
+//!   retries, cache hits and rate limiter waits with the [`metrics`](https://docs.rs/metrics) crate, for any
+//!   exporter such as Prometheus. Metric names start with `polygon_`.
+//!
+//! - **`testing`** - Enables the [`testing`] module with [`MockClient`](testing::MockClient), a [`Request`]
+//!   implementation returning canned responses, errors or delays and recording every request, for unit testing
+//!   code built on this crate without the network.
 //!
 //! # LLM Tool Use
 //!
@@ -107,7 +147,11 @@
 
 #![warn(missing_docs)]
 
-mod client;
+pub mod batch;
+pub mod cache;
+#[cfg(feature = "serde")]
+pub mod cassette;
+pub mod client;
 pub mod error;
 pub mod request;
 pub mod response;
@@ -115,17 +159,30 @@ pub mod rest;
 
 pub mod endpoint;
 pub mod execute;
+#[cfg(feature = "flatfiles")]
+pub mod flatfiles;
+pub mod layer;
 pub mod processor;
+#[cfg(feature = "stream")]
+pub mod stream;
+mod telemetry;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "chrono")]
+pub mod time;
+#[cfg(not(feature = "chrono"))]
+mod time;
 pub mod tool_use;
 
 pub use error::{Error, Result};
-pub use request::Request;
+pub use request::{HttpRequest, Request};
 pub use response::Response;
 
 /// The main polygon.io API client with the default `reqwest::Client` HTTP client.
 ///
 /// This type alias is only available when the `reqwest` feature is enabled.
-/// When `reqwest` is disabled, use `client::Polygon<YourClient>` directly.
+/// When `reqwest` is disabled, or to wrap the HTTP client (e.g., in [`cache::Cached`]),
+/// use `client::Polygon<YourClient>` directly.
 #[cfg(feature = "reqwest")]
 pub type Polygon = client::Polygon<reqwest::Client>;
 
@@ -148,3 +205,12 @@ pub fn initialize(polygon: Polygon) -> std::sync::Arc<Polygon> {
 pub fn instance() -> std::sync::Arc<Polygon> {
     STATIC_INSTANCE.load().clone()
 }
+
+/// Response body of the cassette at `tests/cassettes/{name}.json`, for unit tests
+#[cfg(test)]
+pub(crate) fn fixture(name: &str) -> String {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("tests/cassettes/{name}.json"));
+    let cassette = std::fs::read_to_string(path).expect("Failed to read cassette");
+    let interactions: serde_json::Value = serde_json::from_str(&cassette).expect("Invalid cassette");
+    serde_json::to_string_pretty(&interactions[0]["body"]).expect("Invalid cassette body")
+}
@@ -7,16 +7,56 @@ pub mod raw;
 
 pub use raw::Raw;
 
+mod flat;
+
+pub mod csv;
+
+pub use self::csv::Csv;
+
+pub mod ndjson;
+
+pub use ndjson::NdJson;
+
+#[cfg(any(feature = "table", feature = "arrow"))]
+pub mod schema;
+
+#[cfg(any(feature = "table", feature = "arrow"))]
+pub use schema::Schema;
+
 #[cfg(feature = "table")]
 pub mod table;
 
 #[cfg(feature = "table")]
 pub use table::Table;
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
+#[cfg(feature = "arrow")]
+pub use self::arrow::{Arrow, ParquetWriter, Partition};
+
 pub mod decoder;
 
 pub use decoder::Decoder;
 
+#[cfg(feature = "serde")]
+pub mod streamed;
+
+#[cfg(feature = "serde")]
+pub use streamed::{ResultsParser, Streamed};
+
+#[cfg(feature = "serde")]
+pub mod typed;
+
+#[cfg(feature = "serde")]
+pub use typed::Typed;
+
+#[cfg(feature = "serde")]
+pub mod envelope;
+
+#[cfg(feature = "serde")]
+pub use envelope::Enveloped;
+
 /// Converts an HTTP response to the desired output type
 pub trait Processor {
     /// The output type this processor produces
@@ -27,4 +67,68 @@ pub trait Processor {
     /// The processor handles both success and failure cases.
     /// It can inspect the response, validate status codes, and convert the body.
     fn process<R: Response>(&self, response: Result<R>) -> Result<Self::Output>;
+
+    /// A sink consuming successful response bodies as they arrive, if this processor supports it
+    ///
+    /// When this returns a sink, `200` bodies are passed to it with
+    /// [`send_streaming`](crate::Request::send_streaming) instead of being buffered, and `process`
+    /// only sees other responses and errors.
+    fn sink(&self) -> Option<Box<dyn BodySink<Self::Output>>> {
+        None
+    }
+}
+
+/// Consumes a response body in chunks, see [`Processor::sink`]
+pub trait BodySink<T>: Send {
+    /// Consume the next chunk of the body
+    fn feed(&mut self, chunk: &[u8]) -> Result<()>;
+
+    /// Finish the body and return the output
+    fn finish(self: Box<Self>) -> Result<T>;
+}
+
+/// Switches a request builder to another [`Processor`], keeping its parameters
+///
+/// Implemented by all endpoint request builders (e.g., `Aggregates`, `News`, `All`). The builders'
+/// `as_dataframe()`, `as_csv()`, `decoded()` etc. are shorthands for `process_with`, which also
+/// accepts your own processors:
+///
+/// ```no_run
+/// use polygon::Polygon;
+/// use polygon::processor::Processor;
+/// use polygon::rest;
+/// use polygon::{Response, Result};
+///
+/// /// Returns the size of the response body
+/// struct BodySize;
+///
+/// impl Processor for BodySize {
+///     type Output = usize;
+///
+///     fn process<R: Response>(&self, response: Result<R>) -> Result<usize> {
+///         Ok(response?.body().len())
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let client = Polygon::default().with_key("your_api_key");
+/// let size = rest::tickers::related(&client, "AAPL").process_with(BodySize).get().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait WithProcessor: Sized {
+    /// The current processor
+    type Processor: Processor;
+
+    /// The same builder with processor `Q`
+    type With<Q: Processor>;
+
+    /// Replace the processor
+    fn process_with<Q: Processor>(self, processor: Q) -> Self::With<Q> {
+        self.map_processor(|_| processor)
+    }
+
+    /// Replace the processor with one built from the current one (e.g. a wrapper around it)
+    fn map_processor<Q: Processor>(self, f: impl FnOnce(Self::Processor) -> Q) -> Self::With<Q>;
 }
@@ -0,0 +1,178 @@
+//! Arrow processor. Converts JSON response to an Arrow RecordBatch
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, BooleanArray, Date32Array, Float64Array, Int64Array, ListBuilder, StringArray, StringBuilder,
+    TimestampMillisecondArray,
+};
+use arrow::datatypes::{DataType, Field, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::processor::Processor;
+use crate::processor::schema::{self, Column, Kind, Schema};
+use crate::response::Response;
+
+mod parquet;
+
+pub use self::parquet::{ParquetWriter, Partition};
+
+/// Converts JSON response to an Arrow [`RecordBatch`] with the columns of a [`Schema`]
+///
+/// Timestamps are `Timestamp(Millisecond, "UTC")`, dates are `Date32` and string arrays are
+/// `List(Utf8)`.
+#[derive(Debug, Clone, Copy)]
+pub struct Arrow {
+    schema: &'static Schema,
+}
+
+impl Arrow {
+    /// Build record batches with the columns of `schema`
+    pub const fn new(schema: &'static Schema) -> Self {
+        Self { schema }
+    }
+
+    /// The schema record batches are built with
+    pub fn schema(&self) -> &'static Schema {
+        self.schema
+    }
+}
+
+impl Processor for Arrow {
+    type Output = RecordBatch;
+
+    fn process<R: Response>(&self, response: Result<R>) -> Result<RecordBatch> {
+        let resp = response?; // Propagate HTTP errors
+        if resp.status() != 200 {
+            return Err(Error::ApiError {
+                request_id: resp.request_id().to_owned(),
+                status: resp.status().to_owned(),
+                message: resp.body().to_owned(),
+            });
+        }
+
+        let json: Value = serde_json::from_str(resp.body())?;
+        self.schema.record_batch(&json)
+    }
+}
+
+impl Kind {
+    /// The Arrow data type of columns of this kind
+    pub fn arrow_type(&self) -> DataType {
+        match self {
+            Kind::Boolean => DataType::Boolean,
+            Kind::Int => DataType::Int64,
+            Kind::Float => DataType::Float64,
+            Kind::String => DataType::Utf8,
+            Kind::Strings => DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            Kind::Date => DataType::Date32,
+            Kind::Millis | Kind::Rfc3339 => DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+        }
+    }
+}
+
+impl Schema {
+    /// The Arrow schema of record batches built from this schema
+    pub fn arrow(&self) -> arrow::datatypes::Schema {
+        let fields: Vec<Field> = self
+            .columns
+            .iter()
+            .map(|c| Field::new(c.name, c.kind.arrow_type(), true))
+            .collect();
+        arrow::datatypes::Schema::new(fields)
+    }
+
+    /// Build a record batch from a JSON response body
+    pub fn record_batch(&self, json: &Value) -> Result<RecordBatch> {
+        let rows = self.rows(json);
+        let columns = self.columns.iter().map(|c| array(c, &rows)).collect();
+        RecordBatch::try_new(Arc::new(self.arrow()), columns).map_err(|e| Error::Custom(format!("Arrow error: {e}")))
+    }
+}
+
+fn array(column: &Column, rows: &[&Value]) -> ArrayRef {
+    let values = column.values(rows);
+    match column.kind {
+        Kind::Boolean => Arc::new(values.map(|v| v?.as_bool()).collect::<BooleanArray>()),
+        Kind::Int => Arc::new(values.map(|v| schema::read_int(v?)).collect::<Int64Array>()),
+        Kind::Float => Arc::new(values.map(|v| v?.as_f64()).collect::<Float64Array>()),
+        Kind::String => Arc::new(values.map(|v| v?.as_str()).collect::<StringArray>()),
+        Kind::Strings => {
+            let mut builder = ListBuilder::new(StringBuilder::new());
+            for value in values {
+                match value.and_then(Value::as_array) {
+                    Some(items) => {
+                        for item in items {
+                            builder.values().append_option(item.as_str());
+                        }
+                        builder.append(true);
+                    }
+                    None => builder.append(false),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        Kind::Date => Arc::new(values.map(|v| schema::read_days(v?)).collect::<Date32Array>()),
+        Kind::Millis => Arc::new(
+            values
+                .map(|v| schema::read_int(v?))
+                .collect::<TimestampMillisecondArray>()
+                .with_timezone("UTC"),
+        ),
+        Kind::Rfc3339 => Arc::new(
+            values
+                .map(|v| schema::read_rfc3339(v?))
+                .collect::<TimestampMillisecondArray>()
+                .with_timezone("UTC"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::{Float64Type, TimestampMillisecondType};
+
+    use super::*;
+    use crate::cache::Entry;
+
+    pub(super) fn process(schema: &'static Schema, body: &str) -> RecordBatch {
+        let response = Entry {
+            status: 200,
+            body: body.to_string(),
+            request_id: None,
+            headers: Vec::new(),
+            expires: None,
+            latency: None,
+        };
+        Arrow::new(schema).process(Ok(response)).unwrap()
+    }
+
+    #[test]
+    fn test_record_batch() {
+        let batch = process(
+            &schema::AGGREGATES,
+            r#"{"results": [{"t": 1704205800000, "o": 187.15}, {"t": 1704292200000}]}"#,
+        );
+        assert_eq!(*batch.schema(), schema::AGGREGATES.arrow());
+        assert_eq!(batch.num_rows(), 2);
+        let open = batch.column_by_name("open").unwrap().as_primitive::<Float64Type>();
+        assert_eq!(open.value(0), 187.15);
+        assert!(open.is_null(1));
+        let timestamp = batch.column_by_name("timestamp").unwrap();
+        assert_eq!(
+            timestamp.as_primitive::<TimestampMillisecondType>().value(1),
+            1704292200000
+        );
+
+        let news = process(&schema::NEWS, r#"{"results": [{"tickers": ["AAPL", "MSFT"]}, {}]}"#);
+        let tickers = news.column_by_name("tickers").unwrap().as_list::<i32>();
+        assert_eq!(tickers.value(0).len(), 2);
+        assert!(tickers.is_null(1));
+
+        let empty = process(&schema::NEWS, r#"{"status": "OK"}"#);
+        assert_eq!(empty.num_rows(), 0);
+        assert_eq!(empty.num_columns(), schema::NEWS.columns.len());
+    }
+}
@@ -0,0 +1,302 @@
+//! Partitioned Parquet output
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::PathBuf;
+
+use arrow::array::{Array, AsArray, UInt32Array};
+use arrow::compute::{cast, take_record_batch};
+use arrow::datatypes::{DataType, TimeUnit, TimestampMillisecondType};
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+use crate::error::{Error, Result};
+
+/// Directory name used for null partition values, as in Hive
+const NULL_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// A level of the directory layout written by [`ParquetWriter`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Partition {
+    /// `{column}={value}` from the value of a column (e.g. `ticker=AAPL`)
+    Column(String),
+    /// `date={YYYY-MM-DD}` from a `Date32` or timestamp column (e.g. the aggregates' `timestamp`)
+    ///
+    /// Timestamps are partitioned by their America/New_York date, so a trading day's pre-market,
+    /// regular and after-hours bars land in the same directory.
+    Date(String),
+}
+
+/// Writes record batches to Parquet files in a Hive-style partitioned directory tree
+///
+/// Each [`write`](Self::write) splits the batch by the partitions and writes the rows of each
+/// partition directory to a new file, `part-{n}.parquet` for the `n`th write, so paginated results
+/// can be streamed to disk one page at a time without keeping any file open between writes.
+///
+/// # Example
+///
+/// ```no_run
+/// use polygon::Polygon;
+/// use polygon::processor::{ParquetWriter, Partition};
+/// use polygon::request::common::Timespan;
+/// use polygon::rest;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Polygon::default().with_key("your_api_key");
+/// let mut writer = ParquetWriter::new("lake/aggs").partition(Partition::Date("timestamp".into()));
+///
+/// for ticker in ["AAPL", "MSFT"] {
+///     let batch = rest::arrow::aggs::aggregates(&client, ticker, 1, Timespan::Minute, "2024-01-02", "2024-01-05")
+///         .limit(50000)
+///         .get()
+///         .await?;
+///     // lake/aggs/ticker=AAPL/date=2024-01-02/part-0.parquet, ...
+///     writer.write_with(&batch, &[("ticker", ticker)])?;
+/// }
+/// let files = writer.close()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ParquetWriter {
+    root: PathBuf,
+    partitions: Vec<Partition>,
+    file_prefix: String,
+    properties: WriterProperties,
+    writes: usize,
+    files: Vec<PathBuf>,
+}
+
+impl ParquetWriter {
+    /// Write under `root`, with no partitions and Snappy compression
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            partitions: Vec::new(),
+            file_prefix: "part".to_string(),
+            properties: WriterProperties::builder().set_compression(Compression::SNAPPY).build(),
+            writes: 0,
+            files: Vec::new(),
+        }
+    }
+
+    /// Add a partition level below the existing ones
+    pub fn partition(mut self, partition: Partition) -> Self {
+        self.partitions.push(partition);
+        self
+    }
+
+    /// Set the prefix of the files written in each partition directory (default `part`, giving
+    /// `part-0.parquet`, `part-1.parquet`, ...)
+    ///
+    /// Existing files with the same name are replaced.
+    pub fn file_prefix(mut self, file_prefix: impl Into<String>) -> Self {
+        self.file_prefix = file_prefix.into();
+        self
+    }
+
+    /// Set the Parquet writer properties (compression, row group size, ...)
+    pub fn properties(mut self, properties: WriterProperties) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Write the rows of `batch` to a new file in each of their partitions
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.write_with(batch, &[])
+    }
+
+    /// Write the rows of `batch`, under extra `key=value` directories placed before the partitions
+    ///
+    /// Use this for values that are not columns of the batch, e.g. the ticker of an aggregates request.
+    pub fn write_with(&mut self, batch: &RecordBatch, values: &[(&str, &str)]) -> Result<()> {
+        let mut prefix = self.root.clone();
+        for (key, value) in values {
+            prefix.push(segment(key, Some(value)));
+        }
+
+        let mut groups: BTreeMap<PathBuf, Vec<u32>> = BTreeMap::new();
+        let levels = self
+            .partitions
+            .iter()
+            .map(|p| Level::new(p, batch))
+            .collect::<Result<Vec<_>>>()?;
+        for row in 0..batch.num_rows() {
+            let mut dir = prefix.clone();
+            for level in &levels {
+                dir.push(level.segment(row));
+            }
+            groups.entry(dir).or_default().push(row as u32);
+        }
+
+        let io_error = |e: std::io::Error| Error::Custom(format!("Failed to write parquet file: {e}"));
+        let file_name = format!("{}-{}.parquet", self.file_prefix, self.writes);
+        self.writes += 1;
+        for (dir, rows) in groups {
+            let rows = take_record_batch(batch, &UInt32Array::from(rows)).map_err(arrow_error)?;
+            std::fs::create_dir_all(&dir).map_err(io_error)?;
+            let path = dir.join(&file_name);
+            let file = File::create(&path).map_err(io_error)?;
+            let mut writer =
+                ArrowWriter::try_new(file, batch.schema(), Some(self.properties.clone())).map_err(parquet_error)?;
+            writer.write(&rows).map_err(parquet_error)?;
+            writer.close().map_err(parquet_error)?;
+            self.files.push(path);
+        }
+        Ok(())
+    }
+
+    /// The paths of the files written so far, in order
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Return the paths of every file written
+    ///
+    /// Files are complete as soon as the [`write`](Self::write) creating them returns, so this
+    /// only hands back their paths.
+    pub fn close(self) -> Result<Vec<PathBuf>> {
+        Ok(self.files)
+    }
+}
+
+impl std::fmt::Debug for ParquetWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParquetWriter")
+            .field("root", &self.root)
+            .field("partitions", &self.partitions)
+            .field("file_prefix", &self.file_prefix)
+            .field("files", &self.files.len())
+            .finish()
+    }
+}
+
+/// The partition values of one batch
+struct Level {
+    key: String,
+    values: Vec<Option<String>>,
+}
+
+impl Level {
+    fn new(partition: &Partition, batch: &RecordBatch) -> Result<Self> {
+        let (key, column, to_date) = match partition {
+            Partition::Column(column) => (column.as_str(), column, false),
+            Partition::Date(column) => ("date", column, true),
+        };
+        let array = batch
+            .column_by_name(column)
+            .ok_or_else(|| Error::Custom(format!("No partition column {column}")))?;
+        let array = match array.data_type() {
+            DataType::Timestamp(_, tz) if to_date => {
+                return Ok(Level {
+                    key: key.to_string(),
+                    values: new_york_dates(array.as_ref(), tz.clone())?,
+                });
+            }
+            _ if to_date => cast(array, &DataType::Date32).map_err(arrow_error)?,
+            _ => array.clone(),
+        };
+        let formatter = ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default()).map_err(arrow_error)?;
+        let values = (0..array.len())
+            .map(|i| array.is_valid(i).then(|| formatter.value(i).to_string()))
+            .collect();
+        Ok(Level {
+            key: key.to_string(),
+            values,
+        })
+    }
+
+    fn segment(&self, row: usize) -> String {
+        segment(&self.key, self.values[row].as_deref())
+    }
+}
+
+/// America/New_York dates of a timestamp column
+fn new_york_dates(array: &dyn Array, tz: Option<std::sync::Arc<str>>) -> Result<Vec<Option<String>>> {
+    // Keeping the time zone leaves the values as UTC instants, only the unit changes
+    let millis = cast(array, &DataType::Timestamp(TimeUnit::Millisecond, tz)).map_err(arrow_error)?;
+    Ok(millis
+        .as_primitive::<TimestampMillisecondType>()
+        .iter()
+        .map(|ms| {
+            let dt = crate::time::from_millis(ms?)?;
+            Some(crate::time::to_new_york(&dt).date_naive().to_string())
+        })
+        .collect())
+}
+
+/// `key=value` with path separators escaped
+fn segment(key: &str, value: Option<&str>) -> String {
+    let value = value.map_or(NULL_PARTITION.to_string(), |v| {
+        v.replace('%', "%25").replace('/', "%2F")
+    });
+    format!("{key}={value}")
+}
+
+fn arrow_error(e: arrow::error::ArrowError) -> Error {
+    Error::Custom(format!("Arrow error: {e}"))
+}
+
+fn parquet_error(e: parquet::errors::ParquetError) -> Error {
+    Error::Custom(format!("Parquet error: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+    use crate::processor::arrow::tests::process;
+    use crate::processor::schema;
+
+    #[test]
+    fn test_partitioned_write() {
+        let dir = std::env::temp_dir().join(format!("polygon-parquet-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut writer = ParquetWriter::new(&dir).partition(Partition::Date("timestamp".into()));
+        // 2024-01-02 14:30 UTC, 2024-01-02 14:31 UTC, then 2024-01-03 02:00 UTC (still 2024-01-02 in
+        // New York) and 2024-01-03 14:30 UTC
+        let page = |t: &[i64]| {
+            let results: Vec<String> = t.iter().map(|t| format!(r#"{{"t": {t}, "c": 1.0}}"#)).collect();
+            process(
+                &schema::AGGREGATES,
+                &format!(r#"{{"results": [{}]}}"#, results.join(",")),
+            )
+        };
+        writer
+            .write_with(&page(&[1704205800000, 1704205860000]), &[("ticker", "AAPL")])
+            .unwrap();
+        writer
+            .write_with(&page(&[1704247200000, 1704292200000]), &[("ticker", "AAPL")])
+            .unwrap();
+        writer
+            .write_with(&page(&[1704205800000]), &[("ticker", "BRK/B")])
+            .unwrap();
+        let files = writer.close().unwrap();
+
+        let relative: Vec<_> = files.iter().map(|f| f.strip_prefix(&dir).unwrap().to_owned()).collect();
+        assert_eq!(
+            relative,
+            [
+                "ticker=AAPL/date=2024-01-02/part-0.parquet",
+                "ticker=AAPL/date=2024-01-02/part-1.parquet",
+                "ticker=AAPL/date=2024-01-03/part-1.parquet",
+                "ticker=BRK%2FB/date=2024-01-02/part-2.parquet",
+            ]
+            .map(PathBuf::from)
+        );
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&files[0]).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 2);
+        assert_eq!(*batches[0].schema(), schema::AGGREGATES.arrow());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
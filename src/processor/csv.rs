@@ -0,0 +1,118 @@
+//! CSV processor. Converts JSON response to CSV text
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::processor::Processor;
+use crate::processor::flat;
+use crate::response::Response;
+
+/// Converts JSON response to CSV, one row per result with a header line
+///
+/// Columns are the fields of all results sorted by name, with nested objects flattened into
+/// dotted names (e.g. `address.city`, `branding.logo_url`). Missing fields and
+/// nulls are empty, and arrays are written as JSON (e.g. `["AAPL","MSFT"]`). A response without
+/// results gives an empty string.
+#[derive(Debug, Clone, Copy)]
+pub struct Csv {
+    rows: &'static str,
+}
+
+impl Csv {
+    /// Write one row per result at `rows`, a dot-separated path (e.g. `results.events`), or empty
+    /// when the response itself is the only row
+    pub const fn new(rows: &'static str) -> Self {
+        Self { rows }
+    }
+}
+
+impl Default for Csv {
+    /// Rows from `results`
+    fn default() -> Self {
+        Self::new("results")
+    }
+}
+
+impl Processor for Csv {
+    type Output = String;
+
+    fn process<R: Response>(&self, response: Result<R>) -> Result<String> {
+        let rows = flat::rows(response, self.rows)?;
+
+        let columns: BTreeSet<&str> = rows.iter().flatten().map(|(column, _)| column.as_str()).collect();
+        if columns.is_empty() {
+            return Ok(String::new());
+        }
+
+        let mut out = String::new();
+        write_record(&mut out, columns.iter().map(|c| c.to_string()));
+        for row in &rows {
+            let fields = columns
+                .iter()
+                .map(|column| match row.iter().find(|(c, _)| c == column).map(|(_, v)| v) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(s)) => s.clone(),
+                    Some(value) => value.to_string(),
+                });
+            write_record(&mut out, fields);
+        }
+        Ok(out)
+    }
+}
+
+/// Append a CSV record, quoting fields as in RFC 4180
+fn write_record(out: &mut String, fields: impl Iterator<Item = String>) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(&field);
+        }
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Entry;
+
+    fn process(body: &str) -> String {
+        let response = Entry {
+            status: 200,
+            body: body.to_string(),
+            request_id: None,
+            headers: Vec::new(),
+            expires: None,
+            latency: None,
+        };
+        Csv::default().process(Ok(response)).unwrap()
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = process(
+            r#"{"results": [
+                {"ticker": "AAPL", "name": "Apple Inc.", "address": {"city": "Cupertino"}, "active": true},
+                {"ticker": "BRK.B", "name": "Berkshire \"B\", Class B", "branding": {"logo_url": "https://x/y.svg"}}
+            ]}"#,
+        );
+        assert_eq!(
+            csv,
+            "active,address.city,branding.logo_url,name,ticker\n\
+             true,Cupertino,,Apple Inc.,AAPL\n\
+             ,,https://x/y.svg,\"Berkshire \"\"B\"\", Class B\",BRK.B\n"
+        );
+
+        let details = process(r#"{"results": {"ticker": "AAPL", "tags": ["a", "b"]}}"#);
+        assert_eq!(details, "tags,ticker\n\"[\"\"a\"\",\"\"b\"\"]\",AAPL\n");
+
+        assert_eq!(process(r#"{"status": "OK", "resultsCount": 0}"#), "");
+    }
+}
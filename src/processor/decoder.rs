@@ -20,6 +20,14 @@ impl<T> Decoder<T> {
     }
 }
 
+impl<T> Clone for Decoder<T> {
+    fn clone(&self) -> Self {
+        Self {
+            decoder_fn: Arc::clone(&self.decoder_fn),
+        }
+    }
+}
+
 impl<T> Processor for Decoder<T> {
     type Output = T;
 
@@ -0,0 +1,107 @@
+//! Envelope processor. Wraps another processor's output with the response metadata
+use serde::Deserialize;
+
+use crate::error::Result;
+use crate::processor::Processor;
+use crate::response::Response;
+use crate::response::envelope::Envelope;
+
+/// Wraps the output of another processor in an [`Envelope`] with the response's status, request
+/// ID, counts, `adjusted` flag and `next_url`
+///
+/// The body is always buffered, even when the wrapped processor can stream it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Enveloped<P> {
+    inner: P,
+}
+
+impl<P: Processor> Enveloped<P> {
+    /// Wrap the output of `inner`
+    pub const fn new(inner: P) -> Self {
+        Self { inner }
+    }
+
+    /// The wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+}
+
+/// Envelope fields of a response body, skipping the results
+#[derive(Default, Deserialize)]
+struct Meta {
+    status: Option<String>,
+    request_id: Option<String>,
+    count: Option<i64>,
+    #[serde(rename = "queryCount")]
+    query_count: Option<i64>,
+    #[serde(rename = "resultsCount")]
+    results_count: Option<i64>,
+    adjusted: Option<bool>,
+    next_url: Option<String>,
+}
+
+impl<P: Processor> Processor for Enveloped<P> {
+    type Output = Envelope<P::Output>;
+
+    fn process<R: Response>(&self, response: Result<R>) -> Result<Envelope<P::Output>> {
+        let resp = response?; // Propagate HTTP errors
+        // A body that isn't a JSON object is left to the inner processor to reject
+        let meta: Meta = match resp.status() {
+            200 => serde_json::from_str(resp.body()).unwrap_or_default(),
+            _ => Meta::default(),
+        };
+        let request_id = meta.request_id.or_else(|| resp.request_id().clone());
+
+        Ok(Envelope {
+            results: self.inner.process(Ok(resp))?,
+            status: meta.status,
+            request_id,
+            count: meta.count,
+            query_count: meta.query_count,
+            results_count: meta.results_count,
+            adjusted: meta.adjusted,
+            next_url: meta.next_url,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Entry;
+    use crate::processor::{Raw, Typed};
+    use crate::response::aggs::Agg;
+
+    fn entry(status: u16, body: &str) -> Entry {
+        Entry {
+            status,
+            body: body.to_string(),
+            request_id: Some("header-id".to_string()),
+            headers: Vec::new(),
+            expires: None,
+            latency: None,
+        }
+    }
+
+    #[test]
+    fn test_envelope() {
+        let body = &crate::fixture("aggs/aggregates");
+        let envelope = Enveloped::new(Typed::<Vec<Agg>>::new())
+            .process(Ok(entry(200, body)))
+            .unwrap();
+        assert_eq!(envelope.results.len(), 2);
+        assert_eq!(envelope.results_count, Some(2));
+        assert!(envelope.adjusted.is_some());
+        assert!(!envelope.is_delayed());
+
+        let body = r#"{"status": "DELAYED", "count": 1, "next_url": "https://api.polygon.io/v3/reference/tickers?cursor=abc", "results": []}"#;
+        let envelope = Enveloped::new(Raw).process(Ok(entry(200, body))).unwrap();
+        assert!(envelope.is_delayed());
+        assert!(envelope.has_next());
+        assert_eq!(envelope.request_id.as_deref(), Some("header-id"));
+        assert_eq!(envelope.results, body);
+
+        assert!(Enveloped::new(Raw).process(Ok(entry(404, "not found"))).is_err());
+    }
+}
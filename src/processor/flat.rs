@@ -0,0 +1,64 @@
+//! Flattening of JSON results into rows of dotted columns, shared by the text export processors
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+use crate::response::Response;
+
+/// A result flattened into `(column, value)` pairs, where no value is an object
+pub(crate) type Row = Vec<(String, Value)>;
+
+/// Check the status and flatten the rows of a response found at `path`
+///
+/// `path` is dot-separated (e.g. `results` or `results.events`), or empty when the response itself
+/// is the row. An array gives one row per element and an object a single row. A response without
+/// anything at `path` (e.g. an empty page) has no rows.
+pub(crate) fn rows<R: Response>(response: Result<R>, path: &str) -> Result<Vec<Row>> {
+    let resp = response?; // Propagate HTTP errors
+    if resp.status() != 200 {
+        return Err(Error::ApiError {
+            request_id: resp.request_id().to_owned(),
+            status: resp.status().to_owned(),
+            message: resp.body().to_owned(),
+        });
+    }
+
+    let json: Value = serde_json::from_str(resp.body())?;
+    let found = if path.is_empty() {
+        Some(&json)
+    } else {
+        path.split('.').try_fold(&json, |v, key| v.get(key))
+    };
+    let rows = match found {
+        Some(Value::Array(results)) => results.iter().map(flatten).collect(),
+        Some(Value::Null) | None => Vec::new(),
+        Some(result) => vec![flatten(result)],
+    };
+    Ok(rows)
+}
+
+/// Flatten nested objects into dotted keys (e.g. `address.city`), keeping arrays as they are
+///
+/// Columns are sorted by name, so the output doesn't depend on serde_json's `preserve_order`.
+pub(crate) fn flatten(value: &Value) -> Row {
+    let mut row = Vec::new();
+    match value {
+        Value::Object(map) => flatten_into(&mut row, "", map),
+        value => row.push(("value".to_string(), value.clone())),
+    }
+    row.sort_by(|(a, _), (b, _)| a.cmp(b));
+    row
+}
+
+fn flatten_into(row: &mut Row, prefix: &str, map: &Map<String, Value>) {
+    for (key, value) in map {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            Value::Object(map) => flatten_into(row, &key, map),
+            value => row.push((key, value.clone())),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! NDJSON processor. Converts JSON response to newline-delimited JSON
+use serde_json::{Map, Value};
+
+use crate::error::Result;
+use crate::processor::Processor;
+use crate::processor::flat;
+use crate::response::Response;
+
+/// Converts JSON response to newline-delimited JSON, one flattened object per result
+///
+/// Nested objects are flattened into dotted keys (e.g. `address.city`), so every line has the
+/// same shape as a [`Csv`](super::Csv) row. Each line ends with `\n`.
+#[derive(Debug, Clone, Copy)]
+pub struct NdJson {
+    rows: &'static str,
+}
+
+impl NdJson {
+    /// Write one line per result at `rows`, a dot-separated path (e.g. `results.events`), or empty
+    /// when the response itself is the only result
+    pub const fn new(rows: &'static str) -> Self {
+        Self { rows }
+    }
+}
+
+impl Default for NdJson {
+    /// Results from `results`
+    fn default() -> Self {
+        Self::new("results")
+    }
+}
+
+impl Processor for NdJson {
+    type Output = String;
+
+    fn process<R: Response>(&self, response: Result<R>) -> Result<String> {
+        let mut out = String::new();
+        for row in flat::rows(response, self.rows)? {
+            let object: Map<String, Value> = row.into_iter().collect();
+            out.push_str(&serde_json::to_string(&object)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Entry;
+
+    #[test]
+    fn test_ndjson() {
+        let response = Entry {
+            status: 200,
+            body: r#"{"results": [{"ticker": "AAPL", "address": {"city": "Cupertino"}}, {"ticker": "MSFT"}]}"#
+                .to_string(),
+            request_id: None,
+            headers: Vec::new(),
+            expires: None,
+            latency: None,
+        };
+        let ndjson = NdJson::default().process(Ok(response)).unwrap();
+        assert_eq!(
+            ndjson,
+            "{\"address.city\":\"Cupertino\",\"ticker\":\"AAPL\"}\n{\"ticker\":\"MSFT\"}\n"
+        );
+    }
+}
@@ -0,0 +1,454 @@
+//! Column schemas for the tables returned by each endpoint
+//!
+//! A [`Schema`] lists the output columns, the JSON field each one is read from and its type, so a
+//! Polars DataFrame or Arrow RecordBatch has the same columns and types whatever the response
+//! contains, including when it has no results. Fields missing from a row are null.
+use chrono::DateTime;
+use serde_json::Value;
+
+/// Columns of a table and where to find them in the JSON response
+#[derive(Debug, Clone, Copy)]
+pub struct Schema {
+    /// Dot-separated path to the rows (e.g. `results`), or empty when the response itself is the row
+    ///
+    /// An object at this path is read as a single row.
+    pub rows: &'static str,
+    /// Output columns, in order
+    pub columns: &'static [Column],
+}
+
+/// A column of a [`Schema`]
+#[derive(Debug, Clone, Copy)]
+pub struct Column {
+    /// Column name in the output
+    pub name: &'static str,
+    /// Dot-separated path to the value within a row (e.g. `o` or `publisher.name`)
+    pub field: &'static str,
+    /// How the value is read
+    pub kind: Kind,
+}
+
+/// How a JSON value is read into a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Boolean
+    Boolean,
+    /// 64-bit integer, accepting integral floats
+    Int,
+    /// 64-bit float
+    Float,
+    /// String
+    String,
+    /// List of strings from an array of strings
+    Strings,
+    /// Date from a `YYYY-MM-DD` string
+    Date,
+    /// UTC millisecond timestamp from a Unix millisecond timestamp
+    Millis,
+    /// UTC millisecond timestamp from an RFC 3339 string
+    Rfc3339,
+}
+
+impl Schema {
+    /// The rows of a JSON response body
+    pub(crate) fn rows<'v>(&self, json: &'v Value) -> Vec<&'v Value> {
+        match lookup(json, self.rows) {
+            Some(Value::Array(rows)) => rows.iter().collect(),
+            Some(Value::Null) | None => Vec::new(),
+            Some(row) => vec![row],
+        }
+    }
+}
+
+impl Column {
+    /// Read column `name` from `field`
+    pub const fn new(name: &'static str, field: &'static str, kind: Kind) -> Self {
+        Self { name, field, kind }
+    }
+
+    /// The values of this column in `rows`, `None` where the field is missing
+    pub(crate) fn values<'v>(&self, rows: &[&'v Value]) -> impl Iterator<Item = Option<&'v Value>> {
+        rows.iter().map(|row| lookup(row, self.field))
+    }
+}
+
+/// Aggregate bars (`/v2/aggs/ticker/{ticker}/range/...`)
+pub const AGGREGATES: Schema = Schema {
+    rows: "results",
+    columns: &[
+        Column::new("timestamp", "t", Kind::Millis),
+        Column::new("open", "o", Kind::Float),
+        Column::new("high", "h", Kind::Float),
+        Column::new("low", "l", Kind::Float),
+        Column::new("close", "c", Kind::Float),
+        Column::new("volume", "v", Kind::Float),
+        Column::new("vwap", "vw", Kind::Float),
+        Column::new("transactions", "n", Kind::Int),
+        boolean("otc"),
+    ],
+};
+
+/// Daily open/close (`/v1/open-close/{ticker}/{date}`), a single row read from the response itself
+pub const DAILY_OPEN_CLOSE: Schema = Schema {
+    rows: "",
+    columns: &[
+        date("from"),
+        string("symbol"),
+        float("open"),
+        float("high"),
+        float("low"),
+        float("close"),
+        float("volume"),
+        Column::new("after_hours", "afterHours", Kind::Float),
+        Column::new("pre_market", "preMarket", Kind::Float),
+    ],
+};
+
+/// Previous close bars (`/v2/aggs/ticker/{ticker}/prev`)
+pub const PREVIOUS_CLOSE: Schema = Schema {
+    rows: "results",
+    columns: &[
+        Column::new("ticker", "T", Kind::String),
+        Column::new("timestamp", "t", Kind::Millis),
+        Column::new("open", "o", Kind::Float),
+        Column::new("high", "h", Kind::Float),
+        Column::new("low", "l", Kind::Float),
+        Column::new("close", "c", Kind::Float),
+        Column::new("volume", "v", Kind::Float),
+        Column::new("vwap", "vw", Kind::Float),
+    ],
+};
+
+/// Grouped daily bars (`/v2/aggs/grouped/...`)
+pub const GROUPED_DAILY: Schema = Schema {
+    rows: "results",
+    columns: &[
+        Column::new("ticker", "T", Kind::String),
+        Column::new("timestamp", "t", Kind::Millis),
+        Column::new("open", "o", Kind::Float),
+        Column::new("high", "h", Kind::Float),
+        Column::new("low", "l", Kind::Float),
+        Column::new("close", "c", Kind::Float),
+        Column::new("volume", "v", Kind::Float),
+        Column::new("vwap", "vw", Kind::Float),
+        Column::new("transactions", "n", Kind::Int),
+        boolean("otc"),
+    ],
+};
+
+/// Tickers (`/v3/reference/tickers`)
+pub const TICKERS: Schema = Schema {
+    rows: "results",
+    columns: &[
+        string("ticker"),
+        string("name"),
+        string("market"),
+        string("locale"),
+        string("primary_exchange"),
+        string("type"),
+        boolean("active"),
+        string("currency_name"),
+        string("currency_symbol"),
+        string("base_currency_symbol"),
+        string("base_currency_name"),
+        string("cik"),
+        string("composite_figi"),
+        string("share_class_figi"),
+        rfc3339("last_updated_utc"),
+        rfc3339("delisted_utc"),
+        string("source_feed"),
+    ],
+};
+
+/// Ticker details (`/v3/reference/tickers/{ticker}`), one row per response
+pub const TICKER_DETAILS: Schema = Schema {
+    rows: "results",
+    columns: &[
+        string("ticker"),
+        string("name"),
+        string("market"),
+        string("locale"),
+        string("primary_exchange"),
+        string("type"),
+        boolean("active"),
+        string("currency_name"),
+        string("currency_symbol"),
+        string("base_currency_symbol"),
+        string("base_currency_name"),
+        string("cik"),
+        string("composite_figi"),
+        string("share_class_figi"),
+        rfc3339("last_updated_utc"),
+        rfc3339("delisted_utc"),
+        string("source_feed"),
+        string("description"),
+        string("homepage_url"),
+        date("list_date"),
+        float("market_cap"),
+        string("phone_number"),
+        Column::new("address1", "address.address1", Kind::String),
+        Column::new("city", "address.city", Kind::String),
+        Column::new("state", "address.state", Kind::String),
+        Column::new("postal_code", "address.postal_code", Kind::String),
+        string("sic_code"),
+        string("sic_description"),
+        int("total_employees"),
+        float("share_class_shares_outstanding"),
+        float("weighted_shares_outstanding"),
+        int("round_lot"),
+        string("ticker_root"),
+    ],
+};
+
+/// Related tickers (`/v1/related-companies/{ticker}`)
+pub const RELATED: Schema = Schema {
+    rows: "results",
+    columns: &[string("ticker")],
+};
+
+/// Ticker types (`/v3/reference/tickers/types`)
+pub const TICKER_TYPES: Schema = Schema {
+    rows: "results",
+    columns: &[
+        string("code"),
+        string("description"),
+        string("asset_class"),
+        string("locale"),
+    ],
+};
+
+/// Ticker change events (`/vX/reference/tickers/{id}/events`), one row per event
+pub const TICKER_EVENTS: Schema = Schema {
+    rows: "results.events",
+    columns: &[
+        string("type"),
+        date("date"),
+        Column::new("ticker", "ticker_change.ticker", Kind::String),
+    ],
+};
+
+/// News articles (`/v2/reference/news`)
+pub const NEWS: Schema = Schema {
+    rows: "results",
+    columns: &[
+        string("id"),
+        rfc3339("published_utc"),
+        string("title"),
+        string("author"),
+        Column::new("publisher", "publisher.name", Kind::String),
+        string("article_url"),
+        string("amp_url"),
+        string("image_url"),
+        string("description"),
+        strings("tickers"),
+        strings("keywords"),
+    ],
+};
+
+/// Balance sheets
+pub const BALANCE_SHEETS: Schema = Schema {
+    rows: "results",
+    columns: &[
+        string("cik"),
+        strings("tickers"),
+        string("timeframe"),
+        int("fiscal_year"),
+        int("fiscal_quarter"),
+        date("period_end"),
+        date("filing_date"),
+        float("accounts_payable"),
+        float("accrued_and_other_current_liabilities"),
+        float("accumulated_other_comprehensive_income"),
+        float("additional_paid_in_capital"),
+        float("cash_and_equivalents"),
+        float("commitments_and_contingencies"),
+        float("common_stock"),
+        float("debt_current"),
+        float("deferred_revenue_current"),
+        float("goodwill"),
+        float("intangible_assets_net"),
+        float("inventories"),
+        float("long_term_debt_and_capital_lease_obligations"),
+        float("noncontrolling_interest"),
+        float("other_assets"),
+        float("other_current_assets"),
+        float("other_equity"),
+        float("other_noncurrent_liabilities"),
+        float("preferred_stock"),
+        float("property_plant_equipment_net"),
+        float("receivables"),
+        float("retained_earnings_deficit"),
+        float("short_term_investments"),
+        float("total_assets"),
+        float("total_current_assets"),
+        float("total_current_liabilities"),
+        float("total_equity"),
+        float("total_equity_attributable_to_parent"),
+        float("total_liabilities"),
+        float("total_liabilities_and_equity"),
+        float("treasury_stock"),
+    ],
+};
+
+/// Cash flow statements
+pub const CASH_FLOW_STATEMENTS: Schema = Schema {
+    rows: "results",
+    columns: &[
+        string("cik"),
+        strings("tickers"),
+        string("timeframe"),
+        int("fiscal_year"),
+        int("fiscal_quarter"),
+        date("period_end"),
+        date("filing_date"),
+        float("cash_from_operating_activities_continuing_operations"),
+        float("change_in_cash_and_equivalents"),
+        float("change_in_other_operating_assets_and_liabilities_net"),
+        float("depreciation_depletion_and_amortization"),
+        float("dividends"),
+        float("effect_of_currency_exchange_rate"),
+        float("income_loss_from_discontinued_operations"),
+        float("long_term_debt_issuances_repayments"),
+        float("net_cash_from_financing_activities"),
+        float("net_cash_from_financing_activities_continuing_operations"),
+        float("net_cash_from_financing_activities_discontinued_operations"),
+        float("net_cash_from_investing_activities"),
+        float("net_cash_from_investing_activities_continuing_operations"),
+        float("net_cash_from_investing_activities_discontinued_operations"),
+        float("net_cash_from_operating_activities"),
+        float("net_cash_from_operating_activities_discontinued_operations"),
+        float("net_income"),
+        float("noncontrolling_interests"),
+        float("other_cash_adjustments"),
+        float("other_financing_activities"),
+        float("other_investing_activities"),
+        float("other_operating_activities"),
+        float("purchase_of_property_plant_and_equipment"),
+        float("sale_of_property_plant_and_equipment"),
+        float("short_term_debt_issuances_repayments"),
+    ],
+};
+
+/// Income statements
+pub const INCOME_STATEMENTS: Schema = Schema {
+    rows: "results",
+    columns: &[
+        string("cik"),
+        strings("tickers"),
+        string("timeframe"),
+        int("fiscal_year"),
+        int("fiscal_quarter"),
+        date("period_end"),
+        date("filing_date"),
+        float("basic_earnings_per_share"),
+        float("basic_shares_outstanding"),
+        float("consolidated_net_income_loss"),
+        float("cost_of_revenue"),
+        float("depreciation_depletion_amortization"),
+        float("diluted_earnings_per_share"),
+        float("diluted_shares_outstanding"),
+        float("discontinued_operations"),
+        float("ebitda"),
+        float("equity_in_affiliates"),
+        float("extraordinary_items"),
+        float("gross_profit"),
+        float("income_before_income_taxes"),
+        float("income_taxes"),
+        float("interest_expense"),
+        float("interest_income"),
+        float("net_income_loss_attributable_common_shareholders"),
+        float("noncontrolling_interest"),
+        float("operating_income"),
+        float("other_income_expense"),
+        float("other_operating_expenses"),
+        float("preferred_stock_dividends_declared"),
+        float("research_development"),
+        float("revenue"),
+        float("selling_general_administrative"),
+        float("total_operating_expenses"),
+        float("total_other_income_expense"),
+    ],
+};
+
+/// Financial ratios
+pub const RATIOS: Schema = Schema {
+    rows: "results",
+    columns: &[
+        string("ticker"),
+        string("cik"),
+        date("date"),
+        float("average_volume"),
+        float("cash"),
+        float("current"),
+        float("debt_to_equity"),
+        float("dividend_yield"),
+        float("earnings_per_share"),
+        float("enterprise_value"),
+        float("ev_to_ebitda"),
+        float("ev_to_sales"),
+        float("free_cash_flow"),
+        float("market_cap"),
+        float("price"),
+        float("price_to_book"),
+        float("price_to_cash_flow"),
+        float("price_to_earnings"),
+        float("price_to_free_cash_flow"),
+        float("price_to_sales"),
+        float("quick"),
+        float("return_on_assets"),
+        float("return_on_equity"),
+    ],
+};
+
+const fn boolean(name: &'static str) -> Column {
+    Column::new(name, name, Kind::Boolean)
+}
+
+const fn int(name: &'static str) -> Column {
+    Column::new(name, name, Kind::Int)
+}
+
+const fn float(name: &'static str) -> Column {
+    Column::new(name, name, Kind::Float)
+}
+
+const fn string(name: &'static str) -> Column {
+    Column::new(name, name, Kind::String)
+}
+
+const fn strings(name: &'static str) -> Column {
+    Column::new(name, name, Kind::Strings)
+}
+
+const fn date(name: &'static str) -> Column {
+    Column::new(name, name, Kind::Date)
+}
+
+const fn rfc3339(name: &'static str) -> Column {
+    Column::new(name, name, Kind::Rfc3339)
+}
+
+fn lookup<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    if path.is_empty() {
+        return Some(value);
+    }
+    path.split('.').try_fold(value, |v, key| v.get(key))
+}
+
+/// Read a [`Kind::Int`] or [`Kind::Millis`] value
+pub(crate) fn read_int(value: &Value) -> Option<i64> {
+    value
+        .as_i64()
+        .or_else(|| value.as_f64().filter(|f| f.fract() == 0.0).map(|f| f as i64))
+}
+
+/// Read a [`Kind::Date`] value as days since the Unix epoch
+pub(crate) fn read_days(value: &Value) -> Option<i32> {
+    let date = crate::time::parse_date(value.as_str()?)?;
+    i32::try_from(date.signed_duration_since(DateTime::UNIX_EPOCH.date_naive()).num_days()).ok()
+}
+
+/// Read a [`Kind::Rfc3339`] value as Unix milliseconds
+pub(crate) fn read_rfc3339(value: &Value) -> Option<i64> {
+    crate::time::parse_datetime(value.as_str()?).map(|dt| dt.timestamp_millis())
+}
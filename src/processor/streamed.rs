@@ -0,0 +1,306 @@
+//! Streaming serde processor. Deserializes `results` elements as the body arrives
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, Result};
+use crate::processor::{BodySink, Processor};
+use crate::response::Response;
+
+/// Deserializes the `results` of a response into `Vec<T>` with serde, one element at a time
+///
+/// Unlike [`Decoder`](super::Decoder), the body is never parsed into a `serde_json::Value`: each
+/// element of `results` is deserialized straight into `T` as soon as its last byte is read. With a
+/// client that supports [`send_streaming`](crate::Request::send_streaming) (e.g. reqwest), the body
+/// isn't buffered either, so peak memory is little more than the output.
+///
+/// A `results` object gives a single element, and a missing or null `results` none.
+pub struct Streamed<T> {
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T> Streamed<T> {
+    /// Deserialize the `results` into `Vec<T>`
+    pub const fn new() -> Self {
+        Self { _output: PhantomData }
+    }
+}
+
+impl<T> Default for Streamed<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Streamed<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Streamed<T> {}
+
+impl<T> std::fmt::Debug for Streamed<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Streamed<{}>", std::any::type_name::<T>())
+    }
+}
+
+impl<T: DeserializeOwned + Send + 'static> Processor for Streamed<T> {
+    type Output = Vec<T>;
+
+    fn process<R: Response>(&self, response: Result<R>) -> Result<Vec<T>> {
+        let resp = response?; // Propagate HTTP errors
+        if resp.status() != 200 {
+            return Err(Error::ApiError {
+                request_id: resp.request_id().to_owned(),
+                status: resp.status().to_owned(),
+                message: resp.body().to_owned(),
+            });
+        }
+
+        let mut parser = ResultsParser::new();
+        parser.feed(resp.body().as_bytes())?;
+        parser.finish()
+    }
+
+    fn sink(&self) -> Option<Box<dyn BodySink<Vec<T>>>> {
+        Some(Box::new(ResultsParser::<T>::new()))
+    }
+}
+
+/// Where the parser is in the response body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Skipping the envelope around `results`
+    Envelope,
+    /// After `"results":`, before its value
+    Value,
+    /// Between the elements of the `results` array
+    Array,
+    /// Reading an element, complete when the depth is back to `end`
+    Element { end: usize, scalar: bool },
+}
+
+/// Incremental parser deserializing the elements of `results` from body chunks of any size
+///
+/// Only the element being read is buffered. The envelope around `results` is scanned for
+/// structure but not validated.
+pub struct ResultsParser<T> {
+    results: Vec<T>,
+    mode: Mode,
+    depth: usize,
+    string: bool,
+    escape: bool,
+    expect_key: bool,
+    reading_key: bool,
+    key: Vec<u8>,
+    element: Vec<u8>,
+}
+
+impl<T: DeserializeOwned> ResultsParser<T> {
+    /// Start parsing a response body
+    pub fn new() -> Self {
+        Self {
+            results: Vec::new(),
+            mode: Mode::Envelope,
+            depth: 0,
+            string: false,
+            escape: false,
+            expect_key: false,
+            reading_key: false,
+            key: Vec::new(),
+            element: Vec::new(),
+        }
+    }
+
+    /// Parse the next chunk of the body
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<()> {
+        chunk.iter().try_for_each(|&b| self.byte(b))
+    }
+
+    /// Finish the body and return the deserialized elements
+    pub fn finish(self) -> Result<Vec<T>> {
+        if self.string || self.depth != 0 || self.mode != Mode::Envelope {
+            return Err(Error::Custom("Decode error: truncated JSON body".to_string()));
+        }
+        Ok(self.results)
+    }
+
+    fn byte(&mut self, b: u8) -> Result<()> {
+        // String contents never change the structure
+        if self.string {
+            if self.escape {
+                self.escape = false;
+            } else if b == b'\\' {
+                self.escape = true;
+            } else if b == b'"' {
+                self.string = false;
+            }
+            if matches!(self.mode, Mode::Element { .. }) {
+                self.element.push(b);
+            } else if self.reading_key {
+                if self.string {
+                    self.key.push(b);
+                } else {
+                    self.reading_key = false;
+                }
+            }
+            return Ok(());
+        }
+
+        match self.mode {
+            Mode::Envelope => match b {
+                b'"' => {
+                    self.string = true;
+                    if self.depth == 1 && self.expect_key {
+                        self.reading_key = true;
+                        self.key.clear();
+                    }
+                }
+                b'{' | b'[' => {
+                    self.depth += 1;
+                    if self.depth == 1 {
+                        self.expect_key = b == b'{';
+                    }
+                }
+                b'}' | b']' => self.depth = self.close()?,
+                b',' if self.depth == 1 => self.expect_key = true,
+                b':' if self.depth == 1 => {
+                    self.expect_key = false;
+                    if self.key == b"results" {
+                        self.mode = Mode::Value;
+                    }
+                }
+                _ => {}
+            },
+            Mode::Value => match b {
+                b if b.is_ascii_whitespace() => {}
+                b'[' => {
+                    self.depth += 1;
+                    self.mode = Mode::Array;
+                }
+                b'{' => {
+                    self.mode = Mode::Element {
+                        end: self.depth,
+                        scalar: false,
+                    };
+                    return self.byte(b);
+                }
+                // null
+                _ => {
+                    self.mode = Mode::Envelope;
+                    return self.byte(b);
+                }
+            },
+            Mode::Array => match b {
+                b']' => {
+                    self.depth = self.close()?;
+                    self.mode = Mode::Envelope;
+                }
+                b',' => {}
+                b if b.is_ascii_whitespace() => {}
+                _ => {
+                    self.mode = Mode::Element {
+                        end: self.depth,
+                        scalar: b != b'{' && b != b'[',
+                    };
+                    return self.byte(b);
+                }
+            },
+            Mode::Element { end, scalar } => {
+                if scalar && (matches!(b, b',' | b']' | b'}') || b.is_ascii_whitespace()) {
+                    self.complete()?;
+                    return self.byte(b);
+                }
+                self.element.push(b);
+                match b {
+                    b'"' => self.string = true,
+                    b'{' | b'[' => self.depth += 1,
+                    b'}' | b']' => {
+                        self.depth = self.close()?;
+                        if self.depth == end {
+                            self.complete()?;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Depth after a closing bracket
+    fn close(&self) -> Result<usize> {
+        self.depth
+            .checked_sub(1)
+            .ok_or_else(|| Error::Custom("Decode error: unbalanced JSON body".to_string()))
+    }
+
+    /// Deserialize the buffered element
+    fn complete(&mut self) -> Result<()> {
+        self.results.push(serde_json::from_slice(&self.element)?);
+        self.element.clear();
+        // Elements at depth 1 are a `results` object, deeper ones are in the `results` array
+        self.mode = match self.mode {
+            Mode::Element { end: 1, .. } => Mode::Envelope,
+            _ => Mode::Array,
+        };
+        Ok(())
+    }
+}
+
+impl<T: DeserializeOwned> Default for ResultsParser<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: DeserializeOwned + Send> BodySink<Vec<T>> for ResultsParser<T> {
+    fn feed(&mut self, chunk: &[u8]) -> Result<()> {
+        ResultsParser::feed(self, chunk)
+    }
+
+    fn finish(self: Box<Self>) -> Result<Vec<T>> {
+        ResultsParser::finish(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::aggs::Agg;
+
+    fn parse<T: DeserializeOwned>(body: &str, chunk_size: usize) -> Result<Vec<T>> {
+        let mut parser = ResultsParser::new();
+        for chunk in body.as_bytes().chunks(chunk_size) {
+            parser.feed(chunk)?;
+        }
+        parser.finish()
+    }
+
+    #[test]
+    fn test_results_parser() {
+        let body = &crate::fixture("aggs/aggregates");
+        for chunk_size in [1, 7, body.len()] {
+            let aggs: Vec<Agg> = parse(body, chunk_size).unwrap();
+            assert_eq!(aggs.len(), 2);
+            assert_eq!(aggs[0].open, Some(130.465));
+            assert_eq!(aggs[1].timestamp, Some(1673326800000));
+        }
+
+        // Brackets and quotes in strings, a `results` key in an element, and a results object
+        let body = r#"{"status": "OK [\"]", "nested": {"results": 1}, "results": {"results": "}", "a": [1]}}"#;
+        let values: Vec<serde_json::Value> = parse(body, 3).unwrap();
+        assert_eq!(values, [serde_json::json!({"results": "}", "a": [1]})]);
+
+        let tickers: Vec<String> = parse(r#"{"results":["AAPL","MSFT"]}"#, 1).unwrap();
+        assert_eq!(tickers, ["AAPL", "MSFT"]);
+        assert!(
+            parse::<Agg>(r#"{"status": "OK", "results": null}"#, 4)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(parse::<Agg>(r#"{"results": [{"o": 1.0}"#, 4).is_err());
+    }
+}
@@ -3,12 +3,38 @@ use polars_core::prelude::*;
 use polars_io::prelude::*;
 use std::io::Cursor;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::processor::Processor;
+use crate::processor::schema::{self, Kind, Schema};
 use crate::response::Response;
 
 /// Converts JSON response to Polars DataFrame
-pub struct Table;
+///
+/// The endpoint builders' `as_dataframe()` use the endpoint's [`Schema`], so columns are named and
+/// typed the same way on every call (e.g. `t` becomes a UTC `timestamp` column) and an empty
+/// response gives an empty frame with all columns. [`Table::inferred`] instead lets Polars infer
+/// the columns from the `results` array.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Table {
+    schema: Option<&'static Schema>,
+}
+
+impl Table {
+    /// Build frames with the columns of `schema`
+    pub const fn new(schema: &'static Schema) -> Self {
+        Self { schema: Some(schema) }
+    }
+
+    /// Let Polars infer the columns from the `results` array
+    pub const fn inferred() -> Self {
+        Self { schema: None }
+    }
+
+    /// The schema frames are built with, if any
+    pub fn schema(&self) -> Option<&'static Schema> {
+        self.schema
+    }
+}
 
 #[cfg(feature = "table")]
 impl Processor for Table {
@@ -17,7 +43,7 @@ impl Processor for Table {
     fn process<R: Response>(&self, response: Result<R>) -> Result<DataFrame> {
         let resp = response?; // Propagate HTTP errors
         if resp.status() != 200 {
-            return Err(crate::error::Error::ApiError {
+            return Err(Error::ApiError {
                 request_id: resp.request_id().to_owned(),
                 status: resp.status().to_owned(),
                 message: resp.body().to_owned(),
@@ -26,14 +52,174 @@ impl Processor for Table {
 
         let json = resp.body();
         let json_value: serde_json::Value = serde_json::from_str(json)?;
+        if let Some(schema) = self.schema {
+            return schema.dataframe(&json_value);
+        }
+
         let results = json_value
             .get("results")
-            .ok_or_else(|| crate::error::Error::Custom("Missing 'results' field".into()))?;
+            .ok_or_else(|| Error::Custom("Missing 'results' field".into()))?;
 
         let json_bytes = serde_json::to_vec(results)?;
         let df = JsonReader::new(Cursor::new(json_bytes))
             .finish()
-            .map_err(|e| crate::error::Error::Custom(format!("Polars error: {e}")))?;
+            .map_err(|e| Error::Custom(format!("Polars error: {e}")))?;
         Ok(df)
     }
 }
+
+impl Kind {
+    /// The Polars dtype of columns of this kind
+    pub fn dtype(&self) -> DataType {
+        match self {
+            Kind::Boolean => DataType::Boolean,
+            Kind::Int => DataType::Int64,
+            Kind::Float => DataType::Float64,
+            Kind::String => DataType::String,
+            Kind::Strings => DataType::List(Box::new(DataType::String)),
+            Kind::Date => DataType::Date,
+            Kind::Millis | Kind::Rfc3339 => DataType::Datetime(TimeUnit::Milliseconds, Some(TimeZone::UTC)),
+        }
+    }
+}
+
+impl Schema {
+    /// The Polars schema of frames built from this schema
+    pub fn polars(&self) -> polars_core::schema::Schema {
+        self.columns
+            .iter()
+            .map(|c| Field::new(PlSmallStr::from_static(c.name), c.kind.dtype()))
+            .collect()
+    }
+
+    /// Build a DataFrame from a JSON response body
+    pub fn dataframe(&self, json: &serde_json::Value) -> Result<DataFrame> {
+        let rows = self.rows(json);
+        let columns = self.columns.iter().map(|c| series(c, &rows).into_column()).collect();
+        DataFrame::new(columns).map_err(|e| Error::Custom(format!("Polars error: {e}")))
+    }
+}
+
+fn series(column: &schema::Column, rows: &[&serde_json::Value]) -> Series {
+    let name = PlSmallStr::from_static(column.name);
+    let values = column.values(rows);
+    match column.kind {
+        Kind::Boolean => BooleanChunked::from_iter_options(name, values.map(|v| v?.as_bool())).into_series(),
+        Kind::Int => Int64Chunked::from_iter_options(name, values.map(|v| schema::read_int(v?))).into_series(),
+        Kind::Float => Float64Chunked::from_iter_options(name, values.map(|v| v?.as_f64())).into_series(),
+        Kind::String => StringChunked::from_iter_options(name, values.map(|v| v?.as_str())).into_series(),
+        Kind::Strings => {
+            let lists: Vec<Option<Series>> = values
+                .map(|v| {
+                    let items = v?.as_array()?.iter().map(serde_json::Value::as_str);
+                    Some(StringChunked::from_iter_options(PlSmallStr::EMPTY, items).into_series())
+                })
+                .collect();
+            let mut list = ListChunked::from_iter(lists);
+            list.rename(name);
+            list.into_series()
+                .cast(&column.kind.dtype())
+                .expect("list of strings casts to List(String)")
+        }
+        Kind::Date => Int32Chunked::from_iter_options(name, values.map(|v| schema::read_days(v?)))
+            .into_date()
+            .into_series(),
+        Kind::Millis => Int64Chunked::from_iter_options(name, values.map(|v| schema::read_int(v?)))
+            .into_datetime(TimeUnit::Milliseconds, Some(TimeZone::UTC))
+            .into_series(),
+        Kind::Rfc3339 => Int64Chunked::from_iter_options(name, values.map(|v| schema::read_rfc3339(v?)))
+            .into_datetime(TimeUnit::Milliseconds, Some(TimeZone::UTC))
+            .into_series(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Entry;
+
+    fn process(schema: &'static Schema, body: &str) -> DataFrame {
+        let response = Entry {
+            status: 200,
+            body: body.to_string(),
+            request_id: None,
+            headers: Vec::new(),
+            expires: None,
+            latency: None,
+        };
+        Table::new(schema).process(Ok(response)).unwrap()
+    }
+
+    #[test]
+    fn test_aggregates_schema() {
+        let df = process(
+            &schema::AGGREGATES,
+            r#"{"results": [{"t": 1704205800000, "o": 187.15, "c": 185.64, "v": 82488700, "n": 1008871}]}"#,
+        );
+        assert_eq!(**df.schema(), schema::AGGREGATES.polars());
+        assert_eq!(df.height(), 1);
+        assert_eq!(df.column("open").unwrap().f64().unwrap().get(0), Some(187.15));
+        assert_eq!(df.column("high").unwrap().null_count(), 1);
+        let timestamp = df.column("timestamp").unwrap();
+        assert_eq!(
+            timestamp.dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, Some(TimeZone::UTC))
+        );
+        assert_eq!(timestamp.datetime().unwrap().physical().get(0), Some(1704205800000));
+
+        // No results still gives every column
+        let empty = process(&schema::AGGREGATES, r#"{"resultsCount": 0}"#);
+        assert_eq!(empty.height(), 0);
+        assert_eq!(empty.schema(), df.schema());
+    }
+
+    #[test]
+    fn test_daily_open_close_schema() {
+        let df = process(&schema::DAILY_OPEN_CLOSE, &crate::fixture("aggs/daily_open_close"));
+        assert_eq!(**df.schema(), schema::DAILY_OPEN_CLOSE.polars());
+        assert_eq!(df.height(), 1);
+        assert_eq!(
+            df.column("from").unwrap().date().unwrap().physical().get(0),
+            Some(19366)
+        );
+        assert_eq!(df.column("symbol").unwrap().str().unwrap().get(0), Some("AAPL"));
+        assert_eq!(df.column("close").unwrap().f64().unwrap().get(0), Some(325.12));
+        assert_eq!(df.column("after_hours").unwrap().f64().unwrap().get(0), Some(322.1));
+        assert_eq!(df.column("pre_market").unwrap().f64().unwrap().get(0), Some(324.5));
+    }
+
+    #[test]
+    fn test_nested_schema() {
+        let df = process(
+            &schema::NEWS,
+            r#"{"results": [
+                {"id": "a", "published_utc": "2024-06-24T18:33:53Z", "publisher": {"name": "Example"}, "tickers": ["AAPL", "MSFT"]},
+                {"id": "b"}
+            ]}"#,
+        );
+        assert_eq!(**df.schema(), schema::NEWS.polars());
+        assert_eq!(df.column("publisher").unwrap().str().unwrap().get(0), Some("Example"));
+        assert_eq!(
+            df.column("tickers")
+                .unwrap()
+                .list()
+                .unwrap()
+                .get_as_series(0)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(df.column("published_utc").unwrap().null_count(), 1);
+
+        let df = process(
+            &schema::TICKER_DETAILS,
+            r#"{"results": {"ticker": "AAPL", "list_date": "1980-12-12", "address": {"city": "Cupertino"}}}"#,
+        );
+        assert_eq!(df.height(), 1);
+        assert_eq!(df.column("city").unwrap().str().unwrap().get(0), Some("Cupertino"));
+        assert_eq!(
+            df.column("list_date").unwrap().date().unwrap().physical().get(0),
+            Some(3998)
+        );
+    }
+}
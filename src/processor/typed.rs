@@ -0,0 +1,184 @@
+//! Serde processor. Deserializes JSON response into any `DeserializeOwned` type
+use std::marker::PhantomData;
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, Result};
+use crate::processor::Processor;
+use crate::response::Response;
+
+/// Deserializes the `results` of a response (or the whole body) into `T` with serde
+///
+/// The response types in [`response`](crate::response) accept the API's field names (e.g. `o`,
+/// `vw`, `T`) as aliases, so an endpoint only needs a struct deriving `Deserialize`, without a
+/// hand-written [`Decoder`](super::Decoder) function. Use a `Decoder` for custom decoding.
+///
+/// A missing or null `results` deserializes as an empty array, so `Vec<T>` gives no elements.
+pub struct Typed<T> {
+    body: bool,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T> Typed<T> {
+    /// Deserialize the `results` field into `T`
+    pub const fn new() -> Self {
+        Self {
+            body: false,
+            _output: PhantomData,
+        }
+    }
+
+    /// Deserialize the whole body into `T`, for responses without a `results` field (e.g. daily open/close)
+    pub const fn body() -> Self {
+        Self {
+            body: true,
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for Typed<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Typed<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Typed<T> {}
+
+impl<T> std::fmt::Debug for Typed<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Typed")
+            .field("output", &std::any::type_name::<T>())
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+/// The `results` field of a response, ignoring the rest of the envelope
+#[derive(Deserialize)]
+struct Results<T> {
+    results: Option<T>,
+}
+
+impl<T: DeserializeOwned> Processor for Typed<T> {
+    type Output = T;
+
+    fn process<R: Response>(&self, response: Result<R>) -> Result<T> {
+        let resp = response?; // Propagate HTTP errors
+        if resp.status() != 200 {
+            return Err(Error::ApiError {
+                request_id: resp.request_id().to_owned(),
+                status: resp.status().to_owned(),
+                message: resp.body().to_owned(),
+            });
+        }
+
+        let decode_error = |e: serde_json::Error| Error::Custom(format!("Decode error: {e}"));
+        if self.body {
+            return serde_json::from_str(resp.body()).map_err(decode_error);
+        }
+        match serde_json::from_str::<Results<T>>(resp.body()).map_err(decode_error)? {
+            Results { results: Some(results) } => Ok(results),
+            Results { results: None } => serde_json::from_str("[]")
+                .map_err(|_| Error::Custom("Decode error: missing 'results' field".to_string())),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "decoder"))]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+    use crate::cache::Entry;
+    use crate::response::aggs::*;
+    use crate::response::financials::{BalanceSheet, DecodeFinancials};
+    use crate::response::ticker::*;
+    use crate::rest::decoded::{aggs::decode as aggs_decode, tickers::decode as tickers_decode};
+
+    fn process<T: DeserializeOwned>(processor: Typed<T>, body: &str) -> Result<T> {
+        let response = Entry {
+            status: 200,
+            body: body.to_string(),
+            request_id: None,
+            headers: Vec::new(),
+            expires: None,
+            latency: None,
+        };
+        processor.process(Ok(response))
+    }
+
+    /// Check that serde gives the same value as the hand-written decoder
+    fn same_as_decoder<T: DeserializeOwned + Serialize>(
+        processor: Typed<T>,
+        fixture: &str,
+        decode: impl Fn(decoder::Value) -> decoder::Result<T>,
+    ) {
+        let typed = process(processor, fixture).unwrap();
+        let decoded = decoder::run(serde_json::from_str, decode, fixture).unwrap();
+        assert_eq!(
+            serde_json::to_value(typed).unwrap(),
+            serde_json::to_value(decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_typed() {
+        same_as_decoder(
+            Typed::<Vec<Agg>>::new(),
+            &crate::fixture("aggs/aggregates"),
+            aggs_decode::aggregates,
+        );
+        same_as_decoder(
+            Typed::<Vec<PreviousCloseAgg>>::new(),
+            &crate::fixture("aggs/previous_close"),
+            aggs_decode::previous_close,
+        );
+        same_as_decoder(
+            Typed::<Vec<GroupedDailyAgg>>::new(),
+            &crate::fixture("aggs/grouped_daily"),
+            aggs_decode::grouped_daily,
+        );
+        same_as_decoder(
+            Typed::<DailyOpenCloseAgg>::body(),
+            &crate::fixture("aggs/daily_open_close"),
+            aggs_decode::daily_open_close,
+        );
+        same_as_decoder(
+            Typed::<Vec<Ticker>>::new(),
+            &crate::fixture("tickers/all_tickers"),
+            tickers_decode::all,
+        );
+        same_as_decoder(
+            Typed::<Ticker>::new(),
+            &crate::fixture("tickers/ticker_details"),
+            tickers_decode::details,
+        );
+        same_as_decoder(
+            Typed::<TickerChangeResults>::new(),
+            &crate::fixture("tickers/ticker_events"),
+            tickers_decode::events,
+        );
+        same_as_decoder(
+            Typed::<Vec<TickerNews>>::new(),
+            &crate::fixture("tickers/news"),
+            tickers_decode::news,
+        );
+        same_as_decoder(
+            Typed::<Vec<BalanceSheet>>::new(),
+            &crate::fixture("financials/balance_sheets"),
+            Vec::<BalanceSheet>::decoder_fn(),
+        );
+
+        let empty = process(Typed::<Vec<Agg>>::new(), r#"{"status": "OK", "resultsCount": 0}"#).unwrap();
+        assert!(empty.is_empty());
+        assert!(process(Typed::<Ticker>::new(), r#"{"status": "OK"}"#).is_err());
+    }
+}
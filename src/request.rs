@@ -3,30 +3,185 @@
 use crate::error::Result;
 use crate::response::Response;
 
+use std::fmt;
 use std::future::Future;
+use std::time::Duration;
 
 pub mod aggs;
 pub mod common;
 pub mod financials;
 pub mod tickers;
 
+/// HTTP method of an [`HttpRequest`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    /// GET request
+    Get,
+    /// POST request
+    Post,
+}
+
+impl AsRef<str> for Method {
+    fn as_ref(&self) -> &str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+/// An HTTP request to send with a [`Request`] implementation
+///
+/// Endpoint builders create one per call with the API key in the `Authorization` header. Cancel a
+/// request by dropping the future returned by [`Request::send`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// HTTP method
+    pub method: Method,
+    /// Full request URL
+    pub url: String,
+    /// Headers to send, in order
+    pub headers: Vec<(String, String)>,
+    /// JSON body for POST requests
+    pub body: Option<String>,
+    /// Time limit for the whole request, including reading the body
+    pub timeout: Option<Duration>,
+    /// Skip any response cache (see [`Cached`](crate::cache::Cached))
+    pub bypass_cache: bool,
+    /// Endpoint name for logs and metrics (e.g., `aggs.aggregates`)
+    pub endpoint: Option<&'static str>,
+}
+
+impl HttpRequest {
+    /// Create a GET request
+    pub fn get(url: impl Into<String>) -> Self {
+        Self {
+            method: Method::Get,
+            url: url.into(),
+            headers: Vec::new(),
+            body: None,
+            timeout: None,
+            bypass_cache: false,
+            endpoint: None,
+        }
+    }
+
+    /// Create a POST request with a JSON body
+    pub fn post(url: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            method: Method::Post,
+            body: Some(body.into()),
+            ..Self::get(url)
+        }
+    }
+
+    /// Add a header
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set the timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Value of a header, matching the name case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// The Authorization header carries the API key
+impl fmt::Debug for HttpRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let headers: Vec<(&str, &str)> = self
+            .headers
+            .iter()
+            .map(|(k, v)| {
+                if k.eq_ignore_ascii_case("authorization") {
+                    (k.as_str(), "[REDACTED]")
+                } else {
+                    (k.as_str(), v.as_str())
+                }
+            })
+            .collect();
+        f.debug_struct("HttpRequest")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &headers)
+            .field("body", &self.body)
+            .field("timeout", &self.timeout)
+            .field("bypass_cache", &self.bypass_cache)
+            .field("endpoint", &self.endpoint)
+            .finish()
+    }
+}
+
+/// Replace the value of an `apiKey` query param with `REDACTED`, for URLs that carry one
+pub(crate) fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let params: Vec<String> = query
+        .split('&')
+        .map(|p| match p.split_once('=') {
+            Some(("apiKey", _)) => "apiKey=REDACTED".to_string(),
+            _ => p.to_string(),
+        })
+        .collect();
+    format!("{base}?{}", params.join("&"))
+}
+
 /// Trait for HTTP clients that can make requests to the polygon.io API.
 ///
-/// Implement this trait to use custom HTTP clients with the polygon.io client.
+/// Implement this trait to use custom HTTP clients with the polygon.io client. Implementations must send
+/// every header of the [`HttpRequest`], including the `Authorization` header holding the API key, which is
+/// never part of the URL, and should enforce its `timeout`.
 pub trait Request: Send + Sync {
     /// Associated response type
-    type Response: Response;
+    type Response: Response + Send;
 
     /// Create a new instance of the HTTP client
     fn new() -> Self
     where
         Self: Sized;
 
-    /// Make an HTTP GET request to the given URL
-    fn get(&self, url: &str) -> impl Future<Output = Result<Self::Response>> + Send;
+    /// Send an HTTP request
+    fn send(&self, request: HttpRequest) -> impl Future<Output = Result<Self::Response>> + Send;
 
-    /// Make an HTTP POST request to the given URL with a JSON body
-    fn post(&self, url: &str, body: &str) -> impl Future<Output = Result<Self::Response>> + Send;
+    /// Send an HTTP request, passing the body of a `200` response to `sink` in chunks
+    ///
+    /// Other responses keep their body, and the body of a `200` response must be ignored, but its
+    /// size should still be reported by [`Response::bytes`]. The default implementation buffers the response with [`send`](Request::send) and passes the
+    /// body as a single chunk. Clients that can read the body incrementally (e.g. reqwest) should
+    /// override it so that large bodies are never held in memory whole.
+    fn send_streaming<F>(
+        &self,
+        request: HttpRequest,
+        mut sink: F,
+    ) -> impl Future<Output = Result<Self::Response>> + Send
+    where
+        F: FnMut(&[u8]) -> Result<()> + Send,
+    {
+        async move {
+            let response = self.send(request).await?;
+            if response.status() == 200 {
+                sink(response.body().as_bytes())?;
+            }
+            Ok(response)
+        }
+    }
 }
 
 #[cfg(feature = "reqwest")]
@@ -34,7 +189,11 @@ pub trait Request: Send + Sync {
 pub struct HttpResponse {
     status: u16,
     body: String,
+    /// Size of the body, which is empty once streamed
+    bytes: usize,
     request_id: Option<String>,
+    headers: Vec<(String, String)>,
+    latency: Duration,
 }
 
 #[cfg(feature = "reqwest")]
@@ -50,6 +209,18 @@ impl Response for HttpResponse {
     fn request_id(&self) -> &Option<String> {
         &self.request_id
     }
+
+    fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    fn latency(&self) -> Option<Duration> {
+        Some(self.latency)
+    }
 }
 
 #[cfg(feature = "reqwest")]
@@ -60,33 +231,130 @@ impl Request for reqwest::Client {
         reqwest::Client::new()
     }
 
-    async fn get(&self, url: &str) -> Result<Self::Response> {
-        let response = self.get(url).send().await?;
-        let status = response.status().as_u16();
-        let request_id = response
-            .headers()
-            .get("X-Request-Id")
-            .and_then(|h| h.to_str().ok().map(|s| s.to_string()));
-        let body = response.text().await?;
-        Ok(HttpResponse {
-            status,
-            body,
-            request_id,
-        })
+    async fn send(&self, request: HttpRequest) -> Result<Self::Response> {
+        let endpoint = request.endpoint;
+        let started = std::time::Instant::now();
+        let response: Result<HttpResponse> = async {
+            let response = reqwest_builder(self, request).send().await?;
+            let (status, request_id, headers) = reqwest_head(&response);
+            let body = response.text().await?;
+            Ok(HttpResponse {
+                status,
+                bytes: body.len(),
+                body,
+                request_id,
+                headers,
+                latency: started.elapsed(),
+            })
+        }
+        .await;
+        crate::telemetry::http_request(endpoint, &response);
+        response
     }
 
-    async fn post(&self, url: &str, body: &str) -> Result<Self::Response> {
-        let response = self.post(url).body(body.to_string()).send().await?;
-        let status = response.status().as_u16();
-        let request_id = response
-            .headers()
-            .get("X-Request-Id")
-            .and_then(|h| h.to_str().ok().map(|s| s.to_string()));
-        let body = response.text().await?;
-        Ok(HttpResponse {
-            status,
-            body,
-            request_id,
-        })
+    async fn send_streaming<F>(&self, request: HttpRequest, mut sink: F) -> Result<Self::Response>
+    where
+        F: FnMut(&[u8]) -> Result<()> + Send,
+    {
+        let endpoint = request.endpoint;
+        let started = std::time::Instant::now();
+        let response: Result<HttpResponse> = async {
+            let mut response = reqwest_builder(self, request).send().await?;
+            let (status, request_id, headers) = reqwest_head(&response);
+            let mut bytes = 0;
+            let body = if status == 200 {
+                while let Some(chunk) = response.chunk().await? {
+                    bytes += chunk.len();
+                    sink(&chunk)?;
+                }
+                String::new()
+            } else {
+                let body = response.text().await?;
+                bytes = body.len();
+                body
+            };
+            Ok(HttpResponse {
+                status,
+                body,
+                bytes,
+                request_id,
+                headers,
+                latency: started.elapsed(),
+            })
+        }
+        .await;
+        crate::telemetry::http_request(endpoint, &response);
+        response
+    }
+}
+
+#[cfg(feature = "reqwest")]
+fn reqwest_builder(client: &reqwest::Client, request: HttpRequest) -> reqwest::RequestBuilder {
+    let mut builder = match request.method {
+        Method::Get => client.get(&request.url),
+        Method::Post => client.post(&request.url),
+    };
+    for (name, value) in &request.headers {
+        builder = builder.header(name, value);
+    }
+    if let Some(body) = request.body {
+        builder = builder.body(body);
+    }
+    if let Some(timeout) = request.timeout {
+        builder = builder.timeout(timeout);
+    }
+    builder
+}
+
+/// Status, request ID and headers of a reqwest response
+#[cfg(feature = "reqwest")]
+fn reqwest_head(response: &reqwest::Response) -> (u16, Option<String>, Vec<(String, String)>) {
+    let status = response.status().as_u16();
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+        .collect();
+    let request_id = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("X-Request-Id"))
+        .map(|(_, v)| v.clone());
+    (status, request_id, headers)
+}
+
+#[cfg(all(test, feature = "reqwest"))]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_streamed_bytes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v2/aggs", listener.local_addr().unwrap());
+        let body = r#"{"results": [{"o": 1.0}, {"o": 2.0}]}"#;
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = socket.read(&mut request).await.unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let mut streamed = Vec::new();
+        let response = reqwest::Client::new()
+            .send_streaming(HttpRequest::get(url), |chunk: &[u8]| {
+                streamed.extend_from_slice(chunk);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        server.await.unwrap();
+        assert_eq!(streamed, body.as_bytes());
+        assert_eq!(response.body(), "");
+        assert_eq!(response.bytes(), body.len());
     }
 }
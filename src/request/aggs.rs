@@ -40,6 +40,8 @@
 
 /// Aggregates (custom bars) request builder implementation
 pub mod aggregates;
+/// Aggregates split into date windows below the base aggregate limit
+pub mod chunked;
 /// Daily open/close request builder implementation
 pub mod daily_open_close;
 /// Grouped daily bars request builder implementation
@@ -48,6 +50,7 @@ pub mod grouped_daily;
 pub mod previous_close;
 
 pub use aggregates::Aggregates;
+pub use chunked::{Chunked, JoinChunks};
 pub use daily_open_close::DailyOpenClose;
 pub use grouped_daily::GroupedDaily;
 pub use previous_close::PreviousClose;
@@ -1,14 +1,17 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
 use crate::client::Polygon;
 use crate::error::Result;
 use crate::execute::Execute;
 #[cfg(feature = "table")]
 use crate::processor::Table;
-use crate::processor::{Decoder, Processor, Raw};
+use crate::processor::{Decoder, Processor, Raw, WithProcessor};
 use crate::request::Request;
-use crate::request::common::{Limit, SortOrder, Timespan};
+use crate::request::aggs::Chunked;
+use crate::request::common::{DateLike, Limit, SortOrder, Timespan};
 use crate::response::aggs::Agg;
 
 /// Aggregates request builder
@@ -18,7 +21,7 @@ pub struct Aggregates<'a, Client: Request, P: Processor = Raw> {
     pub ticker: String,
     /// The size of the timespan multiplier
     pub multiplier: u32,
-    /// The size of the time window (e.g., minute, hour, day, week, month, quarter, year)
+    /// The size of the time window (e.g., second, minute, hour, day, week, month, quarter, year)
     pub timespan: Timespan,
     /// Start of the aggregate time window (YYYY-MM-DD or millisecond timestamp)
     pub from: String,
@@ -31,6 +34,8 @@ pub struct Aggregates<'a, Client: Request, P: Processor = Raw> {
     /// Maximum number of base aggregates queried (max: 50000, default: 5000)
     pub limit: Option<u32>,
     processor: P,
+    bypass_cache: bool,
+    timeout: Option<Duration>,
 }
 
 // Constructor - always starts with Raw
@@ -41,20 +46,22 @@ impl<'a, C: Request> Aggregates<'a, C, Raw> {
         ticker: impl Into<String>,
         multiplier: u32,
         timespan: Timespan,
-        from: impl Into<String>,
-        to: impl Into<String>,
+        from: impl DateLike,
+        to: impl DateLike,
     ) -> Self {
         Self {
             client,
             ticker: ticker.into(),
             multiplier,
             timespan,
-            from: from.into(),
-            to: to.into(),
+            from: from.into_date_param(),
+            to: to.into_date_param(),
             adjusted: None,
             sort: None,
             limit: None,
             processor: Raw,
+            bypass_cache: false,
+            timeout: None,
         }
     }
 }
@@ -66,61 +73,72 @@ impl<'a, C: Request, P: Processor + 'a> Aggregates<'a, C, P> {
         Execute::get(self)
     }
 
+    /// Skip any response cache for this request (see [`Cached`](crate::cache::Cached))
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
+    /// Set the timeout for this request, overriding the client default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Switch to another processor, e.g. your own [`Processor`] implementation
+    pub fn process_with<Q: Processor>(self, processor: Q) -> Aggregates<'a, C, Q> {
+        WithProcessor::process_with(self, processor)
+    }
+
+    /// Wrap the output in an [`Envelope`](crate::response::envelope::Envelope) with the response's
+    /// status, request ID, counts and `next_url`
+    #[cfg(feature = "serde")]
+    pub fn with_envelope(self) -> Aggregates<'a, C, crate::processor::Enveloped<P>> {
+        WithProcessor::map_processor(self, crate::processor::Enveloped::new)
+    }
+
     /// Convert to DataFrame output
     #[cfg(feature = "table")]
     pub fn as_dataframe(self) -> Aggregates<'a, C, Table> {
-        Aggregates {
-            client: self.client,
-            ticker: self.ticker,
-            multiplier: self.multiplier,
-            timespan: self.timespan,
-            from: self.from,
-            to: self.to,
-            adjusted: self.adjusted,
-            sort: self.sort,
-            limit: self.limit,
-            processor: Table,
-        }
+        self.process_with(Table::new(&crate::processor::schema::AGGREGATES))
+    }
+
+    /// Convert to Arrow RecordBatch output
+    #[cfg(feature = "arrow")]
+    pub fn as_record_batch(self) -> Aggregates<'a, C, crate::processor::Arrow> {
+        self.process_with(crate::processor::Arrow::new(&crate::processor::schema::AGGREGATES))
+    }
+
+    /// Convert to CSV output, with nested fields flattened into dotted columns
+    pub fn as_csv(self) -> Aggregates<'a, C, crate::processor::Csv> {
+        self.process_with(crate::processor::Csv::new("results"))
+    }
+
+    /// Convert to newline-delimited JSON output, one flattened object per result
+    pub fn as_ndjson(self) -> Aggregates<'a, C, crate::processor::NdJson> {
+        self.process_with(crate::processor::NdJson::new("results"))
+    }
+
+    /// Convert to typed output (`Vec<`[`Agg`]`>`) deserialized with serde as the body arrives
+    ///
+    /// Gives the same bars as [`decoded`](Self::decoded) without holding the whole body or a JSON
+    /// tree in memory, for large responses (see [`Streamed`](crate::processor::Streamed)).
+    #[cfg(feature = "serde")]
+    pub fn streamed(self) -> Aggregates<'a, C, crate::processor::Streamed<Agg>> {
+        self.process_with(crate::processor::Streamed::new())
     }
 
     /// Convert to decoded typed output (`Vec<`[`Agg`]`>`)
     pub fn decoded(self) -> Aggregates<'a, C, Decoder<Vec<Agg>>> {
-        use decoder::decode::{bool as bool_decode, f64, i64, map, sequence};
-
-        // Default decoder for Vec<Agg>
-        let decoder = Decoder::new(|value: decoder::Value| {
-            let mut response = map(value)?;
-            response.required(
-                "results",
-                sequence(|v| {
-                    let mut agg = map(v)?;
-                    Ok(Agg {
-                        open: agg.optional("o", f64)?,
-                        high: agg.optional("h", f64)?,
-                        low: agg.optional("l", f64)?,
-                        close: agg.optional("c", f64)?,
-                        volume: agg.optional("v", f64)?,
-                        vwap: agg.optional("vw", f64)?,
-                        timestamp: agg.optional("t", i64)?,
-                        transactions: agg.optional("n", i64)?,
-                        otc: agg.optional("otc", bool_decode)?,
-                    })
-                }),
-            )
-        });
+        use crate::rest::decoded::aggs::decode;
+        let decoder = Decoder::new(decode::aggregates);
+        self.process_with(decoder)
+    }
 
-        Aggregates {
-            client: self.client,
-            ticker: self.ticker,
-            multiplier: self.multiplier,
-            timespan: self.timespan,
-            from: self.from,
-            to: self.to,
-            adjusted: self.adjusted,
-            sort: self.sort,
-            limit: self.limit,
-            processor: decoder,
-        }
+    /// Convert to typed output (`Vec<`[`Agg`]`>`) deserialized with serde instead of the `decoder` crate
+    #[cfg(feature = "serde")]
+    pub fn typed(self) -> Aggregates<'a, C, crate::processor::Typed<Vec<Agg>>> {
+        self.process_with(crate::processor::Typed::new())
     }
 
     /// Set a custom decoder function
@@ -128,18 +146,7 @@ impl<'a, C: Request, P: Processor + 'a> Aggregates<'a, C, P> {
         self,
         decoder_fn: impl Fn(decoder::Value) -> decoder::Result<Vec<Agg>> + Send + Sync + 'static,
     ) -> Aggregates<'a, C, Decoder<Vec<Agg>>> {
-        Aggregates {
-            client: self.client,
-            ticker: self.ticker,
-            multiplier: self.multiplier,
-            timespan: self.timespan,
-            from: self.from,
-            to: self.to,
-            adjusted: self.adjusted,
-            sort: self.sort,
-            limit: self.limit,
-            processor: Decoder::new(decoder_fn),
-        }
+        self.process_with(Decoder::new(decoder_fn))
     }
 
     /// Set adjusted flag
@@ -159,6 +166,55 @@ impl<'a, C: Request, P: Processor + 'a> Aggregates<'a, C, P> {
         self.limit = limit.into().into();
         self
     }
+
+    /// Split `from`..`to` into windows under the base aggregate limit, fetched separately and
+    /// joined into one output (see [`Chunked`])
+    pub fn chunked(self) -> Chunked<'a, C, P> {
+        Chunked::new(self)
+    }
+
+    /// This request over `from`..`to` with `limit`, for one window of [`Chunked`]
+    pub(crate) fn window(&self, from: String, to: String, limit: u32) -> Self
+    where
+        P: Clone,
+    {
+        Aggregates {
+            client: self.client,
+            ticker: self.ticker.clone(),
+            multiplier: self.multiplier,
+            timespan: self.timespan,
+            from,
+            to,
+            adjusted: self.adjusted,
+            sort: self.sort.clone(),
+            limit: Some(limit),
+            processor: self.processor.clone(),
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl<'a, C: Request, P: Processor + 'a> WithProcessor for Aggregates<'a, C, P> {
+    type Processor = P;
+    type With<Q: Processor> = Aggregates<'a, C, Q>;
+
+    fn map_processor<Q: Processor>(self, f: impl FnOnce(P) -> Q) -> Aggregates<'a, C, Q> {
+        Aggregates {
+            client: self.client,
+            ticker: self.ticker,
+            multiplier: self.multiplier,
+            timespan: self.timespan,
+            from: self.from,
+            to: self.to,
+            adjusted: self.adjusted,
+            sort: self.sort,
+            limit: self.limit,
+            processor: f(self.processor),
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
+        }
+    }
 }
 
 impl<'a, C: Request, P: Processor + 'a> Execute for Aggregates<'a, C, P> {
@@ -167,40 +223,32 @@ impl<'a, C: Request, P: Processor + 'a> Execute for Aggregates<'a, C, P> {
     #[allow(refining_impl_trait_reachable)]
     async fn get(self) -> Result<P::Output> {
         // Build URL path
-        let timespan = format!("{:?}", self.timespan).to_lowercase();
         let mut path = format!(
             "/v2/aggs/ticker/{}/range/{}/{}/{}/{}",
-            self.ticker, self.multiplier, timespan, self.from, self.to
+            self.ticker, self.multiplier, self.timespan, self.from, self.to
         );
 
         // Add query params
-        let api_key = self
-            .client
-            .api_key()
-            .ok_or_else(|| crate::error::Error::Custom("API key not set".to_string()))?;
-
-        let mut params = vec![format!("apiKey={}", api_key)];
+        let mut params = Vec::new();
         if let Some(a) = self.adjusted {
             params.push(format!("adjusted={a}"));
         }
         if let Some(s) = self.sort {
-            params.push(format!("sort={}", format!("{s:?}").to_lowercase()));
+            params.push(format!("sort={s}"));
         }
         if let Some(l) = self.limit {
             params.push(format!("limit={l}"));
         }
 
-        path.push('?');
-        path.push_str(&params.join("&"));
-
-        // Build full URL
-        let url = format!("https://api.polygon.io{path}");
-
-        // Make request using Request trait
-        let response = self.client.client().get(&url).await;
+        if !params.is_empty() {
+            path.push('?');
+            path.push_str(&params.join("&"));
+        }
 
-        // Process using associated Processor type
-        self.processor.process(response)
+        let mut request = self.client.request("aggs.aggregates", &path)?;
+        request.bypass_cache = self.bypass_cache;
+        request.timeout = self.timeout.or(request.timeout);
+        self.client.execute(request, &self.processor).await
     }
 }
 
@@ -211,7 +259,7 @@ pub struct Params {
     pub ticker: String,
     /// The size of the timespan multiplier
     pub multiplier: u32,
-    /// The size of the time window (e.g., minute, hour, day, week, month, quarter, year)
+    /// The size of the time window (e.g., second, minute, hour, day, week, month, quarter, year)
     pub timespan: Timespan,
     /// Start of the aggregate time window (YYYY-MM-DD or millisecond timestamp)
     pub from: String,
@@ -0,0 +1,356 @@
+use std::collections::HashSet;
+
+use crate::batch::bounded;
+use crate::error::{Error, Result};
+use crate::execute::Execute;
+use crate::processor::Processor;
+use crate::request::Request;
+use crate::request::aggs::Aggregates;
+use crate::request::common::{SortOrder, Timespan};
+use crate::response::aggs::Agg;
+use crate::time;
+
+/// Most base aggregates a single aggregates request can query
+pub const MAX_LIMIT: u32 = 50_000;
+
+const SECOND_MS: i64 = 1_000;
+const MINUTE_MS: i64 = 60 * SECOND_MS;
+const DAY_MS: i64 = 24 * 60 * MINUTE_MS;
+
+/// Aggregates request split into consecutive windows of its `from`..`to` range
+///
+/// One request queries at most 50,000 base aggregates (seconds for second bars, minutes for minute
+/// and hour bars, days otherwise), so a year of minute bars comes back silently truncated. Each
+/// window covers a whole number of bars within the request's `limit` (50,000 when unset). The
+/// windows are fetched with up to [`concurrency`](Self::concurrency) in flight and their outputs
+/// joined in order, dropping any bar whose timestamp an earlier window already returned.
+///
+/// Date (`YYYY-MM-DD`) ranges are split into whole days. If either end is a millisecond timestamp,
+/// or a single day holds more base aggregates than the limit (e.g. second bars), the windows are
+/// timestamps instead, with a date end taken as that UTC day. Month, quarter and year
+/// bars have no fixed length and are never split.
+///
+/// ```no_run
+/// use polygon::Polygon;
+/// use polygon::request::common::Timespan;
+/// use polygon::rest;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Polygon::default().with_key("your_api_key");
+/// let bars = rest::aggs::aggregates(&client, "AAPL", 1, Timespan::Minute, "2024-01-01", "2024-12-31")
+///     .decoded()
+///     .chunked()
+///     .concurrency(4)
+///     .get()
+///     .await?;
+/// println!("{} minute bars", bars.len());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Chunked<'a, Client: Request, P: Processor> {
+    request: Aggregates<'a, Client, P>,
+    concurrency: usize,
+}
+
+impl<'a, C: Request, P: Processor + 'a> Chunked<'a, C, P> {
+    /// Split `request` into windows, fetched one at a time
+    pub fn new(request: Aggregates<'a, C, P>) -> Self {
+        Self {
+            request,
+            concurrency: 1,
+        }
+    }
+
+    /// Fetch up to `concurrency` windows at once (default: 1, 0 is treated as 1)
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// The `from` and `to` of each window, in ascending order
+    pub fn windows(&self) -> Result<Vec<(String, String)>> {
+        let request = &self.request;
+        windows(
+            &request.from,
+            &request.to,
+            request.multiplier,
+            request.timespan,
+            request.limit.unwrap_or(MAX_LIMIT),
+        )
+    }
+
+    /// Execute the requests and return the joined result
+    pub fn get(self) -> impl std::future::Future<Output = Result<P::Output>> + 'a
+    where
+        P: Clone,
+        P::Output: JoinChunks,
+    {
+        Execute::get(self)
+    }
+}
+
+impl<'a, C: Request, P> Execute for Chunked<'a, C, P>
+where
+    P: Processor + Clone + 'a,
+    P::Output: JoinChunks,
+{
+    type Output = P::Output;
+
+    #[allow(refining_impl_trait_reachable)]
+    async fn get(self) -> Result<P::Output> {
+        let limit = self.request.limit.unwrap_or(MAX_LIMIT);
+        let requests = self
+            .windows()?
+            .into_iter()
+            .enumerate()
+            .map(|(i, (from, to))| (i, self.request.window(from, to, limit).get()));
+        let mut chunks = bounded(requests, self.concurrency).await;
+        chunks.sort_by_key(|(i, _)| *i);
+        // Windows are ascending, descending bars need them the other way around
+        if matches!(self.request.sort, Some(SortOrder::Desc)) {
+            chunks.reverse();
+        }
+
+        let chunks = chunks.into_iter().map(|(_, chunk)| chunk).collect::<Result<Vec<_>>>()?;
+        P::Output::join_chunks(chunks)
+    }
+}
+
+/// Outputs that [`Chunked`] can join into one
+pub trait JoinChunks: Sized {
+    /// Concatenate the outputs of consecutive windows, dropping bars with an already seen timestamp
+    fn join_chunks(chunks: Vec<Self>) -> Result<Self>;
+}
+
+impl JoinChunks for Vec<Agg> {
+    fn join_chunks(chunks: Vec<Self>) -> Result<Self> {
+        let mut seen = HashSet::new();
+        Ok(chunks
+            .into_iter()
+            .flatten()
+            .filter(|agg| agg.timestamp.is_none_or(|t| seen.insert(t)))
+            .collect())
+    }
+}
+
+#[cfg(feature = "table")]
+impl JoinChunks for polars_core::prelude::DataFrame {
+    fn join_chunks(chunks: Vec<Self>) -> Result<Self> {
+        use polars_core::prelude::*;
+
+        let polars_error = |e: PolarsError| Error::Custom(format!("Polars error: {e}"));
+        // Inferred frames of empty windows have no columns
+        let mut chunks = chunks.into_iter().filter(|df| df.width() > 0);
+        let Some(mut df) = chunks.next() else {
+            return Ok(DataFrame::empty());
+        };
+        for chunk in chunks {
+            df.vstack_mut(&chunk).map_err(polars_error)?;
+        }
+        df.rechunk_mut();
+
+        // `timestamp` with an endpoint schema, `t` when inferred
+        let Some(name) = ["timestamp", "t"]
+            .into_iter()
+            .find(|name| df.get_column_index(name).is_some())
+        else {
+            return Ok(df);
+        };
+        let timestamps = df
+            .column(name)
+            .and_then(|column| column.cast(&DataType::Int64))
+            .map_err(polars_error)?;
+        let mut seen = HashSet::new();
+        let mask: BooleanChunked = timestamps
+            .i64()
+            .map_err(polars_error)?
+            .into_iter()
+            .map(|t| t.is_none_or(|t| seen.insert(t)))
+            .collect();
+        df.filter(&mask).map_err(polars_error)
+    }
+}
+
+/// Split `from`..=`to` into windows of at most `limit` base aggregates
+fn windows(from: &str, to: &str, multiplier: u32, timespan: Timespan, limit: u32) -> Result<Vec<(String, String)>> {
+    let (base, bar) = match timespan {
+        Timespan::Second => (SECOND_MS, SECOND_MS),
+        Timespan::Minute => (MINUTE_MS, MINUTE_MS),
+        Timespan::Hour => (MINUTE_MS, 60 * MINUTE_MS),
+        Timespan::Day => (DAY_MS, DAY_MS),
+        Timespan::Week => (DAY_MS, 7 * DAY_MS),
+        Timespan::Month | Timespan::Quarter | Timespan::Year => return Ok(vec![(from.to_string(), to.to_string())]),
+    };
+    let bar = bar * i64::from(multiplier.max(1));
+    let max = base * i64::from(limit.max(1));
+
+    // Whole days of whole bars, when at least one fits in a window
+    let step = lcm(bar, DAY_MS);
+    let days = max / step * step / DAY_MS;
+    let windows: Vec<_> = match (Bound::parse(from)?, Bound::parse(to)?) {
+        (Bound::Date(start), Bound::Date(end)) if days > 0 => (start..=end)
+            .step_by(days as usize)
+            .map(|day| (time::format_days(day), time::format_days((day + days - 1).min(end))))
+            .collect(),
+        (start, end) => {
+            let (start, end) = (start.first_ms(), end.last_ms());
+            let len = window_len(max, bar);
+            (start..=end)
+                .step_by(len as usize)
+                .map(|ms| (ms.to_string(), (ms + len - 1).min(end).to_string()))
+                .collect()
+        }
+    };
+    // Let the API answer an empty or reversed range
+    if windows.is_empty() {
+        return Ok(vec![(from.to_string(), to.to_string())]);
+    }
+    Ok(windows)
+}
+
+/// The most whole `step`s within `max` milliseconds, at least one
+fn window_len(max: i64, step: i64) -> i64 {
+    (max / step).max(1) * step
+}
+
+fn lcm(a: i64, b: i64) -> i64 {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    a / x * b
+}
+
+/// One end of the range
+#[derive(Debug, Clone, Copy)]
+enum Bound {
+    /// Days since 1970-01-01
+    Date(i64),
+    /// Unix millisecond timestamp
+    Millis(i64),
+}
+
+impl Bound {
+    fn parse(value: &str) -> Result<Self> {
+        let invalid = || {
+            Error::Custom(format!(
+                "Invalid date: {value} (expected YYYY-MM-DD or a millisecond timestamp)"
+            ))
+        };
+        if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+            return value.parse().map(Bound::Millis).map_err(|_| invalid());
+        }
+        time::parse_days(value).map(Bound::Date).ok_or_else(invalid)
+    }
+
+    /// First millisecond of the bound
+    fn first_ms(self) -> i64 {
+        match self {
+            Bound::Date(day) => day * DAY_MS,
+            Bound::Millis(ms) => ms,
+        }
+    }
+
+    /// Last millisecond of the bound
+    fn last_ms(self) -> i64 {
+        match self {
+            Bound::Date(day) => (day + 1) * DAY_MS - 1,
+            Bound::Millis(ms) => ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows() {
+        let year = windows("2024-01-01", "2024-12-31", 1, Timespan::Minute, MAX_LIMIT).unwrap();
+        // 50,000 minutes is 34.7 days
+        assert_eq!(year.len(), 11);
+        assert_eq!(year[0], ("2024-01-01".to_string(), "2024-02-03".to_string()));
+        assert_eq!(year[1].0, "2024-02-04");
+        assert_eq!(year[10], ("2024-12-06".to_string(), "2024-12-31".to_string()));
+
+        // 86,400 seconds a day don't fit in 50,000
+        let seconds = windows("2024-02-28", "2024-03-01", 1, Timespan::Second, MAX_LIMIT).unwrap();
+        assert_eq!(seconds.len(), 6);
+        assert_eq!(seconds[0], ("1709078400000".to_string(), "1709128399999".to_string()));
+        assert_eq!(seconds[1].0, "1709128400000");
+        assert_eq!(seconds[5].1, "1709337599999");
+        let minutes = windows("2024-01-01", "2024-01-01", 1, Timespan::Minute, 1000).unwrap();
+        assert_eq!(
+            minutes,
+            [
+                ("1704067200000".to_string(), "1704127199999".to_string()),
+                ("1704127200000".to_string(), "1704153599999".to_string())
+            ]
+        );
+
+        let hours = windows("0", "1970-01-01", 2, Timespan::Hour, 300).unwrap();
+        // 300 minutes hold two 2-hour bars
+        assert_eq!(hours[0], ("0".to_string(), "14399999".to_string()));
+        assert_eq!(hours[5], ("72000000".to_string(), "86399999".to_string()));
+        assert_eq!(hours.len(), 6);
+
+        let months = windows("2000-01-01", "2024-12-31", 1, Timespan::Month, 10).unwrap();
+        assert_eq!(months, [("2000-01-01".to_string(), "2024-12-31".to_string())]);
+        assert_eq!(
+            windows("2024-01-02", "2024-01-01", 1, Timespan::Day, 1).unwrap().len(),
+            1
+        );
+        assert!(windows("2024-1-1", "2024-01-31", 1, Timespan::Day, MAX_LIMIT).is_err());
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert!(matches!(Bound::parse("2024-01-01"), Ok(Bound::Date(19723))));
+        assert_eq!(Bound::parse("2024-01-01").unwrap().first_ms(), 1_704_067_200_000);
+        assert!(matches!(
+            Bound::parse("1704067200000"),
+            Ok(Bound::Millis(1_704_067_200_000))
+        ));
+        assert!(Bound::parse("2024-01-01T00:00:00Z").is_err());
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_chunked() {
+        use std::time::Duration;
+
+        use crate::client::Polygon;
+        use crate::rest;
+        use crate::testing::{Mock, MockClient};
+
+        let body = |timestamps: &[i64]| {
+            let bars: Vec<_> = timestamps
+                .iter()
+                .map(|t| format!(r#"{{"c": 1.0, "t": {t}}}"#))
+                .collect();
+            format!(r#"{{"status": "OK", "results": [{}]}}"#, bars.join(","))
+        };
+        let mock = MockClient::new();
+        mock.register(
+            Mock::get("/v2/aggs/ticker/AAPL/range/1/minute/2024-01-01/2024-01-01")
+                .respond(200, body(&[1, 2]))
+                .latency(Duration::from_millis(20)),
+        )
+        .register(Mock::get("/v2/aggs/ticker/AAPL/range/1/minute/2024-01-02/2024-01-02").respond(200, body(&[2, 3])))
+        .register(Mock::get("/v2/aggs/ticker/AAPL/range/1/minute/2024-01-03/2024-01-03").respond(200, body(&[4])));
+        let client = Polygon::default().with_client(mock.clone()).with_key("key");
+
+        let bars = rest::aggs::aggregates(&client, "AAPL", 1, Timespan::Minute, "2024-01-01", "2024-01-03")
+            .limit(1440)
+            .decoded()
+            .chunked()
+            .concurrency(3)
+            .get()
+            .await
+            .unwrap();
+        let timestamps: Vec<_> = bars.iter().map(|bar| bar.timestamp.unwrap()).collect();
+        assert_eq!(timestamps, [1, 2, 3, 4]);
+        assert_eq!(mock.requests().len(), 3);
+    }
+}
@@ -1,14 +1,21 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
 use crate::client::Polygon;
 use crate::error::Result;
 use crate::execute::Execute;
-use crate::processor::{Decoder, Processor, Raw};
+use crate::processor::{Decoder, Processor, Raw, WithProcessor};
 use crate::request::Request;
+use crate::request::common::DateParam;
 use crate::response::aggs::DailyOpenCloseAgg;
 
 /// Daily open/close request builder
+///
+/// Queries `/v1/open-close/{ticker}/{date}`. The raw output is that endpoint's flat object (`symbol`,
+/// `from`, `open`, `close`, `afterHours`, `preMarket`, ...) rather than an aggregates response with
+/// a `results` array, which is what earlier versions returned by querying a one-day aggregates range.
 pub struct DailyOpenClose<'a, Client: Request, P: Processor = Raw> {
     client: &'a Polygon<Client>,
     /// Ticker symbol (e.g., "AAPL" for Apple Inc.)
@@ -18,17 +25,21 @@ pub struct DailyOpenClose<'a, Client: Request, P: Processor = Raw> {
     /// Whether results are adjusted for splits (default: true)
     pub adjusted: Option<bool>,
     processor: P,
+    bypass_cache: bool,
+    timeout: Option<Duration>,
 }
 
 impl<'a, C: Request> DailyOpenClose<'a, C, Raw> {
     /// Create new daily open/close request
-    pub fn new(client: &'a Polygon<C>, ticker: impl Into<String>, date: impl Into<String>) -> Self {
+    pub fn new(client: &'a Polygon<C>, ticker: impl Into<String>, date: impl DateParam) -> Self {
         Self {
             client,
             ticker: ticker.into(),
-            date: date.into(),
+            date: date.into_date(),
             adjusted: None,
             processor: Raw,
+            bypass_cache: false,
+            timeout: None,
         }
     }
 }
@@ -39,46 +50,67 @@ impl<'a, C: Request, P: Processor + 'a> DailyOpenClose<'a, C, P> {
         Execute::get(self)
     }
 
+    /// Skip any response cache for this request (see [`Cached`](crate::cache::Cached))
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
+    /// Set the timeout for this request, overriding the client default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Switch to another processor, e.g. your own [`Processor`] implementation
+    pub fn process_with<Q: Processor>(self, processor: Q) -> DailyOpenClose<'a, C, Q> {
+        WithProcessor::process_with(self, processor)
+    }
+
+    /// Wrap the output in an [`Envelope`](crate::response::envelope::Envelope) with the response's
+    /// status, request ID, counts and `next_url`
+    #[cfg(feature = "serde")]
+    pub fn with_envelope(self) -> DailyOpenClose<'a, C, crate::processor::Enveloped<P>> {
+        WithProcessor::map_processor(self, crate::processor::Enveloped::new)
+    }
+
     /// Convert to DataFrame output
     #[cfg(feature = "table")]
     pub fn as_dataframe(self) -> DailyOpenClose<'a, C, crate::processor::Table> {
-        DailyOpenClose {
-            client: self.client,
-            ticker: self.ticker,
-            date: self.date,
-            adjusted: self.adjusted,
-            processor: crate::processor::Table,
-        }
+        self.process_with(crate::processor::Table::new(
+            &crate::processor::schema::DAILY_OPEN_CLOSE,
+        ))
+    }
+
+    /// Convert to Arrow RecordBatch output
+    #[cfg(feature = "arrow")]
+    pub fn as_record_batch(self) -> DailyOpenClose<'a, C, crate::processor::Arrow> {
+        self.process_with(crate::processor::Arrow::new(
+            &crate::processor::schema::DAILY_OPEN_CLOSE,
+        ))
+    }
+
+    /// Convert to CSV output, a single row of the open/close fields
+    pub fn as_csv(self) -> DailyOpenClose<'a, C, crate::processor::Csv> {
+        self.process_with(crate::processor::Csv::new(""))
+    }
+
+    /// Convert to newline-delimited JSON output, a single line with the open/close fields
+    pub fn as_ndjson(self) -> DailyOpenClose<'a, C, crate::processor::NdJson> {
+        self.process_with(crate::processor::NdJson::new(""))
     }
 
     /// Convert to decoded typed output (DailyOpenCloseAgg)
     pub fn decoded(self) -> DailyOpenClose<'a, C, Decoder<DailyOpenCloseAgg>> {
-        use decoder::decode::{bool as bool_decode, f64, map, string};
-
-        let decoder = Decoder::new(|value: decoder::Value| {
-            let mut daily = map(value)?;
-            Ok(DailyOpenCloseAgg {
-                after_hours: daily.optional("afterHours", f64)?,
-                close: daily.optional("close", f64)?,
-                from: daily.optional("from", string)?,
-                high: daily.optional("high", f64)?,
-                low: daily.optional("low", f64)?,
-                open: daily.optional("open", f64)?,
-                pre_market: daily.optional("preMarket", f64)?,
-                status: daily.optional("status", string)?,
-                symbol: daily.optional("symbol", string)?,
-                volume: daily.optional("volume", f64)?,
-                otc: daily.optional("otc", bool_decode)?,
-            })
-        });
+        use crate::rest::decoded::aggs::decode;
+        let decoder = Decoder::new(decode::daily_open_close);
+        self.process_with(decoder)
+    }
 
-        DailyOpenClose {
-            client: self.client,
-            ticker: self.ticker,
-            date: self.date,
-            adjusted: self.adjusted,
-            processor: decoder,
-        }
+    /// Convert to typed output ([`DailyOpenCloseAgg`]) deserialized with serde instead of the `decoder` crate
+    #[cfg(feature = "serde")]
+    pub fn typed(self) -> DailyOpenClose<'a, C, crate::processor::Typed<DailyOpenCloseAgg>> {
+        self.process_with(crate::processor::Typed::body())
     }
 
     /// Set a custom decoder function
@@ -86,13 +118,7 @@ impl<'a, C: Request, P: Processor + 'a> DailyOpenClose<'a, C, P> {
         self,
         decoder_fn: impl Fn(decoder::Value) -> decoder::Result<DailyOpenCloseAgg> + Send + Sync + 'static,
     ) -> DailyOpenClose<'a, C, Decoder<DailyOpenCloseAgg>> {
-        DailyOpenClose {
-            client: self.client,
-            ticker: self.ticker,
-            date: self.date,
-            adjusted: self.adjusted,
-            processor: Decoder::new(decoder_fn),
-        }
+        self.process_with(Decoder::new(decoder_fn))
     }
 
     /// Set adjusted flag
@@ -102,31 +128,43 @@ impl<'a, C: Request, P: Processor + 'a> DailyOpenClose<'a, C, P> {
     }
 }
 
+impl<'a, C: Request, P: Processor + 'a> WithProcessor for DailyOpenClose<'a, C, P> {
+    type Processor = P;
+    type With<Q: Processor> = DailyOpenClose<'a, C, Q>;
+
+    fn map_processor<Q: Processor>(self, f: impl FnOnce(P) -> Q) -> DailyOpenClose<'a, C, Q> {
+        DailyOpenClose {
+            client: self.client,
+            ticker: self.ticker,
+            date: self.date,
+            adjusted: self.adjusted,
+            processor: f(self.processor),
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
+        }
+    }
+}
+
 impl<'a, C: Request, P: Processor + 'a> Execute for DailyOpenClose<'a, C, P> {
     type Output = P::Output;
 
     #[allow(refining_impl_trait_reachable)]
     async fn get(self) -> Result<P::Output> {
-        let api_key = self
-            .client
-            .api_key()
-            .ok_or_else(|| crate::error::Error::Custom("API key not set".to_string()))?;
-
-        let mut path = format!(
-            "/v2/aggs/ticker/{}/range/1/day/{}/{}",
-            self.ticker, self.date, self.date
-        );
-        let mut params = vec![format!("apiKey={}", api_key)];
+        let mut path = format!("/v1/open-close/{}/{}", self.ticker, self.date);
+        let mut params = Vec::new();
 
         if let Some(a) = self.adjusted {
             params.push(format!("adjusted={a}"));
         }
 
-        path.push('?');
-        path.push_str(&params.join("&"));
-        let url = format!("https://api.polygon.io{path}");
-        let response = self.client.client().get(&url).await;
-        self.processor.process(response)
+        if !params.is_empty() {
+            path.push('?');
+            path.push_str(&params.join("&"));
+        }
+        let mut request = self.client.request("aggs.daily_open_close", &path)?;
+        request.bypass_cache = self.bypass_cache;
+        request.timeout = self.timeout.or(request.timeout);
+        self.client.execute(request, &self.processor).await
     }
 }
 
@@ -1,11 +1,14 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
 use crate::client::Polygon;
 use crate::error::Result;
 use crate::execute::Execute;
-use crate::processor::{Decoder, Processor, Raw};
+use crate::processor::{Decoder, Processor, Raw, WithProcessor};
 use crate::request::Request;
+use crate::request::common::DateParam;
 use crate::response::aggs::GroupedDailyAgg;
 
 /// Grouped daily bars request builder
@@ -18,17 +21,21 @@ pub struct GroupedDaily<'a, Client: Request, P: Processor = Raw> {
     /// Include OTC (Over-the-Counter) securities in the results
     pub include_otc: Option<bool>,
     processor: P,
+    bypass_cache: bool,
+    timeout: Option<Duration>,
 }
 
 impl<'a, C: Request> GroupedDaily<'a, C, Raw> {
     /// Create new grouped daily request
-    pub fn new(client: &'a Polygon<C>, date: impl Into<String>) -> Self {
+    pub fn new(client: &'a Polygon<C>, date: impl DateParam) -> Self {
         Self {
             client,
-            date: date.into(),
+            date: date.into_date(),
             adjusted: None,
             include_otc: None,
             processor: Raw,
+            bypass_cache: false,
+            timeout: None,
         }
     }
 }
@@ -39,51 +46,72 @@ impl<'a, C: Request, P: Processor + 'a> GroupedDaily<'a, C, P> {
         Execute::get(self)
     }
 
+    /// Skip any response cache for this request (see [`Cached`](crate::cache::Cached))
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
+    /// Set the timeout for this request, overriding the client default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Switch to another processor, e.g. your own [`Processor`] implementation
+    pub fn process_with<Q: Processor>(self, processor: Q) -> GroupedDaily<'a, C, Q> {
+        WithProcessor::process_with(self, processor)
+    }
+
+    /// Wrap the output in an [`Envelope`](crate::response::envelope::Envelope) with the response's
+    /// status, request ID, counts and `next_url`
+    #[cfg(feature = "serde")]
+    pub fn with_envelope(self) -> GroupedDaily<'a, C, crate::processor::Enveloped<P>> {
+        WithProcessor::map_processor(self, crate::processor::Enveloped::new)
+    }
+
     /// Convert to DataFrame output
     #[cfg(feature = "table")]
     pub fn as_dataframe(self) -> GroupedDaily<'a, C, crate::processor::Table> {
-        GroupedDaily {
-            client: self.client,
-            date: self.date,
-            adjusted: self.adjusted,
-            include_otc: self.include_otc,
-            processor: crate::processor::Table,
-        }
+        self.process_with(crate::processor::Table::new(&crate::processor::schema::GROUPED_DAILY))
+    }
+
+    /// Convert to Arrow RecordBatch output
+    #[cfg(feature = "arrow")]
+    pub fn as_record_batch(self) -> GroupedDaily<'a, C, crate::processor::Arrow> {
+        self.process_with(crate::processor::Arrow::new(&crate::processor::schema::GROUPED_DAILY))
+    }
+
+    /// Convert to CSV output, with nested fields flattened into dotted columns
+    pub fn as_csv(self) -> GroupedDaily<'a, C, crate::processor::Csv> {
+        self.process_with(crate::processor::Csv::new("results"))
+    }
+
+    /// Convert to newline-delimited JSON output, one flattened object per result
+    pub fn as_ndjson(self) -> GroupedDaily<'a, C, crate::processor::NdJson> {
+        self.process_with(crate::processor::NdJson::new("results"))
+    }
+
+    /// Convert to typed output (`Vec<`[`GroupedDailyAgg`]`>`) deserialized with serde as the body arrives
+    ///
+    /// Gives the same bars as [`decoded`](Self::decoded) without holding the whole body or a JSON
+    /// tree in memory, for large responses (see [`Streamed`](crate::processor::Streamed)).
+    #[cfg(feature = "serde")]
+    pub fn streamed(self) -> GroupedDaily<'a, C, crate::processor::Streamed<GroupedDailyAgg>> {
+        self.process_with(crate::processor::Streamed::new())
     }
 
     /// Convert to decoded typed output (`Vec<`[`GroupedDailyAgg`]`>`)
     pub fn decoded(self) -> GroupedDaily<'a, C, Decoder<Vec<GroupedDailyAgg>>> {
-        use decoder::decode::{bool as bool_decode, f64, i64, map, sequence, string};
-
-        let decoder = Decoder::new(|value: decoder::Value| {
-            let mut response = map(value)?;
-            response.required(
-                "results",
-                sequence(|v| {
-                    let mut grouped = map(v)?;
-                    Ok(GroupedDailyAgg {
-                        ticker: grouped.optional("T", string)?,
-                        open: grouped.optional("o", f64)?,
-                        high: grouped.optional("h", f64)?,
-                        low: grouped.optional("l", f64)?,
-                        close: grouped.optional("c", f64)?,
-                        volume: grouped.optional("v", f64)?,
-                        vwap: grouped.optional("vw", f64)?,
-                        timestamp: grouped.optional("t", i64)?,
-                        transactions: grouped.optional("n", i64)?,
-                        otc: grouped.optional("otc", bool_decode)?,
-                    })
-                }),
-            )
-        });
+        use crate::rest::decoded::aggs::decode;
+        let decoder = Decoder::new(decode::grouped_daily);
+        self.process_with(decoder)
+    }
 
-        GroupedDaily {
-            client: self.client,
-            date: self.date,
-            adjusted: self.adjusted,
-            include_otc: self.include_otc,
-            processor: decoder,
-        }
+    /// Convert to typed output (`Vec<`[`GroupedDailyAgg`]`>`) deserialized with serde instead of the `decoder` crate
+    #[cfg(feature = "serde")]
+    pub fn typed(self) -> GroupedDaily<'a, C, crate::processor::Typed<Vec<GroupedDailyAgg>>> {
+        self.process_with(crate::processor::Typed::new())
     }
 
     /// Set a custom decoder function
@@ -91,13 +119,7 @@ impl<'a, C: Request, P: Processor + 'a> GroupedDaily<'a, C, P> {
         self,
         decoder_fn: impl Fn(decoder::Value) -> decoder::Result<Vec<GroupedDailyAgg>> + Send + Sync + 'static,
     ) -> GroupedDaily<'a, C, Decoder<Vec<GroupedDailyAgg>>> {
-        GroupedDaily {
-            client: self.client,
-            date: self.date,
-            adjusted: self.adjusted,
-            include_otc: self.include_otc,
-            processor: Decoder::new(decoder_fn),
-        }
+        self.process_with(Decoder::new(decoder_fn))
     }
 
     /// Set adjusted flag
@@ -113,18 +135,30 @@ impl<'a, C: Request, P: Processor + 'a> GroupedDaily<'a, C, P> {
     }
 }
 
+impl<'a, C: Request, P: Processor + 'a> WithProcessor for GroupedDaily<'a, C, P> {
+    type Processor = P;
+    type With<Q: Processor> = GroupedDaily<'a, C, Q>;
+
+    fn map_processor<Q: Processor>(self, f: impl FnOnce(P) -> Q) -> GroupedDaily<'a, C, Q> {
+        GroupedDaily {
+            client: self.client,
+            date: self.date,
+            adjusted: self.adjusted,
+            include_otc: self.include_otc,
+            processor: f(self.processor),
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
+        }
+    }
+}
+
 impl<'a, C: Request, P: Processor + 'a> Execute for GroupedDaily<'a, C, P> {
     type Output = P::Output;
 
     #[allow(refining_impl_trait_reachable)]
     async fn get(self) -> Result<P::Output> {
-        let api_key = self
-            .client
-            .api_key()
-            .ok_or_else(|| crate::error::Error::Custom("API key not set".to_string()))?;
-
         let mut path = format!("/v2/aggs/grouped/locale/us/market/stocks/{}", self.date);
-        let mut params = vec![format!("apiKey={}", api_key)];
+        let mut params = Vec::new();
 
         if let Some(a) = self.adjusted {
             params.push(format!("adjusted={a}"));
@@ -133,11 +167,14 @@ impl<'a, C: Request, P: Processor + 'a> Execute for GroupedDaily<'a, C, P> {
             params.push(format!("include_otc={o}"));
         }
 
-        path.push('?');
-        path.push_str(&params.join("&"));
-        let url = format!("https://api.polygon.io{path}");
-        let response = self.client.client().get(&url).await;
-        self.processor.process(response)
+        if !params.is_empty() {
+            path.push('?');
+            path.push_str(&params.join("&"));
+        }
+        let mut request = self.client.request("aggs.grouped_daily", &path)?;
+        request.bypass_cache = self.bypass_cache;
+        request.timeout = self.timeout.or(request.timeout);
+        self.client.execute(request, &self.processor).await
     }
 }
 
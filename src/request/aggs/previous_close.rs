@@ -1,10 +1,12 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
 use crate::client::Polygon;
 use crate::error::Result;
 use crate::execute::Execute;
-use crate::processor::{Decoder, Processor, Raw};
+use crate::processor::{Decoder, Processor, Raw, WithProcessor};
 use crate::request::Request;
 use crate::response::aggs::PreviousCloseAgg;
 
@@ -16,6 +18,8 @@ pub struct PreviousClose<'a, Client: Request, P: Processor = Raw> {
     /// Whether results are adjusted for splits (default: true)
     pub adjusted: Option<bool>,
     processor: P,
+    bypass_cache: bool,
+    timeout: Option<Duration>,
 }
 
 impl<'a, C: Request> PreviousClose<'a, C, Raw> {
@@ -26,6 +30,8 @@ impl<'a, C: Request> PreviousClose<'a, C, Raw> {
             ticker: ticker.into(),
             adjusted: None,
             processor: Raw,
+            bypass_cache: false,
+            timeout: None,
         }
     }
 }
@@ -36,47 +42,63 @@ impl<'a, C: Request, P: Processor + 'a> PreviousClose<'a, C, P> {
         Execute::get(self)
     }
 
+    /// Skip any response cache for this request (see [`Cached`](crate::cache::Cached))
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
+    /// Set the timeout for this request, overriding the client default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Switch to another processor, e.g. your own [`Processor`] implementation
+    pub fn process_with<Q: Processor>(self, processor: Q) -> PreviousClose<'a, C, Q> {
+        WithProcessor::process_with(self, processor)
+    }
+
+    /// Wrap the output in an [`Envelope`](crate::response::envelope::Envelope) with the response's
+    /// status, request ID, counts and `next_url`
+    #[cfg(feature = "serde")]
+    pub fn with_envelope(self) -> PreviousClose<'a, C, crate::processor::Enveloped<P>> {
+        WithProcessor::map_processor(self, crate::processor::Enveloped::new)
+    }
+
     /// Convert to DataFrame output
     #[cfg(feature = "table")]
     pub fn as_dataframe(self) -> PreviousClose<'a, C, crate::processor::Table> {
-        PreviousClose {
-            client: self.client,
-            ticker: self.ticker,
-            adjusted: self.adjusted,
-            processor: crate::processor::Table,
-        }
+        self.process_with(crate::processor::Table::new(&crate::processor::schema::PREVIOUS_CLOSE))
+    }
+
+    /// Convert to Arrow RecordBatch output
+    #[cfg(feature = "arrow")]
+    pub fn as_record_batch(self) -> PreviousClose<'a, C, crate::processor::Arrow> {
+        self.process_with(crate::processor::Arrow::new(&crate::processor::schema::PREVIOUS_CLOSE))
+    }
+
+    /// Convert to CSV output, with nested fields flattened into dotted columns
+    pub fn as_csv(self) -> PreviousClose<'a, C, crate::processor::Csv> {
+        self.process_with(crate::processor::Csv::new("results"))
+    }
+
+    /// Convert to newline-delimited JSON output, one flattened object per result
+    pub fn as_ndjson(self) -> PreviousClose<'a, C, crate::processor::NdJson> {
+        self.process_with(crate::processor::NdJson::new("results"))
     }
 
     /// Convert to decoded typed output (`Vec<`[`PreviousCloseAgg`]`>`)
     pub fn decoded(self) -> PreviousClose<'a, C, Decoder<Vec<PreviousCloseAgg>>> {
-        use decoder::decode::{f64, i64, map, sequence, string};
-
-        let decoder = Decoder::new(|value: decoder::Value| {
-            let mut response = map(value)?;
-            response.required(
-                "results",
-                sequence(|v| {
-                    let mut prev = map(v)?;
-                    Ok(PreviousCloseAgg {
-                        ticker: prev.optional("T", string)?,
-                        close: prev.optional("c", f64)?,
-                        high: prev.optional("h", f64)?,
-                        low: prev.optional("l", f64)?,
-                        open: prev.optional("o", f64)?,
-                        timestamp: prev.optional("t", i64)?,
-                        volume: prev.optional("v", f64)?,
-                        vwap: prev.optional("vw", f64)?,
-                    })
-                }),
-            )
-        });
+        use crate::rest::decoded::aggs::decode;
+        let decoder = Decoder::new(decode::previous_close);
+        self.process_with(decoder)
+    }
 
-        PreviousClose {
-            client: self.client,
-            ticker: self.ticker,
-            adjusted: self.adjusted,
-            processor: decoder,
-        }
+    /// Convert to typed output (`Vec<`[`PreviousCloseAgg`]`>`) deserialized with serde instead of the `decoder` crate
+    #[cfg(feature = "serde")]
+    pub fn typed(self) -> PreviousClose<'a, C, crate::processor::Typed<Vec<PreviousCloseAgg>>> {
+        self.process_with(crate::processor::Typed::new())
     }
 
     /// Set a custom decoder function
@@ -84,12 +106,7 @@ impl<'a, C: Request, P: Processor + 'a> PreviousClose<'a, C, P> {
         self,
         decoder_fn: impl Fn(decoder::Value) -> decoder::Result<Vec<PreviousCloseAgg>> + Send + Sync + 'static,
     ) -> PreviousClose<'a, C, Decoder<Vec<PreviousCloseAgg>>> {
-        PreviousClose {
-            client: self.client,
-            ticker: self.ticker,
-            adjusted: self.adjusted,
-            processor: Decoder::new(decoder_fn),
-        }
+        self.process_with(Decoder::new(decoder_fn))
     }
 
     /// Set adjusted flag
@@ -99,28 +116,42 @@ impl<'a, C: Request, P: Processor + 'a> PreviousClose<'a, C, P> {
     }
 }
 
+impl<'a, C: Request, P: Processor + 'a> WithProcessor for PreviousClose<'a, C, P> {
+    type Processor = P;
+    type With<Q: Processor> = PreviousClose<'a, C, Q>;
+
+    fn map_processor<Q: Processor>(self, f: impl FnOnce(P) -> Q) -> PreviousClose<'a, C, Q> {
+        PreviousClose {
+            client: self.client,
+            ticker: self.ticker,
+            adjusted: self.adjusted,
+            processor: f(self.processor),
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
+        }
+    }
+}
+
 impl<'a, C: Request, P: Processor + 'a> Execute for PreviousClose<'a, C, P> {
     type Output = P::Output;
 
     #[allow(refining_impl_trait_reachable)]
     async fn get(self) -> Result<P::Output> {
-        let api_key = self
-            .client
-            .api_key()
-            .ok_or_else(|| crate::error::Error::Custom("API key not set".to_string()))?;
-
         let mut path = format!("/v2/aggs/ticker/{}/prev", self.ticker);
-        let mut params = vec![format!("apiKey={}", api_key)];
+        let mut params = Vec::new();
 
         if let Some(a) = self.adjusted {
             params.push(format!("adjusted={a}"));
         }
 
-        path.push('?');
-        path.push_str(&params.join("&"));
-        let url = format!("https://api.polygon.io{path}");
-        let response = self.client.client().get(&url).await;
-        self.processor.process(response)
+        if !params.is_empty() {
+            path.push('?');
+            path.push_str(&params.join("&"));
+        }
+        let mut request = self.client.request("aggs.previous_close", &path)?;
+        request.bypass_cache = self.bypass_cache;
+        request.timeout = self.timeout.or(request.timeout);
+        self.client.execute(request, &self.processor).await
     }
 }
 
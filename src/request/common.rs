@@ -1,4 +1,5 @@
 //! Common types used across multiple endpoints
+use std::fmt;
 use std::str::FromStr;
 
 use schemars::JsonSchema;
@@ -50,10 +51,29 @@ impl From<SortOrder> for String {
     }
 }
 
+impl AsRef<str> for SortOrder {
+    /// Query string representation (e.g., `asc`)
+    fn as_ref(&self) -> &str {
+        match self {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+            SortOrder::Custom(s) => s,
+        }
+    }
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
 /// Timespan for aggregate data
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Timespan {
+    /// Second bars
+    Second,
     /// Minute bars
     Minute,
     /// Hour bars
@@ -75,6 +95,7 @@ impl FromStr for Timespan {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.to_lowercase();
         match s.as_str() {
+            "second" | "sec" | "s" => Ok(Timespan::Second),
             "minute" | "min" => Ok(Timespan::Minute),
             "hour" | "hr" | "h" => Ok(Timespan::Hour),
             "day" | "d" | "dy" => Ok(Timespan::Day),
@@ -87,6 +108,112 @@ impl FromStr for Timespan {
     }
 }
 
+impl AsRef<str> for Timespan {
+    /// Query string representation (e.g., `minute`)
+    fn as_ref(&self) -> &str {
+        match self {
+            Timespan::Second => "second",
+            Timespan::Minute => "minute",
+            Timespan::Hour => "hour",
+            Timespan::Day => "day",
+            Timespan::Week => "week",
+            Timespan::Month => "month",
+            Timespan::Quarter => "quarter",
+            Timespan::Year => "year",
+        }
+    }
+}
+
+impl fmt::Display for Timespan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+/// A date or timestamp accepted by the `from` and `to` of aggregates
+///
+/// Strings are passed through unchanged (e.g., `"2024-01-31"`) and integers are treated as
+/// millisecond Unix timestamps. With the `chrono` feature, [`chrono::NaiveDate`] is sent as
+/// `YYYY-MM-DD` and [`chrono::DateTime`] as a millisecond timestamp. Parameters that only take a
+/// date use [`DateParam`] instead.
+pub trait DateLike {
+    /// Convert to the representation used in request URLs
+    fn into_date_param(self) -> String;
+}
+
+impl DateLike for String {
+    fn into_date_param(self) -> String {
+        self
+    }
+}
+
+impl DateLike for &str {
+    fn into_date_param(self) -> String {
+        self.to_string()
+    }
+}
+
+impl DateLike for &String {
+    fn into_date_param(self) -> String {
+        self.clone()
+    }
+}
+
+impl DateLike for i64 {
+    fn into_date_param(self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl DateLike for chrono::NaiveDate {
+    fn into_date_param(self) -> String {
+        self.format("%Y-%m-%d").to_string()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> DateLike for chrono::DateTime<Tz> {
+    fn into_date_param(self) -> String {
+        self.timestamp_millis().to_string()
+    }
+}
+
+/// A date accepted by date-only parameters (`date`, `filing_date`, `period_of_report_date`, ...)
+///
+/// Strings are passed through unchanged (e.g., `"2024-01-31"`). With the `chrono` feature,
+/// [`chrono::NaiveDate`] is sent as `YYYY-MM-DD`. Timestamps aren't accepted, as these endpoints
+/// reject them.
+pub trait DateParam {
+    /// Convert to the `YYYY-MM-DD` representation used in request URLs
+    fn into_date(self) -> String;
+}
+
+impl DateParam for String {
+    fn into_date(self) -> String {
+        self
+    }
+}
+
+impl DateParam for &str {
+    fn into_date(self) -> String {
+        self.to_string()
+    }
+}
+
+impl DateParam for &String {
+    fn into_date(self) -> String {
+        self.clone()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl DateParam for chrono::NaiveDate {
+    fn into_date(self) -> String {
+        self.format("%Y-%m-%d").to_string()
+    }
+}
+
 /// Limit for number of results
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 pub enum Limit {
@@ -136,3 +263,54 @@ impl From<Limit> for Option<u32> {
         }
     }
 }
+
+impl fmt::Display for Limit {
+    /// Query string representation (the limit value, or empty for no limit)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::None => Ok(()),
+            Limit::Some(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+/// Percent-encode a query parameter value, keeping only unreserved characters as they are
+pub(crate) fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{b:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_order_query_repr() {
+        assert_eq!(SortOrder::Asc.to_string(), "asc");
+        assert_eq!(SortOrder::Desc.to_string(), "desc");
+        assert_eq!(SortOrder::from("timestamp").to_string(), "timestamp");
+    }
+
+    #[test]
+    fn test_timespan_query_repr() {
+        for ts in ["second", "minute", "hour", "day", "week", "month", "quarter", "year"] {
+            assert_eq!(ts.parse::<Timespan>().unwrap().to_string(), ts);
+        }
+        assert!("fortnight".parse::<Timespan>().is_err());
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("AAPL"), "AAPL");
+        assert_eq!(encode("AT&T"), "AT%26T");
+        assert_eq!(encode("Berkshire Hathaway #1=A?"), "Berkshire%20Hathaway%20%231%3DA%3F");
+        assert_eq!(encode("Nestlé"), "Nestl%C3%A9");
+    }
+}
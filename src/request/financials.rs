@@ -38,24 +38,59 @@
 //!
 //! **Use Cases:** Company valuation, comparative analysis, financial health assessment, investment screening.
 
+use std::time::Duration;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::client::Polygon;
 use crate::error::Result;
 use crate::execute::Execute;
-use crate::processor::{Decoder, Processor, Raw};
+use crate::processor::{Decoder, Processor, Raw, WithProcessor};
 use crate::request::Request;
-use crate::request::common::SortOrder;
+use crate::request::common::{DateParam, SortOrder};
 
 use super::common::Limit;
 
 // Re-export raw endpoints for convenience
 pub use crate::rest::raw::financials::{balance_sheets, cash_flow_statements, income_statements, ratios};
 
+/// The kind of financial data a [`Financials`] builder requests
+#[derive(Debug, Clone, Copy)]
+enum Statement {
+    BalanceSheets,
+    CashFlowStatements,
+    IncomeStatements,
+    Ratios,
+}
+
+impl Statement {
+    fn endpoint(self) -> &'static str {
+        match self {
+            Statement::BalanceSheets => "financials.balance_sheets",
+            Statement::CashFlowStatements => "financials.cash_flow_statements",
+            Statement::IncomeStatements => "financials.income_statements",
+            Statement::Ratios => "financials.ratios",
+        }
+    }
+
+    #[cfg(any(feature = "table", feature = "arrow"))]
+    fn schema(self) -> &'static crate::processor::Schema {
+        use crate::processor::schema;
+
+        match self {
+            Statement::BalanceSheets => &schema::BALANCE_SHEETS,
+            Statement::CashFlowStatements => &schema::CASH_FLOW_STATEMENTS,
+            Statement::IncomeStatements => &schema::INCOME_STATEMENTS,
+            Statement::Ratios => &schema::RATIOS,
+        }
+    }
+}
+
 /// Request builder for financial data (balance sheets, cash flow statements, income statements, ratios)
 pub struct Financials<'a, Client: Request, P: Processor = Raw> {
     client: &'a Polygon<Client>,
+    statement: Statement,
     endpoint_path: &'static str,
     /// Ticker symbol to filter by (e.g., "AAPL" for Apple Inc.)
     pub ticker: Option<String>,
@@ -70,32 +105,35 @@ pub struct Financials<'a, Client: Request, P: Processor = Raw> {
     /// Sort order for results (asc or desc)
     pub order: Option<SortOrder>,
     processor: P,
+    bypass_cache: bool,
+    timeout: Option<Duration>,
 }
 
 impl<'a, C: Request> Financials<'a, C, Raw> {
     /// Create a new balance sheets request
     pub fn balance_sheets(client: &'a Polygon<C>) -> Self {
-        Self::new(client, "/vX/reference/financials")
+        Self::new(client, Statement::BalanceSheets, "/vX/reference/financials")
     }
 
     /// Create a new cash flow statements request
     pub fn cash_flow_statements(client: &'a Polygon<C>) -> Self {
-        Self::new(client, "/vX/reference/financials")
+        Self::new(client, Statement::CashFlowStatements, "/vX/reference/financials")
     }
 
     /// Create a new income statements request
     pub fn income_statements(client: &'a Polygon<C>) -> Self {
-        Self::new(client, "/vX/reference/financials")
+        Self::new(client, Statement::IncomeStatements, "/vX/reference/financials")
     }
 
     /// Create a new financial ratios request
     pub fn ratios(client: &'a Polygon<C>) -> Self {
-        Self::new(client, "/vX/reference/financials")
+        Self::new(client, Statement::Ratios, "/vX/reference/financials")
     }
 
-    fn new(client: &'a Polygon<C>, endpoint_path: &'static str) -> Self {
+    fn new(client: &'a Polygon<C>, statement: Statement, endpoint_path: &'static str) -> Self {
         Self {
             client,
+            statement,
             endpoint_path,
             ticker: None,
             cik: None,
@@ -104,6 +142,8 @@ impl<'a, C: Request> Financials<'a, C, Raw> {
             limit: None,
             order: None,
             processor: Raw,
+            bypass_cache: false,
+            timeout: None,
         }
     }
 }
@@ -114,6 +154,30 @@ impl<'a, C: Request, P: Processor + 'a> Financials<'a, C, P> {
         Execute::get(self)
     }
 
+    /// Skip any response cache for this request (see [`Cached`](crate::cache::Cached))
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
+    /// Set the timeout for this request, overriding the client default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Switch to another processor, e.g. your own [`Processor`] implementation
+    pub fn process_with<Q: Processor>(self, processor: Q) -> Financials<'a, C, Q> {
+        WithProcessor::process_with(self, processor)
+    }
+
+    /// Wrap the output in an [`Envelope`](crate::response::envelope::Envelope) with the response's
+    /// status, request ID, counts and `next_url`
+    #[cfg(feature = "serde")]
+    pub fn with_envelope(self) -> Financials<'a, C, crate::processor::Enveloped<P>> {
+        WithProcessor::map_processor(self, crate::processor::Enveloped::new)
+    }
+
     /// Convert to decoded typed output
     ///
     /// The return type T is inferred from context. For example:
@@ -126,34 +190,40 @@ impl<'a, C: Request, P: Processor + 'a> Financials<'a, C, P> {
         T: crate::response::financials::DecodeFinancials,
     {
         let decoder = Decoder::new(T::decoder_fn());
+        self.process_with(decoder)
+    }
 
-        Financials {
-            client: self.client,
-            endpoint_path: self.endpoint_path,
-            ticker: self.ticker,
-            cik: self.cik,
-            filing_date: self.filing_date,
-            period_of_report_date: self.period_of_report_date,
-            limit: self.limit,
-            order: self.order,
-            processor: decoder,
-        }
+    /// Convert to typed output deserialized with serde instead of the `decoder` crate
+    ///
+    /// The return type T is inferred from context, as with [`decoded`](Self::decoded) (e.g.
+    /// `typed::<Vec<BalanceSheet>>()`).
+    #[cfg(feature = "serde")]
+    pub fn typed<T: serde::de::DeserializeOwned>(self) -> Financials<'a, C, crate::processor::Typed<T>> {
+        self.process_with(crate::processor::Typed::new())
     }
 
     /// Convert this request to return results as a Polars DataFrame instead of raw JSON
     #[cfg(feature = "table")]
     pub fn as_dataframe(self) -> Financials<'a, C, crate::processor::Table> {
-        Financials {
-            client: self.client,
-            endpoint_path: self.endpoint_path,
-            ticker: self.ticker,
-            cik: self.cik,
-            filing_date: self.filing_date,
-            period_of_report_date: self.period_of_report_date,
-            limit: self.limit,
-            order: self.order,
-            processor: crate::processor::Table,
-        }
+        let schema = self.statement.schema();
+        self.process_with(crate::processor::Table::new(schema))
+    }
+
+    /// Convert this request to return results as an Arrow RecordBatch instead of raw JSON
+    #[cfg(feature = "arrow")]
+    pub fn as_record_batch(self) -> Financials<'a, C, crate::processor::Arrow> {
+        let schema = self.statement.schema();
+        self.process_with(crate::processor::Arrow::new(schema))
+    }
+
+    /// Convert to CSV output, with nested fields flattened into dotted columns
+    pub fn as_csv(self) -> Financials<'a, C, crate::processor::Csv> {
+        self.process_with(crate::processor::Csv::new("results"))
+    }
+
+    /// Convert to newline-delimited JSON output, one flattened object per result
+    pub fn as_ndjson(self) -> Financials<'a, C, crate::processor::NdJson> {
+        self.process_with(crate::processor::NdJson::new("results"))
     }
 
     /// Set a custom decoder function to return typed data instead of raw JSON
@@ -164,17 +234,7 @@ impl<'a, C: Request, P: Processor + 'a> Financials<'a, C, P> {
         self,
         decoder_fn: impl Fn(decoder::Value) -> decoder::Result<T> + Send + Sync + 'static,
     ) -> Financials<'a, C, Decoder<T>> {
-        Financials {
-            client: self.client,
-            endpoint_path: self.endpoint_path,
-            ticker: self.ticker,
-            cik: self.cik,
-            filing_date: self.filing_date,
-            period_of_report_date: self.period_of_report_date,
-            limit: self.limit,
-            order: self.order,
-            processor: Decoder::new(decoder_fn),
-        }
+        self.process_with(Decoder::new(decoder_fn))
     }
 
     /// Filter by ticker symbol (e.g., "AAPL")
@@ -190,14 +250,14 @@ impl<'a, C: Request, P: Processor + 'a> Financials<'a, C, P> {
     }
 
     /// Filter by filing date (YYYY-MM-DD)
-    pub fn filing_date(mut self, date: impl Into<String>) -> Self {
-        self.filing_date = Some(date.into());
+    pub fn filing_date(mut self, date: impl DateParam) -> Self {
+        self.filing_date = Some(date.into_date());
         self
     }
 
     /// Filter by period of report date (YYYY-MM-DD)
-    pub fn period_of_report_date(mut self, period: impl Into<String>) -> Self {
-        self.period_of_report_date = Some(period.into());
+    pub fn period_of_report_date(mut self, period: impl DateParam) -> Self {
+        self.period_of_report_date = Some(period.into_date());
         self
     }
 
@@ -214,18 +274,35 @@ impl<'a, C: Request, P: Processor + 'a> Financials<'a, C, P> {
     }
 }
 
+impl<'a, C: Request, P: Processor + 'a> WithProcessor for Financials<'a, C, P> {
+    type Processor = P;
+    type With<Q: Processor> = Financials<'a, C, Q>;
+
+    fn map_processor<Q: Processor>(self, f: impl FnOnce(P) -> Q) -> Financials<'a, C, Q> {
+        Financials {
+            client: self.client,
+            statement: self.statement,
+            endpoint_path: self.endpoint_path,
+            ticker: self.ticker,
+            cik: self.cik,
+            filing_date: self.filing_date,
+            period_of_report_date: self.period_of_report_date,
+            limit: self.limit,
+            order: self.order,
+            processor: f(self.processor),
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
+        }
+    }
+}
+
 impl<'a, C: Request, P: Processor + 'a> Execute for Financials<'a, C, P> {
     type Output = P::Output;
 
     #[allow(refining_impl_trait_reachable)]
     async fn get(self) -> Result<P::Output> {
-        let api_key = self
-            .client
-            .api_key()
-            .ok_or_else(|| crate::error::Error::Custom("API key not set".to_string()))?;
-
         let mut path = self.endpoint_path.to_string();
-        let mut params = vec![format!("apiKey={}", api_key)];
+        let mut params = Vec::new();
 
         if let Some(t) = self.ticker {
             params.push(format!("ticker={t}"));
@@ -243,14 +320,17 @@ impl<'a, C: Request, P: Processor + 'a> Execute for Financials<'a, C, P> {
             params.push(format!("limit={l}"));
         }
         if let Some(o) = self.order {
-            params.push(format!("order={}", format!("{o:?}").to_lowercase()));
+            params.push(format!("order={o}"));
         }
 
-        path.push('?');
-        path.push_str(&params.join("&"));
-        let url = format!("https://api.polygon.io{path}");
-        let response = self.client.client().get(&url).await;
-        self.processor.process(response)
+        if !params.is_empty() {
+            path.push('?');
+            path.push_str(&params.join("&"));
+        }
+        let mut request = self.client.request(self.statement.endpoint(), &path)?;
+        request.bypass_cache = self.bypass_cache;
+        request.timeout = self.timeout.or(request.timeout);
+        self.client.execute(request, &self.processor).await
     }
 }
 
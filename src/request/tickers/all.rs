@@ -1,12 +1,14 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
 use crate::client::Polygon;
 use crate::error::Result;
 use crate::execute::Execute;
-use crate::processor::{Decoder, Processor, Raw};
+use crate::processor::{Decoder, Processor, Raw, WithProcessor};
 use crate::request::Request;
-use crate::request::common::{Limit, SortOrder};
+use crate::request::common::{DateParam, Limit, SortOrder, encode};
 use crate::response::ticker::Ticker;
 
 /// Request builder for querying all tickers
@@ -14,19 +16,41 @@ pub struct All<'a, Client: Request, P: Processor = Raw> {
     client: &'a Polygon<Client>,
     /// Ticker symbol to filter by (e.g., "AAPL" for Apple Inc.)
     pub ticker: Option<String>,
+    /// Return tickers greater than or equal to this symbol
+    pub ticker_gte: Option<String>,
+    /// Return tickers greater than this symbol
+    pub ticker_gt: Option<String>,
+    /// Return tickers less than or equal to this symbol
+    pub ticker_lte: Option<String>,
+    /// Return tickers less than this symbol
+    pub ticker_lt: Option<String>,
     /// Type of ticker (e.g., "CS" for common stock, "ETF" for exchange-traded fund)
     pub ticker_type: Option<String>,
     /// Market type (e.g., "stocks", "crypto", "fx")
     pub market: Option<String>,
     /// Primary exchange MIC (Market Identifier Code) according to ISO 10383
     pub exchange: Option<String>,
+    /// CUSIP code of the asset
+    pub cusip: Option<String>,
+    /// Central Index Key (CIK) assigned by the SEC to identify the company
+    pub cik: Option<String>,
+    /// Point-in-time date to list tickers that were available on (YYYY-MM-DD)
+    pub date: Option<String>,
+    /// Search terms matched against the ticker and company name
+    pub search: Option<String>,
+    /// Whether to return actively traded tickers (default: true)
+    pub active: Option<bool>,
     /// Maximum number of results to return (default: 100, max: 1000)
     pub limit: Option<u32>,
     /// Field to sort by
     pub sort: Option<String>,
     /// Sort order for results (asc or desc)
     pub order: Option<SortOrder>,
+    /// Pagination cursor taken from the `next_url` of a previous response
+    pub cursor: Option<String>,
     processor: P,
+    bypass_cache: bool,
+    timeout: Option<Duration>,
 }
 
 impl<'a, C: Request> All<'a, C, Raw> {
@@ -35,13 +59,25 @@ impl<'a, C: Request> All<'a, C, Raw> {
         Self {
             client,
             ticker: None,
+            ticker_gte: None,
+            ticker_gt: None,
+            ticker_lte: None,
+            ticker_lt: None,
             ticker_type: None,
             market: None,
             exchange: None,
+            cusip: None,
+            cik: None,
+            date: None,
+            search: None,
+            active: None,
             limit: None,
             sort: None,
             order: None,
+            cursor: None,
             processor: Raw,
+            bypass_cache: false,
+            timeout: None,
         }
     }
 }
@@ -52,38 +88,63 @@ impl<'a, C: Request, P: Processor + 'a> All<'a, C, P> {
         Execute::get(self)
     }
 
+    /// Skip any response cache for this request (see [`Cached`](crate::cache::Cached))
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
+    /// Set the timeout for this request, overriding the client default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Switch to another processor, e.g. your own [`Processor`] implementation
+    pub fn process_with<Q: Processor>(self, processor: Q) -> All<'a, C, Q> {
+        WithProcessor::process_with(self, processor)
+    }
+
+    /// Wrap the output in an [`Envelope`](crate::response::envelope::Envelope) with the response's
+    /// status, request ID, counts and `next_url`
+    #[cfg(feature = "serde")]
+    pub fn with_envelope(self) -> All<'a, C, crate::processor::Enveloped<P>> {
+        WithProcessor::map_processor(self, crate::processor::Enveloped::new)
+    }
+
     /// Convert this request to return results as a Polars DataFrame instead of raw JSON
     #[cfg(feature = "table")]
     pub fn as_dataframe(self) -> All<'a, C, crate::processor::Table> {
-        All {
-            client: self.client,
-            ticker: self.ticker,
-            ticker_type: self.ticker_type,
-            market: self.market,
-            exchange: self.exchange,
-            limit: self.limit,
-            sort: self.sort,
-            order: self.order,
-            processor: crate::processor::Table,
-        }
+        self.process_with(crate::processor::Table::new(&crate::processor::schema::TICKERS))
+    }
+
+    /// Convert this request to return results as an Arrow RecordBatch instead of raw JSON
+    #[cfg(feature = "arrow")]
+    pub fn as_record_batch(self) -> All<'a, C, crate::processor::Arrow> {
+        self.process_with(crate::processor::Arrow::new(&crate::processor::schema::TICKERS))
+    }
+
+    /// Convert to CSV output, with nested fields flattened into dotted columns
+    pub fn as_csv(self) -> All<'a, C, crate::processor::Csv> {
+        self.process_with(crate::processor::Csv::new("results"))
+    }
+
+    /// Convert to newline-delimited JSON output, one flattened object per result
+    pub fn as_ndjson(self) -> All<'a, C, crate::processor::NdJson> {
+        self.process_with(crate::processor::NdJson::new("results"))
     }
 
     /// Convert to decoded typed output (`Vec<`[`Ticker`]`>`)
     pub fn decoded(self) -> All<'a, C, Decoder<Vec<Ticker>>> {
         use crate::rest::decoded::tickers::decode;
         let decoder = Decoder::new(decode::all);
+        self.process_with(decoder)
+    }
 
-        All {
-            client: self.client,
-            ticker: self.ticker,
-            ticker_type: self.ticker_type,
-            market: self.market,
-            exchange: self.exchange,
-            limit: self.limit,
-            sort: self.sort,
-            order: self.order,
-            processor: decoder,
-        }
+    /// Convert to typed output (`Vec<`[`Ticker`]`>`) deserialized with serde instead of the `decoder` crate
+    #[cfg(feature = "serde")]
+    pub fn typed(self) -> All<'a, C, crate::processor::Typed<Vec<Ticker>>> {
+        self.process_with(crate::processor::Typed::new())
     }
 
     /// Set a custom decoder function to convert JSON to typed data
@@ -91,17 +152,7 @@ impl<'a, C: Request, P: Processor + 'a> All<'a, C, P> {
         self,
         decoder_fn: impl Fn(decoder::Value) -> decoder::Result<T> + Send + Sync + 'static,
     ) -> All<'a, C, Decoder<T>> {
-        All {
-            client: self.client,
-            ticker: self.ticker,
-            ticker_type: self.ticker_type,
-            market: self.market,
-            exchange: self.exchange,
-            limit: self.limit,
-            sort: self.sort,
-            order: self.order,
-            processor: Decoder::new(decoder_fn),
-        }
+        self.process_with(Decoder::new(decoder_fn))
     }
 
     /// Filter by ticker symbol (e.g., "AAPL")
@@ -110,6 +161,30 @@ impl<'a, C: Request, P: Processor + 'a> All<'a, C, P> {
         self
     }
 
+    /// Return tickers alphabetically greater than or equal to the given symbol
+    pub fn ticker_gte(mut self, ticker: impl Into<String>) -> Self {
+        self.ticker_gte = Some(ticker.into());
+        self
+    }
+
+    /// Return tickers alphabetically greater than the given symbol
+    pub fn ticker_gt(mut self, ticker: impl Into<String>) -> Self {
+        self.ticker_gt = Some(ticker.into());
+        self
+    }
+
+    /// Return tickers alphabetically less than or equal to the given symbol
+    pub fn ticker_lte(mut self, ticker: impl Into<String>) -> Self {
+        self.ticker_lte = Some(ticker.into());
+        self
+    }
+
+    /// Return tickers alphabetically less than the given symbol
+    pub fn ticker_lt(mut self, ticker: impl Into<String>) -> Self {
+        self.ticker_lt = Some(ticker.into());
+        self
+    }
+
     /// Filter by ticker type (e.g., "CS" for common stock)
     pub fn ticker_type(mut self, ticker_type: impl Into<String>) -> Self {
         self.ticker_type = Some(ticker_type.into());
@@ -128,6 +203,38 @@ impl<'a, C: Request, P: Processor + 'a> All<'a, C, P> {
         self
     }
 
+    /// Filter by CUSIP code
+    pub fn cusip(mut self, cusip: impl Into<String>) -> Self {
+        self.cusip = Some(cusip.into());
+        self
+    }
+
+    /// Filter by Central Index Key (CIK)
+    pub fn cik(mut self, cik: impl Into<String>) -> Self {
+        self.cik = Some(cik.into());
+        self
+    }
+
+    /// List tickers as they were on the given date (YYYY-MM-DD)
+    ///
+    /// Combine with `.active(false)` to include tickers that have since been delisted.
+    pub fn date(mut self, date: impl DateParam) -> Self {
+        self.date = Some(date.into_date());
+        self
+    }
+
+    /// Search for terms within the ticker and/or company name
+    pub fn search(mut self, search: impl Into<String>) -> Self {
+        self.search = Some(search.into());
+        self
+    }
+
+    /// Filter by whether tickers are actively traded (`false` returns delisted tickers)
+    pub fn active(mut self, active: bool) -> Self {
+        self.active = Some(active);
+        self
+    }
+
     /// Set the maximum number of results to return (default: 100, max: 1000)
     pub fn limit(mut self, limit: impl Into<Limit>) -> Self {
         self.limit = limit.into().into();
@@ -145,6 +252,43 @@ impl<'a, C: Request, P: Processor + 'a> All<'a, C, P> {
         self.order = Some(order);
         self
     }
+
+    /// Continue from a pagination cursor (the `cursor` query parameter of a response's `next_url`)
+    pub fn cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+}
+
+impl<'a, C: Request, P: Processor + 'a> WithProcessor for All<'a, C, P> {
+    type Processor = P;
+    type With<Q: Processor> = All<'a, C, Q>;
+
+    fn map_processor<Q: Processor>(self, f: impl FnOnce(P) -> Q) -> All<'a, C, Q> {
+        All {
+            client: self.client,
+            ticker: self.ticker,
+            ticker_gte: self.ticker_gte,
+            ticker_gt: self.ticker_gt,
+            ticker_lte: self.ticker_lte,
+            ticker_lt: self.ticker_lt,
+            ticker_type: self.ticker_type,
+            market: self.market,
+            exchange: self.exchange,
+            cusip: self.cusip,
+            cik: self.cik,
+            date: self.date,
+            search: self.search,
+            active: self.active,
+            limit: self.limit,
+            sort: self.sort,
+            order: self.order,
+            cursor: self.cursor,
+            processor: f(self.processor),
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
+        }
+    }
 }
 
 impl<'a, C: Request, P: Processor + 'a> Execute for All<'a, C, P> {
@@ -152,41 +296,70 @@ impl<'a, C: Request, P: Processor + 'a> Execute for All<'a, C, P> {
 
     #[allow(refining_impl_trait_reachable)]
     async fn get(self) -> Result<P::Output> {
-        let api_key = self
-            .client
-            .api_key()
-            .ok_or_else(|| crate::error::Error::Custom("API key not set".to_string()))?;
-
         let mut path = "/v3/reference/tickers".to_string();
-        let mut params = vec![format!("apiKey={}", api_key)];
+        let mut params = Vec::new();
 
         if let Some(t) = self.ticker {
-            params.push(format!("ticker={t}"));
+            params.push(format!("ticker={}", encode(&t)));
+        }
+        if let Some(t) = self.ticker_gte {
+            params.push(format!("ticker.gte={}", encode(&t)));
+        }
+        if let Some(t) = self.ticker_gt {
+            params.push(format!("ticker.gt={}", encode(&t)));
+        }
+        if let Some(t) = self.ticker_lte {
+            params.push(format!("ticker.lte={}", encode(&t)));
+        }
+        if let Some(t) = self.ticker_lt {
+            params.push(format!("ticker.lt={}", encode(&t)));
         }
         if let Some(tt) = self.ticker_type {
-            params.push(format!("type={tt}"));
+            params.push(format!("type={}", encode(&tt)));
         }
         if let Some(m) = self.market {
-            params.push(format!("market={m}"));
+            params.push(format!("market={}", encode(&m)));
         }
         if let Some(e) = self.exchange {
-            params.push(format!("exchange={e}"));
+            params.push(format!("exchange={}", encode(&e)));
+        }
+        if let Some(c) = self.cusip {
+            params.push(format!("cusip={}", encode(&c)));
+        }
+        if let Some(c) = self.cik {
+            params.push(format!("cik={}", encode(&c)));
+        }
+        if let Some(d) = self.date {
+            params.push(format!("date={}", encode(&d)));
+        }
+        if let Some(s) = self.search {
+            params.push(format!("search={}", encode(&s)));
+        }
+        if let Some(a) = self.active {
+            params.push(format!("active={a}"));
         }
         if let Some(l) = self.limit {
             params.push(format!("limit={l}"));
         }
         if let Some(s) = self.sort {
-            params.push(format!("sort={s}"));
+            params.push(format!("sort={}", encode(&s)));
         }
         if let Some(o) = self.order {
-            params.push(format!("order={}", format!("{o:?}").to_lowercase()));
+            params.push(format!("order={o}"));
+        }
+        // Taken from `next_url`, so already encoded
+        if let Some(c) = self.cursor {
+            params.push(format!("cursor={c}"));
         }
 
-        path.push('?');
-        path.push_str(&params.join("&"));
-        let url = format!("https://api.polygon.io{path}");
-        let response = self.client.client().get(&url).await;
-        self.processor.process(response)
+        if !params.is_empty() {
+            path.push('?');
+            path.push_str(&params.join("&"));
+        }
+        let mut request = self.client.request("tickers.all", &path)?;
+        request.bypass_cache = self.bypass_cache;
+        request.timeout = self.timeout.or(request.timeout);
+        self.client.execute(request, &self.processor).await
     }
 }
 
@@ -197,6 +370,22 @@ pub struct Params {
     /// Ticker symbol to filter by (e.g., "AAPL" for Apple Inc.)
     pub ticker: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "ticker.gte")]
+    /// Return tickers greater than or equal to this symbol
+    pub ticker_gte: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "ticker.gt")]
+    /// Return tickers greater than this symbol
+    pub ticker_gt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "ticker.lte")]
+    /// Return tickers less than or equal to this symbol
+    pub ticker_lte: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "ticker.lt")]
+    /// Return tickers less than this symbol
+    pub ticker_lt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "type")]
     /// Type of ticker (e.g., "CS" for common stock, "ETF" for exchange-traded fund)
     pub ticker_type: Option<String>,
@@ -207,6 +396,21 @@ pub struct Params {
     /// Primary exchange MIC (Market Identifier Code) according to ISO 10383
     pub exchange: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    /// CUSIP code of the asset
+    pub cusip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Central Index Key (CIK) assigned by the SEC to identify the company
+    pub cik: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Point-in-time date to list tickers that were available on (YYYY-MM-DD)
+    pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Search terms matched against the ticker and company name
+    pub search: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Whether to return actively traded tickers (default: true)
+    pub active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     /// Maximum number of results to return (default: 100, max: 1000)
     pub limit: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -215,4 +419,44 @@ pub struct Params {
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Sort order for results (asc or desc)
     pub order: Option<SortOrder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Pagination cursor taken from the `next_url` of a previous response
+    pub cursor: Option<String>,
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::client::Polygon;
+    use crate::request::common::SortOrder;
+    use crate::rest;
+    use crate::testing::{Mock, MockClient};
+
+    #[tokio::test]
+    async fn test_query() {
+        let mock = MockClient::new();
+        mock.register(Mock::get("/v3/reference/tickers").respond(200, "{}"));
+        let client = Polygon::default().with_client(mock.clone()).with_key("key");
+
+        rest::tickers::all(&client)
+            .search("AT&T")
+            .ticker_gte("BRK.A")
+            .cusip("037833 100")
+            .date("2024-01-02")
+            .active(true)
+            .order(SortOrder::Asc)
+            .cursor("YXA9MTAw%3D")
+            .get()
+            .await
+            .unwrap();
+
+        let request = &mock.requests()[0];
+        assert_eq!(request.query("search"), Some("AT%26T"));
+        assert_eq!(request.query("ticker.gte"), Some("BRK.A"));
+        assert_eq!(request.query("cusip"), Some("037833%20100"));
+        assert_eq!(request.query("date"), Some("2024-01-02"));
+        assert_eq!(request.query("active"), Some("true"));
+        assert_eq!(request.query("order"), Some("asc"));
+        assert_eq!(request.query("cursor"), Some("YXA9MTAw%3D"));
+        assert_eq!(request.params.len(), 7);
+    }
 }
@@ -1,11 +1,14 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
 use crate::client::Polygon;
 use crate::error::Result;
 use crate::execute::Execute;
-use crate::processor::{Decoder, Processor, Raw};
+use crate::processor::{Decoder, Processor, Raw, WithProcessor};
 use crate::request::Request;
+use crate::request::common::DateParam;
 use crate::response::ticker::Ticker;
 
 /// Request builder for ticker details (overview)
@@ -16,6 +19,8 @@ pub struct Details<'a, Client: Request, P: Processor = Raw> {
     /// Date to retrieve details for (YYYY-MM-DD)
     pub date: Option<String>,
     processor: P,
+    bypass_cache: bool,
+    timeout: Option<Duration>,
 }
 
 impl<'a, C: Request> Details<'a, C, Raw> {
@@ -26,6 +31,8 @@ impl<'a, C: Request> Details<'a, C, Raw> {
             ticker: ticker.into(),
             date: None,
             processor: Raw,
+            bypass_cache: false,
+            timeout: None,
         }
     }
 }
@@ -36,28 +43,63 @@ impl<'a, C: Request, P: Processor + 'a> Details<'a, C, P> {
         Execute::get(self)
     }
 
+    /// Skip any response cache for this request (see [`Cached`](crate::cache::Cached))
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
+    /// Set the timeout for this request, overriding the client default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Switch to another processor, e.g. your own [`Processor`] implementation
+    pub fn process_with<Q: Processor>(self, processor: Q) -> Details<'a, C, Q> {
+        WithProcessor::process_with(self, processor)
+    }
+
+    /// Wrap the output in an [`Envelope`](crate::response::envelope::Envelope) with the response's
+    /// status, request ID, counts and `next_url`
+    #[cfg(feature = "serde")]
+    pub fn with_envelope(self) -> Details<'a, C, crate::processor::Enveloped<P>> {
+        WithProcessor::map_processor(self, crate::processor::Enveloped::new)
+    }
+
     /// Convert this request to return results as a Polars DataFrame instead of raw JSON
     #[cfg(feature = "table")]
     pub fn as_dataframe(self) -> Details<'a, C, crate::processor::Table> {
-        Details {
-            client: self.client,
-            ticker: self.ticker,
-            date: self.date,
-            processor: crate::processor::Table,
-        }
+        self.process_with(crate::processor::Table::new(&crate::processor::schema::TICKER_DETAILS))
+    }
+
+    /// Convert this request to return results as an Arrow RecordBatch instead of raw JSON
+    #[cfg(feature = "arrow")]
+    pub fn as_record_batch(self) -> Details<'a, C, crate::processor::Arrow> {
+        self.process_with(crate::processor::Arrow::new(&crate::processor::schema::TICKER_DETAILS))
+    }
+
+    /// Convert to CSV output, with nested fields flattened into dotted columns
+    pub fn as_csv(self) -> Details<'a, C, crate::processor::Csv> {
+        self.process_with(crate::processor::Csv::new("results"))
+    }
+
+    /// Convert to newline-delimited JSON output, one flattened object per result
+    pub fn as_ndjson(self) -> Details<'a, C, crate::processor::NdJson> {
+        self.process_with(crate::processor::NdJson::new("results"))
     }
 
     /// Convert to decoded typed output (Ticker)
     pub fn decoded(self) -> Details<'a, C, Decoder<Ticker>> {
         use crate::rest::decoded::tickers::decode;
         let decoder = Decoder::new(decode::details);
+        self.process_with(decoder)
+    }
 
-        Details {
-            client: self.client,
-            ticker: self.ticker,
-            date: self.date,
-            processor: decoder,
-        }
+    /// Convert to typed output ([`Ticker`]) deserialized with serde instead of the `decoder` crate
+    #[cfg(feature = "serde")]
+    pub fn typed(self) -> Details<'a, C, crate::processor::Typed<Ticker>> {
+        self.process_with(crate::processor::Typed::new())
     }
 
     /// Set a custom decoder function to convert JSON to typed data
@@ -65,19 +107,30 @@ impl<'a, C: Request, P: Processor + 'a> Details<'a, C, P> {
         self,
         decoder_fn: impl Fn(decoder::Value) -> decoder::Result<T> + Send + Sync + 'static,
     ) -> Details<'a, C, Decoder<T>> {
+        self.process_with(Decoder::new(decoder_fn))
+    }
+
+    /// Set the date to retrieve details for
+    pub fn date(mut self, d: impl DateParam) -> Self {
+        self.date = Some(d.into_date());
+        self
+    }
+}
+
+impl<'a, C: Request, P: Processor + 'a> WithProcessor for Details<'a, C, P> {
+    type Processor = P;
+    type With<Q: Processor> = Details<'a, C, Q>;
+
+    fn map_processor<Q: Processor>(self, f: impl FnOnce(P) -> Q) -> Details<'a, C, Q> {
         Details {
             client: self.client,
             ticker: self.ticker,
             date: self.date,
-            processor: Decoder::new(decoder_fn),
+            processor: f(self.processor),
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
         }
     }
-
-    /// Set the date to retrieve details for
-    pub fn date(mut self, d: impl Into<String>) -> Self {
-        self.date = Some(d.into());
-        self
-    }
 }
 
 impl<'a, C: Request, P: Processor + 'a> Execute for Details<'a, C, P> {
@@ -85,23 +138,21 @@ impl<'a, C: Request, P: Processor + 'a> Execute for Details<'a, C, P> {
 
     #[allow(refining_impl_trait_reachable)]
     async fn get(self) -> Result<P::Output> {
-        let api_key = self
-            .client
-            .api_key()
-            .ok_or_else(|| crate::error::Error::Custom("API key not set".to_string()))?;
-
         let mut path = format!("/v3/reference/tickers/{}", self.ticker);
-        let mut params = vec![format!("apiKey={}", api_key)];
+        let mut params = Vec::new();
 
         if let Some(d) = self.date {
             params.push(format!("date={d}"));
         }
 
-        path.push('?');
-        path.push_str(&params.join("&"));
-        let url = format!("https://api.polygon.io{path}");
-        let response = self.client.client().get(&url).await;
-        self.processor.process(response)
+        if !params.is_empty() {
+            path.push('?');
+            path.push_str(&params.join("&"));
+        }
+        let mut request = self.client.request("tickers.details", &path)?;
+        request.bypass_cache = self.bypass_cache;
+        request.timeout = self.timeout.or(request.timeout);
+        self.client.execute(request, &self.processor).await
     }
 }
 
@@ -1,10 +1,12 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
 use crate::client::Polygon;
 use crate::error::Result;
 use crate::execute::Execute;
-use crate::processor::{Decoder, Processor, Raw};
+use crate::processor::{Decoder, Processor, Raw, WithProcessor};
 use crate::request::Request;
 use crate::response::ticker::TickerChangeResults;
 
@@ -16,6 +18,8 @@ pub struct Events<'a, Client: Request, P: Processor = Raw> {
     /// Event types to filter by (comma-separated)
     pub types: Option<String>,
     processor: P,
+    bypass_cache: bool,
+    timeout: Option<Duration>,
 }
 
 impl<'a, C: Request> Events<'a, C, Raw> {
@@ -26,6 +30,8 @@ impl<'a, C: Request> Events<'a, C, Raw> {
             ticker: ticker.into(),
             types: None,
             processor: Raw,
+            bypass_cache: false,
+            timeout: None,
         }
     }
 }
@@ -36,28 +42,63 @@ impl<'a, C: Request, P: Processor + 'a> Events<'a, C, P> {
         Execute::get(self)
     }
 
+    /// Skip any response cache for this request (see [`Cached`](crate::cache::Cached))
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
+    /// Set the timeout for this request, overriding the client default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Switch to another processor, e.g. your own [`Processor`] implementation
+    pub fn process_with<Q: Processor>(self, processor: Q) -> Events<'a, C, Q> {
+        WithProcessor::process_with(self, processor)
+    }
+
+    /// Wrap the output in an [`Envelope`](crate::response::envelope::Envelope) with the response's
+    /// status, request ID, counts and `next_url`
+    #[cfg(feature = "serde")]
+    pub fn with_envelope(self) -> Events<'a, C, crate::processor::Enveloped<P>> {
+        WithProcessor::map_processor(self, crate::processor::Enveloped::new)
+    }
+
     /// Convert this request to return results as a Polars DataFrame instead of raw JSON
     #[cfg(feature = "table")]
     pub fn as_dataframe(self) -> Events<'a, C, crate::processor::Table> {
-        Events {
-            client: self.client,
-            ticker: self.ticker,
-            types: self.types,
-            processor: crate::processor::Table,
-        }
+        self.process_with(crate::processor::Table::new(&crate::processor::schema::TICKER_EVENTS))
+    }
+
+    /// Convert this request to return results as an Arrow RecordBatch instead of raw JSON
+    #[cfg(feature = "arrow")]
+    pub fn as_record_batch(self) -> Events<'a, C, crate::processor::Arrow> {
+        self.process_with(crate::processor::Arrow::new(&crate::processor::schema::TICKER_EVENTS))
+    }
+
+    /// Convert to CSV output, one row per event with nested fields flattened into dotted columns
+    pub fn as_csv(self) -> Events<'a, C, crate::processor::Csv> {
+        self.process_with(crate::processor::Csv::new("results.events"))
+    }
+
+    /// Convert to newline-delimited JSON output, one flattened object per event
+    pub fn as_ndjson(self) -> Events<'a, C, crate::processor::NdJson> {
+        self.process_with(crate::processor::NdJson::new("results.events"))
     }
 
     /// Convert to decoded typed output (TickerChangeResults)
     pub fn decoded(self) -> Events<'a, C, Decoder<TickerChangeResults>> {
         use crate::rest::decoded::tickers::decode;
         let decoder = Decoder::new(decode::events);
+        self.process_with(decoder)
+    }
 
-        Events {
-            client: self.client,
-            ticker: self.ticker,
-            types: self.types,
-            processor: decoder,
-        }
+    /// Convert to typed output ([`TickerChangeResults`]) deserialized with serde instead of the `decoder` crate
+    #[cfg(feature = "serde")]
+    pub fn typed(self) -> Events<'a, C, crate::processor::Typed<TickerChangeResults>> {
+        self.process_with(crate::processor::Typed::new())
     }
 
     /// Set a custom decoder function to convert JSON to typed data
@@ -65,12 +106,7 @@ impl<'a, C: Request, P: Processor + 'a> Events<'a, C, P> {
         self,
         decoder_fn: impl Fn(decoder::Value) -> decoder::Result<T> + Send + Sync + 'static,
     ) -> Events<'a, C, Decoder<T>> {
-        Events {
-            client: self.client,
-            ticker: self.ticker,
-            types: self.types,
-            processor: Decoder::new(decoder_fn),
-        }
+        self.process_with(Decoder::new(decoder_fn))
     }
 
     /// Set the event types to filter by (comma-separated)
@@ -80,28 +116,42 @@ impl<'a, C: Request, P: Processor + 'a> Events<'a, C, P> {
     }
 }
 
+impl<'a, C: Request, P: Processor + 'a> WithProcessor for Events<'a, C, P> {
+    type Processor = P;
+    type With<Q: Processor> = Events<'a, C, Q>;
+
+    fn map_processor<Q: Processor>(self, f: impl FnOnce(P) -> Q) -> Events<'a, C, Q> {
+        Events {
+            client: self.client,
+            ticker: self.ticker,
+            types: self.types,
+            processor: f(self.processor),
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
+        }
+    }
+}
+
 impl<'a, C: Request, P: Processor + 'a> Execute for Events<'a, C, P> {
     type Output = P::Output;
 
     #[allow(refining_impl_trait_reachable)]
     async fn get(self) -> Result<P::Output> {
-        let api_key = self
-            .client
-            .api_key()
-            .ok_or_else(|| crate::error::Error::Custom("API key not set".to_string()))?;
-
         let mut path = format!("/vX/reference/tickers/{}/events", self.ticker);
-        let mut params = vec![format!("apiKey={}", api_key)];
+        let mut params = Vec::new();
 
         if let Some(t) = self.types {
             params.push(format!("types={t}"));
         }
 
-        path.push('?');
-        path.push_str(&params.join("&"));
-        let url = format!("https://api.polygon.io{path}");
-        let response = self.client.client().get(&url).await;
-        self.processor.process(response)
+        if !params.is_empty() {
+            path.push('?');
+            path.push_str(&params.join("&"));
+        }
+        let mut request = self.client.request("tickers.events", &path)?;
+        request.bypass_cache = self.bypass_cache;
+        request.timeout = self.timeout.or(request.timeout);
+        self.client.execute(request, &self.processor).await
     }
 }
 
@@ -1,12 +1,14 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
 use crate::client::Polygon;
 use crate::error::Result;
 use crate::execute::Execute;
-use crate::processor::{Decoder, Processor, Raw};
+use crate::processor::{Decoder, Processor, Raw, WithProcessor};
 use crate::request::Request;
-use crate::request::common::{Limit, SortOrder};
+use crate::request::common::{Limit, SortOrder, encode};
 use crate::response::ticker::TickerNews;
 
 /// Request builder for news articles
@@ -14,11 +16,27 @@ pub struct News<'a, Client: Request, P: Processor = Raw> {
     client: &'a Polygon<Client>,
     /// Ticker symbol to filter news by (e.g., "AAPL" for Apple Inc.)
     pub ticker: Option<String>,
+    /// Only return articles published on this date or timestamp
+    pub published_utc: Option<String>,
+    /// Only return articles published on or after this date or timestamp
+    pub published_utc_gte: Option<String>,
+    /// Only return articles published after this date or timestamp
+    pub published_utc_gt: Option<String>,
+    /// Only return articles published on or before this date or timestamp
+    pub published_utc_lte: Option<String>,
+    /// Only return articles published before this date or timestamp
+    pub published_utc_lt: Option<String>,
     /// Maximum number of results to return
     pub limit: Option<u32>,
     /// Sort order for results (asc or desc)
     pub order: Option<SortOrder>,
+    /// Field to sort by (e.g., "published_utc")
+    pub sort: Option<String>,
+    /// Pagination cursor taken from the `next_url` of a previous response
+    pub cursor: Option<String>,
     processor: P,
+    bypass_cache: bool,
+    timeout: Option<Duration>,
 }
 
 impl<'a, C: Request> News<'a, C, Raw> {
@@ -27,9 +45,18 @@ impl<'a, C: Request> News<'a, C, Raw> {
         Self {
             client,
             ticker: None,
+            published_utc: None,
+            published_utc_gte: None,
+            published_utc_gt: None,
+            published_utc_lte: None,
+            published_utc_lt: None,
             limit: None,
             order: None,
+            sort: None,
+            cursor: None,
             processor: Raw,
+            bypass_cache: false,
+            timeout: None,
         }
     }
 }
@@ -40,30 +67,63 @@ impl<'a, C: Request, P: Processor + 'a> News<'a, C, P> {
         Execute::get(self)
     }
 
+    /// Skip any response cache for this request (see [`Cached`](crate::cache::Cached))
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
+    /// Set the timeout for this request, overriding the client default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Switch to another processor, e.g. your own [`Processor`] implementation
+    pub fn process_with<Q: Processor>(self, processor: Q) -> News<'a, C, Q> {
+        WithProcessor::process_with(self, processor)
+    }
+
+    /// Wrap the output in an [`Envelope`](crate::response::envelope::Envelope) with the response's
+    /// status, request ID, counts and `next_url`
+    #[cfg(feature = "serde")]
+    pub fn with_envelope(self) -> News<'a, C, crate::processor::Enveloped<P>> {
+        WithProcessor::map_processor(self, crate::processor::Enveloped::new)
+    }
+
     /// Convert this request to return results as a Polars DataFrame instead of raw JSON
     #[cfg(feature = "table")]
     pub fn as_dataframe(self) -> News<'a, C, crate::processor::Table> {
-        News {
-            client: self.client,
-            ticker: self.ticker,
-            limit: self.limit,
-            order: self.order,
-            processor: crate::processor::Table,
-        }
+        self.process_with(crate::processor::Table::new(&crate::processor::schema::NEWS))
+    }
+
+    /// Convert this request to return results as an Arrow RecordBatch instead of raw JSON
+    #[cfg(feature = "arrow")]
+    pub fn as_record_batch(self) -> News<'a, C, crate::processor::Arrow> {
+        self.process_with(crate::processor::Arrow::new(&crate::processor::schema::NEWS))
+    }
+
+    /// Convert to CSV output, with nested fields flattened into dotted columns
+    pub fn as_csv(self) -> News<'a, C, crate::processor::Csv> {
+        self.process_with(crate::processor::Csv::new("results"))
+    }
+
+    /// Convert to newline-delimited JSON output, one flattened object per result
+    pub fn as_ndjson(self) -> News<'a, C, crate::processor::NdJson> {
+        self.process_with(crate::processor::NdJson::new("results"))
     }
 
     /// Convert to decoded typed output (`Vec<`[`TickerNews`]`>`)
     pub fn decoded(self) -> News<'a, C, Decoder<Vec<TickerNews>>> {
         use crate::rest::decoded::tickers::decode;
         let decoder = Decoder::new(decode::news);
+        self.process_with(decoder)
+    }
 
-        News {
-            client: self.client,
-            ticker: self.ticker,
-            limit: self.limit,
-            order: self.order,
-            processor: decoder,
-        }
+    /// Convert to typed output (`Vec<`[`TickerNews`]`>`) deserialized with serde instead of the `decoder` crate
+    #[cfg(feature = "serde")]
+    pub fn typed(self) -> News<'a, C, crate::processor::Typed<Vec<TickerNews>>> {
+        self.process_with(crate::processor::Typed::new())
     }
 
     /// Set a custom decoder function to convert JSON to typed data
@@ -71,13 +131,7 @@ impl<'a, C: Request, P: Processor + 'a> News<'a, C, P> {
         self,
         decoder_fn: impl Fn(decoder::Value) -> decoder::Result<T> + Send + Sync + 'static,
     ) -> News<'a, C, Decoder<T>> {
-        News {
-            client: self.client,
-            ticker: self.ticker,
-            limit: self.limit,
-            order: self.order,
-            processor: Decoder::new(decoder_fn),
-        }
+        self.process_with(Decoder::new(decoder_fn))
     }
 
     /// Filter news by ticker symbol
@@ -86,6 +140,36 @@ impl<'a, C: Request, P: Processor + 'a> News<'a, C, P> {
         self
     }
 
+    /// Filter by exact publication date (YYYY-MM-DD) or timestamp
+    pub fn published_utc(mut self, published: impl Into<String>) -> Self {
+        self.published_utc = Some(published.into());
+        self
+    }
+
+    /// Only return articles published on or after the given date or timestamp
+    pub fn published_utc_gte(mut self, published: impl Into<String>) -> Self {
+        self.published_utc_gte = Some(published.into());
+        self
+    }
+
+    /// Only return articles published after the given date or timestamp
+    pub fn published_utc_gt(mut self, published: impl Into<String>) -> Self {
+        self.published_utc_gt = Some(published.into());
+        self
+    }
+
+    /// Only return articles published on or before the given date or timestamp
+    pub fn published_utc_lte(mut self, published: impl Into<String>) -> Self {
+        self.published_utc_lte = Some(published.into());
+        self
+    }
+
+    /// Only return articles published before the given date or timestamp
+    pub fn published_utc_lt(mut self, published: impl Into<String>) -> Self {
+        self.published_utc_lt = Some(published.into());
+        self
+    }
+
     /// Set the maximum number of results to return
     pub fn limit(mut self, limit: impl Into<Limit>) -> Self {
         self.limit = limit.into().into();
@@ -97,6 +181,42 @@ impl<'a, C: Request, P: Processor + 'a> News<'a, C, P> {
         self.order = Some(order);
         self
     }
+
+    /// Set the field to sort by (e.g., "published_utc")
+    pub fn sort(mut self, sort: impl Into<String>) -> Self {
+        self.sort = Some(sort.into());
+        self
+    }
+
+    /// Continue from a pagination cursor (the `cursor` query parameter of a response's `next_url`)
+    pub fn cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+}
+
+impl<'a, C: Request, P: Processor + 'a> WithProcessor for News<'a, C, P> {
+    type Processor = P;
+    type With<Q: Processor> = News<'a, C, Q>;
+
+    fn map_processor<Q: Processor>(self, f: impl FnOnce(P) -> Q) -> News<'a, C, Q> {
+        News {
+            client: self.client,
+            ticker: self.ticker,
+            published_utc: self.published_utc,
+            published_utc_gte: self.published_utc_gte,
+            published_utc_gt: self.published_utc_gt,
+            published_utc_lte: self.published_utc_lte,
+            published_utc_lt: self.published_utc_lt,
+            limit: self.limit,
+            order: self.order,
+            sort: self.sort,
+            cursor: self.cursor,
+            processor: f(self.processor),
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
+        }
+    }
 }
 
 impl<'a, C: Request, P: Processor + 'a> Execute for News<'a, C, P> {
@@ -104,29 +224,49 @@ impl<'a, C: Request, P: Processor + 'a> Execute for News<'a, C, P> {
 
     #[allow(refining_impl_trait_reachable)]
     async fn get(self) -> Result<P::Output> {
-        let api_key = self
-            .client
-            .api_key()
-            .ok_or_else(|| crate::error::Error::Custom("API key not set".to_string()))?;
-
         let mut path = "/v2/reference/news".to_string();
-        let mut params = vec![format!("apiKey={}", api_key)];
+        let mut params = Vec::new();
 
         if let Some(t) = self.ticker {
-            params.push(format!("ticker={t}"));
+            params.push(format!("ticker={}", encode(&t)));
+        }
+        if let Some(p) = self.published_utc {
+            params.push(format!("published_utc={}", encode(&p)));
+        }
+        if let Some(p) = self.published_utc_gte {
+            params.push(format!("published_utc.gte={}", encode(&p)));
+        }
+        if let Some(p) = self.published_utc_gt {
+            params.push(format!("published_utc.gt={}", encode(&p)));
+        }
+        if let Some(p) = self.published_utc_lte {
+            params.push(format!("published_utc.lte={}", encode(&p)));
+        }
+        if let Some(p) = self.published_utc_lt {
+            params.push(format!("published_utc.lt={}", encode(&p)));
         }
         if let Some(l) = self.limit {
             params.push(format!("limit={l}"));
         }
         if let Some(o) = self.order {
-            params.push(format!("order={}", format!("{o:?}").to_lowercase()));
+            params.push(format!("order={o}"));
+        }
+        if let Some(s) = self.sort {
+            params.push(format!("sort={}", encode(&s)));
+        }
+        // Taken from `next_url`, so already encoded
+        if let Some(c) = self.cursor {
+            params.push(format!("cursor={c}"));
         }
 
-        path.push('?');
-        path.push_str(&params.join("&"));
-        let url = format!("https://api.polygon.io{path}");
-        let response = self.client.client().get(&url).await;
-        self.processor.process(response)
+        if !params.is_empty() {
+            path.push('?');
+            path.push_str(&params.join("&"));
+        }
+        let mut request = self.client.request("tickers.news", &path)?;
+        request.bypass_cache = self.bypass_cache;
+        request.timeout = self.timeout.or(request.timeout);
+        self.client.execute(request, &self.processor).await
     }
 }
 
@@ -137,9 +277,67 @@ pub struct Params {
     /// Ticker symbol to filter news by (e.g., "AAPL" for Apple Inc.")
     pub ticker: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    /// Only return articles published on this date or timestamp
+    pub published_utc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "published_utc.gte")]
+    /// Only return articles published on or after this date or timestamp
+    pub published_utc_gte: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "published_utc.gt")]
+    /// Only return articles published after this date or timestamp
+    pub published_utc_gt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "published_utc.lte")]
+    /// Only return articles published on or before this date or timestamp
+    pub published_utc_lte: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "published_utc.lt")]
+    /// Only return articles published before this date or timestamp
+    pub published_utc_lt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     /// Maximum number of results to return
     pub limit: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Sort order for results (asc or desc)
     pub order: Option<SortOrder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Field to sort by (e.g., "published_utc")
+    pub sort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Pagination cursor taken from the `next_url` of a previous response
+    pub cursor: Option<String>,
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::client::Polygon;
+    use crate::rest;
+    use crate::testing::{Mock, MockClient};
+
+    #[tokio::test]
+    async fn test_query() {
+        let mock = MockClient::new();
+        mock.register(Mock::get("/v2/reference/news").respond(200, "{}"));
+        let client = Polygon::default().with_client(mock.clone()).with_key("key");
+
+        rest::tickers::news(&client)
+            .ticker("BRK.A")
+            .published_utc_gte("2024-01-01T00:00:00+00:00")
+            .sort("published_utc")
+            .cursor("YXA9MTAw%3D")
+            .get()
+            .await
+            .unwrap();
+
+        let request = &mock.requests()[0];
+        assert_eq!(request.query("ticker"), Some("BRK.A"));
+        assert_eq!(
+            request.query("published_utc.gte"),
+            Some("2024-01-01T00%3A00%3A00%2B00%3A00")
+        );
+        assert_eq!(request.query("sort"), Some("published_utc"));
+        assert_eq!(request.query("cursor"), Some("YXA9MTAw%3D"));
+        assert_eq!(request.params.len(), 4);
+    }
 }
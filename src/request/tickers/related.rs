@@ -1,10 +1,12 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
 use crate::client::Polygon;
 use crate::error::Result;
 use crate::execute::Execute;
-use crate::processor::{Decoder, Processor, Raw};
+use crate::processor::{Decoder, Processor, Raw, WithProcessor};
 use crate::request::Request;
 
 /// Request builder for related tickers
@@ -13,6 +15,8 @@ pub struct Related<'a, Client: Request, P: Processor = Raw> {
     /// Ticker symbol (e.g., "AAPL" for Apple Inc.)
     pub ticker: String,
     processor: P,
+    bypass_cache: bool,
+    timeout: Option<Duration>,
 }
 
 impl<'a, C: Request> Related<'a, C, Raw> {
@@ -22,6 +26,8 @@ impl<'a, C: Request> Related<'a, C, Raw> {
             client,
             ticker: ticker.into(),
             processor: Raw,
+            bypass_cache: false,
+            timeout: None,
         }
     }
 }
@@ -32,26 +38,57 @@ impl<'a, C: Request, P: Processor + 'a> Related<'a, C, P> {
         Execute::get(self)
     }
 
+    /// Skip any response cache for this request (see [`Cached`](crate::cache::Cached))
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
+    /// Set the timeout for this request, overriding the client default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Switch to another processor, e.g. your own [`Processor`] implementation
+    pub fn process_with<Q: Processor>(self, processor: Q) -> Related<'a, C, Q> {
+        WithProcessor::process_with(self, processor)
+    }
+
+    /// Wrap the output in an [`Envelope`](crate::response::envelope::Envelope) with the response's
+    /// status, request ID, counts and `next_url`
+    #[cfg(feature = "serde")]
+    pub fn with_envelope(self) -> Related<'a, C, crate::processor::Enveloped<P>> {
+        WithProcessor::map_processor(self, crate::processor::Enveloped::new)
+    }
+
     /// Convert this request to return results as a Polars DataFrame instead of raw JSON
     #[cfg(feature = "table")]
     pub fn as_dataframe(self) -> Related<'a, C, crate::processor::Table> {
-        Related {
-            client: self.client,
-            ticker: self.ticker,
-            processor: crate::processor::Table,
-        }
+        self.process_with(crate::processor::Table::new(&crate::processor::schema::RELATED))
+    }
+
+    /// Convert this request to return results as an Arrow RecordBatch instead of raw JSON
+    #[cfg(feature = "arrow")]
+    pub fn as_record_batch(self) -> Related<'a, C, crate::processor::Arrow> {
+        self.process_with(crate::processor::Arrow::new(&crate::processor::schema::RELATED))
+    }
+
+    /// Convert to CSV output, with nested fields flattened into dotted columns
+    pub fn as_csv(self) -> Related<'a, C, crate::processor::Csv> {
+        self.process_with(crate::processor::Csv::new("results"))
+    }
+
+    /// Convert to newline-delimited JSON output, one flattened object per result
+    pub fn as_ndjson(self) -> Related<'a, C, crate::processor::NdJson> {
+        self.process_with(crate::processor::NdJson::new("results"))
     }
 
     /// Convert to decoded typed output (`Vec<String>`)
     pub fn decoded(self) -> Related<'a, C, Decoder<Vec<String>>> {
         use crate::rest::decoded::tickers::decode;
         let decoder = Decoder::new(decode::tickers);
-
-        Related {
-            client: self.client,
-            ticker: self.ticker,
-            processor: decoder,
-        }
+        self.process_with(decoder)
     }
 
     /// Set a custom decoder function to convert JSON to typed data
@@ -59,10 +96,21 @@ impl<'a, C: Request, P: Processor + 'a> Related<'a, C, P> {
         self,
         decoder_fn: impl Fn(decoder::Value) -> decoder::Result<T> + Send + Sync + 'static,
     ) -> Related<'a, C, Decoder<T>> {
+        self.process_with(Decoder::new(decoder_fn))
+    }
+}
+
+impl<'a, C: Request, P: Processor + 'a> WithProcessor for Related<'a, C, P> {
+    type Processor = P;
+    type With<Q: Processor> = Related<'a, C, Q>;
+
+    fn map_processor<Q: Processor>(self, f: impl FnOnce(P) -> Q) -> Related<'a, C, Q> {
         Related {
             client: self.client,
             ticker: self.ticker,
-            processor: Decoder::new(decoder_fn),
+            processor: f(self.processor),
+            bypass_cache: self.bypass_cache,
+            timeout: self.timeout,
         }
     }
 }
@@ -72,15 +120,11 @@ impl<'a, C: Request, P: Processor + 'a> Execute for Related<'a, C, P> {
 
     #[allow(refining_impl_trait_reachable)]
     async fn get(self) -> Result<P::Output> {
-        let api_key = self
-            .client
-            .api_key()
-            .ok_or_else(|| crate::error::Error::Custom("API key not set".to_string()))?;
-
-        let path = format!("/v1/related-companies/{}?apiKey={}", self.ticker, api_key);
-        let url = format!("https://api.polygon.io{path}");
-        let response = self.client.client().get(&url).await;
-        self.processor.process(response)
+        let path = format!("/v1/related-companies/{}", self.ticker);
+        let mut request = self.client.request("tickers.related", &path)?;
+        request.bypass_cache = self.bypass_cache;
+        request.timeout = self.timeout.or(request.timeout);
+        self.client.execute(request, &self.processor).await
     }
 }
 
@@ -2,7 +2,10 @@
 //!
 //! This module contains all response/output types returned by the API.
 
+use std::time::Duration;
+
 pub mod aggs;
+pub mod envelope;
 pub mod financials;
 pub mod ticker;
 
@@ -16,4 +19,101 @@ pub trait Response {
 
     /// The ID of the corresponding request
     fn request_id(&self) -> &Option<String>;
+
+    /// Size of the body in bytes, including a body passed to the sink of
+    /// [`send_streaming`](crate::Request::send_streaming) that [`body`](Self::body) no longer holds
+    fn bytes(&self) -> usize {
+        self.body().len()
+    }
+
+    /// Response headers, empty if the client does not expose them
+    fn headers(&self) -> &[(String, String)] {
+        &[]
+    }
+
+    /// Value of a response header, matching the name case-insensitively
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers()
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Delay requested by a `Retry-After` header given in seconds
+    fn retry_after(&self) -> Option<Duration> {
+        self.header("retry-after")?.trim().parse().ok().map(Duration::from_secs)
+    }
+
+    /// Time from sending the request to reading the whole body, `None` if not measured (e.g., cache hits)
+    fn latency(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(all(test, feature = "serde", feature = "decoder"))]
+mod tests {
+    use serde::Serialize;
+    use serde::de::DeserializeOwned;
+
+    use crate::response::financials::DecodeFinancials;
+    use crate::response::{aggs::*, financials::*};
+    use crate::rest::decoded::{aggs::decode as aggs_decode, tickers::decode as tickers_decode};
+
+    /// Decode a recorded response, then check that serializing and deserializing it is lossless
+    fn round_trip<T: Serialize + DeserializeOwned>(
+        fixture: &str,
+        decode: impl Fn(decoder::Value) -> decoder::Result<T>,
+    ) -> serde_json::Value {
+        let decoded = decoder::run(serde_json::from_str, decode, fixture).unwrap();
+        let json = serde_json::to_value(&decoded).unwrap();
+        let restored: T = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&restored).unwrap(), json);
+        json
+    }
+
+    #[test]
+    fn test_aggs_round_trip() {
+        let json = round_trip(&crate::fixture("aggs/aggregates"), aggs_decode::aggregates);
+        assert_eq!(json[0]["open"], 130.465);
+        assert_eq!(json[1]["timestamp"], 1673326800000i64);
+
+        round_trip::<Vec<PreviousCloseAgg>>(&crate::fixture("aggs/previous_close"), aggs_decode::previous_close);
+        let json =
+            round_trip::<Vec<GroupedDailyAgg>>(&crate::fixture("aggs/grouped_daily"), aggs_decode::grouped_daily);
+        assert_eq!(json[2]["otc"], true);
+        round_trip::<DailyOpenCloseAgg>(&crate::fixture("aggs/daily_open_close"), aggs_decode::daily_open_close);
+    }
+
+    #[test]
+    fn test_tickers_round_trip() {
+        let json = round_trip(&crate::fixture("tickers/all_tickers"), tickers_decode::all);
+        assert_eq!(json[0]["type"], "CS");
+        assert_eq!(json[1]["delisted_utc"], "2012-03-08T05:00:00Z");
+
+        round_trip(&crate::fixture("tickers/ticker_details"), tickers_decode::details);
+        round_trip(&crate::fixture("tickers/ticker_events"), tickers_decode::events);
+        let json = round_trip(&crate::fixture("tickers/news"), tickers_decode::news);
+        assert_eq!(json[0]["insights"][0]["sentiment"], "positive");
+    }
+
+    #[test]
+    fn test_financials_round_trip() {
+        round_trip(
+            &crate::fixture("financials/balance_sheets"),
+            Vec::<BalanceSheet>::decoder_fn(),
+        );
+        round_trip(
+            &crate::fixture("financials/cash_flow_statements"),
+            Vec::<CashFlowStatement>::decoder_fn(),
+        );
+        round_trip(
+            &crate::fixture("financials/income_statements"),
+            Vec::<IncomeStatement>::decoder_fn(),
+        );
+        let json = round_trip(
+            &crate::fixture("financials/ratios"),
+            Vec::<FinancialRatio>::decoder_fn(),
+        );
+        assert_eq!(json[0]["ticker"], "AAPL");
+    }
 }
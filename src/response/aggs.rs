@@ -1,23 +1,37 @@
 //! Aggregate data types
 
+#[cfg(feature = "serde")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Aggregate data for a ticker over a date range
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct Agg {
     /// Opening price
+    #[cfg_attr(feature = "serde", serde(alias = "o"))]
     pub open: Option<f64>,
     /// High price
+    #[cfg_attr(feature = "serde", serde(alias = "h"))]
     pub high: Option<f64>,
     /// Low price
+    #[cfg_attr(feature = "serde", serde(alias = "l"))]
     pub low: Option<f64>,
     /// Closing price
+    #[cfg_attr(feature = "serde", serde(alias = "c"))]
     pub close: Option<f64>,
     /// Volume
+    #[cfg_attr(feature = "serde", serde(alias = "v"))]
     pub volume: Option<f64>,
     /// Volume weighted average price
+    #[cfg_attr(feature = "serde", serde(alias = "vw"))]
     pub vwap: Option<f64>,
     /// Unix timestamp (milliseconds)
+    #[cfg_attr(feature = "serde", serde(alias = "t"))]
     pub timestamp: Option<i64>,
     /// Number of transactions
+    #[cfg_attr(feature = "serde", serde(alias = "n"))]
     pub transactions: Option<i64>,
     /// Whether this is OTC
     pub otc: Option<bool>,
@@ -25,24 +39,34 @@ pub struct Agg {
 
 /// Grouped daily aggregate data
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct GroupedDailyAgg {
     /// Ticker symbol
+    #[cfg_attr(feature = "serde", serde(alias = "T"))]
     pub ticker: Option<String>,
     /// Opening price
+    #[cfg_attr(feature = "serde", serde(alias = "o"))]
     pub open: Option<f64>,
     /// High price
+    #[cfg_attr(feature = "serde", serde(alias = "h"))]
     pub high: Option<f64>,
     /// Low price
+    #[cfg_attr(feature = "serde", serde(alias = "l"))]
     pub low: Option<f64>,
     /// Closing price
+    #[cfg_attr(feature = "serde", serde(alias = "c"))]
     pub close: Option<f64>,
     /// Volume
+    #[cfg_attr(feature = "serde", serde(alias = "v"))]
     pub volume: Option<f64>,
     /// Volume weighted average price
+    #[cfg_attr(feature = "serde", serde(alias = "vw"))]
     pub vwap: Option<f64>,
     /// Unix timestamp (milliseconds)
+    #[cfg_attr(feature = "serde", serde(alias = "t"))]
     pub timestamp: Option<i64>,
     /// Number of transactions
+    #[cfg_attr(feature = "serde", serde(alias = "n"))]
     pub transactions: Option<i64>,
     /// Whether this is OTC
     pub otc: Option<bool>,
@@ -50,8 +74,10 @@ pub struct GroupedDailyAgg {
 
 /// Daily open/close aggregate data
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct DailyOpenCloseAgg {
     /// After hours price
+    #[cfg_attr(feature = "serde", serde(alias = "afterHours"))]
     pub after_hours: Option<f64>,
     /// Closing price
     pub close: Option<f64>,
@@ -64,6 +90,7 @@ pub struct DailyOpenCloseAgg {
     /// Opening price
     pub open: Option<f64>,
     /// Pre-market price
+    #[cfg_attr(feature = "serde", serde(alias = "preMarket"))]
     pub pre_market: Option<f64>,
     /// Status
     pub status: Option<String>,
@@ -77,28 +104,63 @@ pub struct DailyOpenCloseAgg {
 
 /// Previous close aggregate data
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct PreviousCloseAgg {
     /// Ticker symbol
+    #[cfg_attr(feature = "serde", serde(alias = "T"))]
     pub ticker: Option<String>,
     /// Closing price
+    #[cfg_attr(feature = "serde", serde(alias = "c"))]
     pub close: Option<f64>,
     /// High price
+    #[cfg_attr(feature = "serde", serde(alias = "h"))]
     pub high: Option<f64>,
     /// Low price
+    #[cfg_attr(feature = "serde", serde(alias = "l"))]
     pub low: Option<f64>,
     /// Opening price
+    #[cfg_attr(feature = "serde", serde(alias = "o"))]
     pub open: Option<f64>,
     /// Unix timestamp (milliseconds)
+    #[cfg_attr(feature = "serde", serde(alias = "t"))]
     pub timestamp: Option<i64>,
     /// Volume
+    #[cfg_attr(feature = "serde", serde(alias = "v"))]
     pub volume: Option<f64>,
     /// Volume weighted average price
+    #[cfg_attr(feature = "serde", serde(alias = "vw"))]
     pub vwap: Option<f64>,
 }
 
+/// Typed accessors for bars carrying a millisecond `timestamp`
+#[cfg(feature = "chrono")]
+macro_rules! impl_bar_time {
+    ($t:ty) => {
+        impl $t {
+            /// Bar start time in UTC
+            pub fn datetime(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+                self.timestamp.and_then(crate::time::from_millis)
+            }
 
+            /// Bar start time in America/New_York
+            pub fn datetime_new_york(&self) -> Option<chrono::DateTime<chrono_tz::Tz>> {
+                self.datetime().map(|dt| crate::time::to_new_york(&dt))
+            }
+        }
+    };
+}
 
+#[cfg(feature = "chrono")]
+impl_bar_time!(Agg);
+#[cfg(feature = "chrono")]
+impl_bar_time!(GroupedDailyAgg);
+#[cfg(feature = "chrono")]
+impl_bar_time!(PreviousCloseAgg);
 
-
-
-
+#[cfg(feature = "chrono")]
+impl DailyOpenCloseAgg {
+    /// Trading date of this bar
+    pub fn date(&self) -> Option<chrono::NaiveDate> {
+        self.from.as_deref().and_then(crate::time::parse_date)
+    }
+}
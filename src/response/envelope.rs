@@ -0,0 +1,45 @@
+//! Response envelope metadata
+
+#[cfg(feature = "serde")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Results together with the metadata of the response envelope they came in
+///
+/// Returned by the builders' `with_envelope()`. `results` is the output of the builder's
+/// processor (e.g. `Vec<Agg>` after `decoded()`, or the raw JSON).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct Envelope<T> {
+    /// Results of the request
+    pub results: T,
+    /// Response status (e.g. "OK", or "DELAYED" for delayed data)
+    pub status: Option<String>,
+    /// Request ID from the body, or the `X-Request-Id` header
+    pub request_id: Option<String>,
+    /// Number of results in this page
+    pub count: Option<i64>,
+    /// Number of base aggregates queried to build the results
+    #[cfg_attr(feature = "serde", serde(alias = "queryCount"))]
+    pub query_count: Option<i64>,
+    /// Number of results returned
+    #[cfg_attr(feature = "serde", serde(alias = "resultsCount"))]
+    pub results_count: Option<i64>,
+    /// Whether the results are adjusted for splits
+    pub adjusted: Option<bool>,
+    /// URL of the next page, if the results are partial
+    pub next_url: Option<String>,
+}
+
+impl<T> Envelope<T> {
+    /// Whether the data is delayed rather than real-time (`status: "DELAYED"`)
+    pub fn is_delayed(&self) -> bool {
+        self.status.as_deref() == Some("DELAYED")
+    }
+
+    /// Whether more results are available on a next page
+    pub fn has_next(&self) -> bool {
+        self.next_url.is_some()
+    }
+}
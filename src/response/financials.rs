@@ -1,7 +1,13 @@
 //! Financial data types
 
+#[cfg(feature = "serde")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Balance sheet data for a company
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct BalanceSheet {
     /// Accounts payable
     pub accounts_payable: Option<f64>,
@@ -83,6 +89,7 @@ pub struct BalanceSheet {
 
 /// Cash flow statement data for a company
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct CashFlowStatement {
     /// Cash from operating activities from continuing operations
     pub cash_from_operating_activities_continuing_operations: Option<f64>,
@@ -152,6 +159,7 @@ pub struct CashFlowStatement {
 
 /// Income statement data for a company
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct IncomeStatement {
     /// Basic earnings per share
     pub basic_earnings_per_share: Option<f64>,
@@ -225,6 +233,7 @@ pub struct IncomeStatement {
 
 /// Financial ratios data for a company
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct FinancialRatio {
     /// Average trading volume (30-day)
     pub average_volume: Option<f64>,
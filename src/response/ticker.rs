@@ -1,7 +1,13 @@
 //! Ticker data types and decoders
 
+#[cfg(feature = "serde")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Company address data
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct CompanyAddress {
     /// Address line 1
     pub address1: Option<String>,
@@ -19,6 +25,7 @@ pub struct CompanyAddress {
 
 /// Branding data for a ticker
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct Branding {
     /// Icon URL
     pub icon_url: Option<String>,
@@ -34,6 +41,7 @@ pub struct Branding {
 
 /// Basic ticker information
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct Ticker {
     /// Whether the ticker is actively traded
     pub active: Option<bool>,
@@ -66,13 +74,28 @@ pub struct Ticker {
     /// Ticker symbol
     pub ticker: Option<String>,
     /// Ticker type
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub type_: Option<String>,
     /// Source feed (optional)
     pub source_feed: Option<String>,
 }
 
+#[cfg(feature = "chrono")]
+impl Ticker {
+    /// Delisting time parsed from `delisted_utc`
+    pub fn delisted(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.delisted_utc.as_deref().and_then(crate::time::parse_datetime)
+    }
+
+    /// Last update time parsed from `last_updated_utc`
+    pub fn last_updated(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.last_updated_utc.as_deref().and_then(crate::time::parse_datetime)
+    }
+}
+
 /// Publisher information for news articles
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct Publisher {
     /// Favicon URL
     pub favicon_url: Option<String>,
@@ -86,6 +109,7 @@ pub struct Publisher {
 
 /// Insight data related to a news article
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct Insight {
     /// Sentiment (positive, negative, neutral)
     pub sentiment: Option<String>,
@@ -97,6 +121,7 @@ pub struct Insight {
 
 /// News article data
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct TickerNews {
     /// AMP URL
     pub amp_url: Option<String>,
@@ -124,8 +149,30 @@ pub struct TickerNews {
     pub title: Option<String>,
 }
 
+impl TickerNews {
+    /// Get the sentiment insight for a specific ticker, if the article has one
+    pub fn insight(&self, ticker: &str) -> Option<&Insight> {
+        self.insights
+            .as_ref()?
+            .iter()
+            .find(|i| i.ticker.as_deref() == Some(ticker))
+    }
+
+    /// Publication date (YYYY-MM-DD) taken from `published_utc`
+    pub fn published_date(&self) -> Option<&str> {
+        self.published_utc.as_deref().and_then(|p| p.get(..10))
+    }
+
+    /// Publication time parsed from `published_utc`
+    #[cfg(feature = "chrono")]
+    pub fn published(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.published_utc.as_deref().and_then(crate::time::parse_datetime)
+    }
+}
+
 /// Ticker change information
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct TickerChange {
     /// New ticker symbol
     pub ticker: String,
@@ -133,8 +180,10 @@ pub struct TickerChange {
 
 /// Ticker change event
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct TickerChangeEvent {
     /// Event type
+    #[cfg_attr(feature = "serde", serde(alias = "type"))]
     pub event_type: String,
     /// Event date
     pub date: String,
@@ -144,6 +193,7 @@ pub struct TickerChangeEvent {
 
 /// Results from ticker events endpoint
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
 pub struct TickerChangeResults {
     /// Ticker name
     pub name: String,
@@ -154,8 +204,3 @@ pub struct TickerChangeResults {
     /// List of events
     pub events: Option<Vec<TickerChangeEvent>>,
 }
-
-
-
-
-
@@ -7,5 +7,8 @@ pub mod decoded;
 #[cfg(feature = "table")]
 pub mod table;
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
 // Re-export raw module for convenience.
 pub use raw::*;
@@ -0,0 +1,4 @@
+//! Arrow REST API endpoints that return RecordBatches
+pub mod aggs;
+pub mod financials;
+pub mod tickers;
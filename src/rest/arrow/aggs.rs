@@ -0,0 +1,71 @@
+//! Aggregates endpoints returning Arrow RecordBatches
+use crate::client::Polygon;
+use crate::processor::Arrow;
+use crate::request::Request;
+use crate::request::aggs::{Aggregates, DailyOpenClose, GroupedDaily, PreviousClose};
+use crate::request::common::{DateLike, DateParam, Timespan};
+
+/// Get aggregate bars for a stock over a given date range
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.adjusted()`, `.sort()`, `.limit()` to customize the request.
+/// String timespans can be parsed with [`str::parse`], which rejects unknown values.
+///
+/// # Example
+/// ```no_run
+/// # use polygon::Polygon;
+/// # use polygon::request::common::Timespan;
+/// # async fn example() -> polygon::Result<()> {
+/// # let client = Polygon::default().with_key("api-key");
+/// let timespan: Timespan = "day".parse()?;
+/// let batch = polygon::rest::arrow::aggs::aggregates(&client, "AAPL", 1, timespan, "2023-01-01", "2023-12-31")
+///     .adjusted(true)
+///     .get()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn aggregates<'a, Client: Request>(
+    client: &'a Polygon<Client>,
+    ticker: impl Into<String>,
+    multiplier: u32,
+    timespan: Timespan,
+    from: impl DateLike,
+    to: impl DateLike,
+) -> Aggregates<'a, Client, Arrow> {
+    Aggregates::new(client, ticker, multiplier, timespan, from, to).as_record_batch()
+}
+
+/// Get the previous day's OHLC for a stock
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.adjusted()` to customize the request.
+pub fn previous_close<'a, Client: Request>(
+    client: &'a Polygon<Client>,
+    ticker: impl Into<String>,
+) -> PreviousClose<'a, Client, Arrow> {
+    PreviousClose::new(client, ticker).as_record_batch()
+}
+
+/// Get daily OHLC for the entire market
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.adjusted()` and `.include_otc()` to customize the request.
+pub fn grouped_daily<'a, Client: Request>(
+    client: &'a Polygon<Client>,
+    date: impl DateParam,
+) -> GroupedDaily<'a, Client, Arrow> {
+    GroupedDaily::new(client, date).as_record_batch()
+}
+
+/// Get the open/close/afterhours prices of a stock on a specific date
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.adjusted()` to customize the request.
+pub fn daily_open_close<'a, Client: Request>(
+    client: &'a Polygon<Client>,
+    ticker: impl Into<String>,
+    date: impl DateParam,
+) -> DailyOpenClose<'a, Client, Arrow> {
+    DailyOpenClose::new(client, ticker, date).as_record_batch()
+}
@@ -0,0 +1,52 @@
+//! Financials endpoints returning Arrow RecordBatches
+use crate::client::Polygon;
+use crate::processor::Arrow;
+use crate::request::Request;
+use crate::request::financials::Financials;
+
+/// Get balance sheet data for public companies
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.ticker()`, `.cik()`, `.filing_date()`, `.limit()` to customize the request.
+///
+/// # Example
+/// ```no_run
+/// # use polygon::Client;
+/// # use polygon::execute::Execute;
+/// # async fn example() {
+/// # let client = Client::new("api-key");
+/// let batch = polygon::rest::arrow::financials::balance_sheets(&client)
+///     .ticker("AAPL")
+///     .limit(10)
+///     .get()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub fn balance_sheets<'a, Client: Request>(client: &'a Polygon<Client>) -> Financials<'a, Client, Arrow> {
+    Financials::balance_sheets(client).as_record_batch()
+}
+
+/// Get cash flow statement data for public companies
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.ticker()`, `.cik()`, `.filing_date()`, `.limit()` to customize the request.
+pub fn cash_flow_statements<'a, Client: Request>(client: &'a Polygon<Client>) -> Financials<'a, Client, Arrow> {
+    Financials::cash_flow_statements(client).as_record_batch()
+}
+
+/// Get income statement data for public companies
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.ticker()`, `.cik()`, `.filing_date()`, `.limit()` to customize the request.
+pub fn income_statements<'a, Client: Request>(client: &'a Polygon<Client>) -> Financials<'a, Client, Arrow> {
+    Financials::income_statements(client).as_record_batch()
+}
+
+/// Get financial ratios data for public companies
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.ticker()`, `.cik()`, `.filing_date()`, `.limit()` to customize the request.
+pub fn ratios<'a, Client: Request>(client: &'a Polygon<Client>) -> Financials<'a, Client, Arrow> {
+    Financials::ratios(client).as_record_batch()
+}
@@ -0,0 +1,144 @@
+//! Ticker endpoints returning Arrow RecordBatches
+use crate::client::Polygon;
+use crate::processor::Arrow;
+use crate::request::Request;
+use crate::request::tickers::{All, Details, Events, News, Related, Types};
+
+/// Get a list of all tickers
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.ticker()`, `.market()`, `.exchange()`, `.limit()` to customize the request.
+///
+/// # Example
+/// ```no_run
+/// # use polygon::Client;
+/// # use polygon::execute::Execute;
+/// # async fn example() {
+/// # let client = Client::new("api-key");
+/// let batch = polygon::rest::arrow::tickers::all(&client)
+///     .market("stocks")
+///     .limit(100)
+///     .get()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub fn all<'a, Client: Request>(client: &'a Polygon<Client>) -> All<'a, Client, Arrow> {
+    All::new(client).as_record_batch()
+}
+
+/// Get detailed information about a ticker
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.date()` to customize the request.
+///
+/// # Example
+/// ```no_run
+/// # use polygon::Client;
+/// # use polygon::execute::Execute;
+/// # async fn example() {
+/// # let client = Client::new("api-key");
+/// let batch = polygon::rest::arrow::tickers::details(&client, "AAPL")
+///     .get()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub fn details<'a, Client: Request>(
+    client: &'a Polygon<Client>,
+    ticker: impl Into<String>,
+) -> Details<'a, Client, Arrow> {
+    Details::new(client, ticker).as_record_batch()
+}
+
+/// Get tickers related to a given ticker
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+///
+/// # Example
+/// ```no_run
+/// # use polygon::Client;
+/// # use polygon::execute::Execute;
+/// # async fn example() {
+/// # let client = Client::new("api-key");
+/// let batch = polygon::rest::arrow::tickers::related(&client, "AAPL")
+///     .get()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub fn related<'a, Client: Request>(
+    client: &'a Polygon<Client>,
+    ticker: impl Into<String>,
+) -> Related<'a, Client, Arrow> {
+    Related::new(client, ticker).as_record_batch()
+}
+
+/// Get all ticker types
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.asset_class()` and `.locale()` to customize the request.
+///
+/// # Example
+/// ```no_run
+/// # use polygon::Client;
+/// # use polygon::execute::Execute;
+/// # async fn example() {
+/// # let client = Client::new("api-key");
+/// let batch = polygon::rest::arrow::tickers::types(&client)
+///     .asset_class("stocks")
+///     .get()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub fn types<'a, Client: Request>(client: &'a Polygon<Client>) -> Types<'a, Client, Arrow> {
+    Types::new(client).as_record_batch()
+}
+
+/// Get event history for a ticker
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.types()` to customize the request.
+///
+/// # Example
+/// ```no_run
+/// # use polygon::Client;
+/// # use polygon::execute::Execute;
+/// # async fn example() {
+/// # let client = Client::new("api-key");
+/// let batch = polygon::rest::arrow::tickers::events(&client, "AAPL")
+///     .get()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub fn events<'a, Client: Request>(
+    client: &'a Polygon<Client>,
+    ticker: impl Into<String>,
+) -> Events<'a, Client, Arrow> {
+    Events::new(client, ticker).as_record_batch()
+}
+
+/// Get the most recent news articles
+///
+/// Returns a request builder that will return results as a Arrow RecordBatch.
+/// Use builder methods like `.ticker()`, `.limit()`, `.order()` to customize the request.
+///
+/// # Example
+/// ```no_run
+/// # use polygon::Client;
+/// # use polygon::execute::Execute;
+/// # async fn example() {
+/// # let client = Client::new("api-key");
+/// let batch = polygon::rest::arrow::tickers::news(&client)
+///     .ticker("AAPL")
+///     .limit(10)
+///     .get()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub fn news<'a, Client: Request>(client: &'a Polygon<Client>) -> News<'a, Client, Arrow> {
+    News::new(client).as_record_batch()
+}
@@ -4,7 +4,7 @@ use crate::client::Polygon;
 use crate::processor::Decoder;
 use crate::request::Request;
 use crate::request::aggs::{Aggregates, DailyOpenClose, GroupedDaily, PreviousClose};
-use crate::request::common::Timespan;
+use crate::request::common::{DateLike, DateParam, Timespan};
 use crate::response::aggs::{Agg, DailyOpenCloseAgg, GroupedDailyAgg, PreviousCloseAgg};
 use crate::rest::aggs;
 
@@ -17,8 +17,8 @@ pub fn aggregates<'a, Client: Request>(
     ticker: impl Into<String>,
     multiplier: u32,
     timespan: Timespan,
-    from: impl Into<String>,
-    to: impl Into<String>,
+    from: impl DateLike,
+    to: impl DateLike,
 ) -> Aggregates<'a, Client, Decoder<Vec<Agg>>> {
     aggs::aggregates(client, ticker, multiplier, timespan, from, to).decoded()
 }
@@ -40,7 +40,7 @@ pub fn previous_close<'a, Client: Request>(
 /// Use builder methods like `.adjusted()` and `.include_otc()` to customize the request.
 pub fn grouped_daily<'a, Client: Request>(
     client: &'a Polygon<Client>,
-    date: impl Into<String>,
+    date: impl DateParam,
 ) -> GroupedDaily<'a, Client, Decoder<Vec<GroupedDailyAgg>>> {
     aggs::grouped_daily(client, date).decoded()
 }
@@ -52,7 +52,101 @@ pub fn grouped_daily<'a, Client: Request>(
 pub fn daily_open_close<'a, Client: Request>(
     client: &'a Polygon<Client>,
     ticker: impl Into<String>,
-    date: impl Into<String>,
+    date: impl DateParam,
 ) -> DailyOpenClose<'a, Client, Decoder<DailyOpenCloseAgg>> {
     aggs::daily_open_close(client, ticker, date).decoded()
 }
+
+pub mod decode {
+    //! Decode functions for aggregate endpoints
+    use super::*;
+    use decoder::decode::{bool, f64, i64, map, sequence, string};
+
+    /// Decode a list of aggregate bars
+    pub fn aggregates(value: decoder::Value) -> decoder::Result<Vec<Agg>> {
+        let mut response = map(value)?;
+        response.required("results", sequence(agg))
+    }
+
+    /// Decode a list of previous close bars
+    pub fn previous_close(value: decoder::Value) -> decoder::Result<Vec<PreviousCloseAgg>> {
+        let mut response = map(value)?;
+        response.required("results", sequence(previous_close_agg))
+    }
+
+    /// Decode a list of grouped daily bars
+    pub fn grouped_daily(value: decoder::Value) -> decoder::Result<Vec<GroupedDailyAgg>> {
+        let mut response = map(value)?;
+        response.required("results", sequence(grouped_daily_agg))
+    }
+
+    /// Decode a daily open/close response
+    pub fn daily_open_close(value: decoder::Value) -> decoder::Result<DailyOpenCloseAgg> {
+        let mut daily = map(value)?;
+
+        Ok(DailyOpenCloseAgg {
+            after_hours: daily.optional("afterHours", f64)?,
+            close: daily.optional("close", f64)?,
+            from: daily.optional("from", string)?,
+            high: daily.optional("high", f64)?,
+            low: daily.optional("low", f64)?,
+            open: daily.optional("open", f64)?,
+            pre_market: daily.optional("preMarket", f64)?,
+            status: daily.optional("status", string)?,
+            symbol: daily.optional("symbol", string)?,
+            volume: daily.optional("volume", f64)?,
+            otc: daily.optional("otc", bool)?,
+        })
+    }
+
+    /// Decode an aggregate bar
+    pub fn agg(value: decoder::Value) -> decoder::Result<Agg> {
+        let mut agg = map(value)?;
+
+        Ok(Agg {
+            open: agg.optional("o", f64)?,
+            high: agg.optional("h", f64)?,
+            low: agg.optional("l", f64)?,
+            close: agg.optional("c", f64)?,
+            volume: agg.optional("v", f64)?,
+            vwap: agg.optional("vw", f64)?,
+            timestamp: agg.optional("t", i64)?,
+            transactions: agg.optional("n", i64)?,
+            otc: agg.optional("otc", bool)?,
+        })
+    }
+
+    /// Decode a previous close bar
+    pub fn previous_close_agg(value: decoder::Value) -> decoder::Result<PreviousCloseAgg> {
+        let mut prev = map(value)?;
+
+        Ok(PreviousCloseAgg {
+            ticker: prev.optional("T", string)?,
+            close: prev.optional("c", f64)?,
+            high: prev.optional("h", f64)?,
+            low: prev.optional("l", f64)?,
+            open: prev.optional("o", f64)?,
+            timestamp: prev.optional("t", i64)?,
+            volume: prev.optional("v", f64)?,
+            vwap: prev.optional("vw", f64)?,
+        })
+    }
+
+    /// Decode a grouped daily bar
+    pub fn grouped_daily_agg(value: decoder::Value) -> decoder::Result<GroupedDailyAgg> {
+        let mut grouped = map(value)?;
+
+        Ok(GroupedDailyAgg {
+            ticker: grouped.optional("T", string)?,
+            open: grouped.optional("o", f64)?,
+            high: grouped.optional("h", f64)?,
+            low: grouped.optional("l", f64)?,
+            close: grouped.optional("c", f64)?,
+            volume: grouped.optional("v", f64)?,
+            vwap: grouped.optional("vw", f64)?,
+            timestamp: grouped.optional("t", i64)?,
+            transactions: grouped.optional("n", i64)?,
+            otc: grouped.optional("otc", bool)?,
+        })
+    }
+}
@@ -180,3 +180,50 @@ pub mod decode {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_news_insights() {
+        let json = r#"{
+            "status": "OK",
+            "results": [{
+                "id": "abc",
+                "amp_url": "https://amp.example.com/a",
+                "article_url": "https://example.com/a",
+                "keywords": ["earnings", "iphone"],
+                "published_utc": "2024-06-24T18:33:53Z",
+                "publisher": {
+                    "name": "Example",
+                    "favicon_url": "https://example.com/favicon.ico"
+                },
+                "tickers": ["AAPL", "MSFT"],
+                "insights": [
+                    {"ticker": "AAPL", "sentiment": "positive", "sentiment_reasoning": "Strong sales"},
+                    {"ticker": "MSFT", "sentiment": "neutral", "sentiment_reasoning": "Mentioned in passing"}
+                ]
+            }]
+        }"#;
+
+        let news = decoder::run(serde_json::from_str, decode::news, json).unwrap();
+        let article = &news[0];
+
+        assert_eq!(article.amp_url.as_deref(), Some("https://amp.example.com/a"));
+        assert_eq!(
+            article.keywords.as_deref(),
+            Some(&["earnings".to_string(), "iphone".to_string()][..])
+        );
+        assert_eq!(
+            article.publisher.as_ref().and_then(|p| p.favicon_url.as_deref()),
+            Some("https://example.com/favicon.ico")
+        );
+        assert_eq!(article.published_date(), Some("2024-06-24"));
+
+        let insight = article.insight("MSFT").unwrap();
+        assert_eq!(insight.sentiment.as_deref(), Some("neutral"));
+        assert_eq!(insight.sentiment_reasoning.as_deref(), Some("Mentioned in passing"));
+        assert!(article.insight("GOOG").is_none());
+    }
+}
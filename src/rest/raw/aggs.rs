@@ -4,7 +4,7 @@ use crate::client::Polygon;
 use crate::processor::Raw;
 use crate::request::Request;
 use crate::request::aggs::{Aggregates, DailyOpenClose, GroupedDaily, PreviousClose};
-use crate::request::common::Timespan;
+use crate::request::common::{DateLike, DateParam, Timespan};
 
 /// Get aggregate bars for a stock over a given date range
 ///
@@ -13,11 +13,11 @@ use crate::request::common::Timespan;
 ///
 /// # Example
 /// ```no_run
-/// # use polygon::Client;
-/// # use polygon::execute::Execute;
+/// # use polygon::Polygon;
+/// # use polygon::request::common::Timespan;
 /// # async fn example() {
-/// # let client = Client::new("api-key");
-/// let json = polygon::rest::aggs::aggregates(&client, "AAPL", 1, "day", "2023-01-01", "2023-12-31")
+/// # let client = Polygon::default().with_key("api-key");
+/// let json = polygon::rest::aggs::aggregates(&client, "AAPL", 1, Timespan::Day, "2023-01-01", "2023-12-31")
 ///     .adjusted(true)
 ///     .get()
 ///     .await
@@ -29,8 +29,8 @@ pub fn aggregates<'a, Client: Request>(
     ticker: impl Into<String>,
     multiplier: u32,
     timespan: Timespan,
-    from: impl Into<String>,
-    to: impl Into<String>,
+    from: impl DateLike,
+    to: impl DateLike,
 ) -> Aggregates<'a, Client, Raw> {
     Aggregates::new(client, ticker, multiplier, timespan, from, to)
 }
@@ -52,62 +52,109 @@ pub fn previous_close<'a, Client: Request>(
 /// Use builder methods like `.adjusted()` and `.include_otc()` to customize the request.
 pub fn grouped_daily<'a, Client: Request>(
     client: &'a Polygon<Client>,
-    date: impl Into<String>,
+    date: impl DateParam,
 ) -> GroupedDaily<'a, Client, Raw> {
     GroupedDaily::new(client, date)
 }
 
 /// Get the open, close, and afterhours prices of a stock on a specific date
 ///
-/// Returns a request builder that will return results as raw JSON string.
+/// Returns a request builder that will return results as raw JSON string: the `/v1/open-close`
+/// object itself, with no `results` array (see [`DailyOpenClose`]).
 /// Use builder methods like `.adjusted()` to customize the request.
 pub fn daily_open_close<'a, Client: Request>(
     client: &'a Polygon<Client>,
     ticker: impl Into<String>,
-    date: impl Into<String>,
+    date: impl DateParam,
 ) -> DailyOpenClose<'a, Client, Raw> {
     DailyOpenClose::new(client, ticker, date)
 }
 
-#[cfg(all(test, feature = "dotenvy"))]
+#[cfg(all(test, feature = "reqwest", feature = "serde"))]
 mod tests {
     use super::*;
+    use crate::cassette::{Cassette, test_client};
 
-    fn setup() -> Polygon<reqwest::Client> {
-        Polygon::new().expect("Failed to create client. Make sure POLYGON_API_KEY is set in .env file")
+    /// Replays the synthetic cassette `tests/cassettes/aggs/<name>.json`; record a real one with `POLYGON_RECORD=1`
+    fn setup(name: &str) -> Polygon<Cassette<reqwest::Client>> {
+        test_client(&format!("aggs/{name}"))
     }
 
     #[tokio::test]
-    #[ignore] // Run with: cargo test -- --ignored --test-threads=1
     async fn test_aggregates() {
-        let client = setup();
-        let result = aggregates(&client, "AAPL", 1, Timespan::Day, "2023-01-01", "2023-01-10")
+        let client = setup("aggregates");
+        let aggs = aggregates(&client, "AAPL", 1, Timespan::Day, "2023-01-01", "2023-01-10")
+            .typed()
             .get()
-            .await;
-        assert!(result.is_ok(), "Failed to fetch aggregates: {result:?}");
+            .await
+            .expect("Failed to fetch aggregates");
+        assert_eq!(aggs.len(), 2);
+        assert_eq!(aggs[0].open, Some(130.465));
+        assert_eq!(aggs[0].timestamp, Some(1673240400000));
+        assert_eq!(aggs[1].close, Some(130.73));
+        assert_eq!(aggs[1].transactions, Some(554940));
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_previous_close() {
-        let client = setup();
-        let result = previous_close(&client, "AAPL").get().await;
-        assert!(result.is_ok(), "Failed to fetch previous close: {result:?}");
+        let client = setup("previous_close");
+        let prev = previous_close(&client, "AAPL")
+            .typed()
+            .get()
+            .await
+            .expect("Failed to fetch previous close");
+        assert_eq!(prev.len(), 1);
+        assert_eq!(prev[0].ticker.as_deref(), Some("AAPL"));
+        assert_eq!(prev[0].close, Some(130.15));
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_grouped_daily() {
-        let client = setup();
-        let result = grouped_daily(&client, "2023-01-09").get().await;
-        assert!(result.is_ok(), "Failed to fetch grouped daily: {result:?}");
+        let client = setup("grouped_daily");
+        let grouped = grouped_daily(&client, "2023-01-09")
+            .typed()
+            .get()
+            .await
+            .expect("Failed to fetch grouped daily");
+        let tickers: Vec<_> = grouped.iter().filter_map(|a| a.ticker.as_deref()).collect();
+        assert_eq!(tickers, ["KIMpL", "TANH", "VSAT"]);
+        assert_eq!(grouped[1].volume, Some(25933.6));
+        assert_eq!(grouped[2].otc, Some(true));
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_daily_open_close() {
-        let client = setup();
-        let result = daily_open_close(&client, "AAPL", "2023-01-09").get().await;
-        assert!(result.is_ok(), "Failed to fetch daily open/close: {result:?}");
+        let client = setup("daily_open_close");
+        let day = daily_open_close(&client, "AAPL", "2023-01-09")
+            .typed()
+            .get()
+            .await
+            .expect("Failed to fetch daily open/close");
+        assert_eq!(day.symbol.as_deref(), Some("AAPL"));
+        assert_eq!(day.from.as_deref(), Some("2023-01-09"));
+        assert_eq!(day.open, Some(324.66));
+        assert_eq!(day.after_hours, Some(322.1));
+    }
+
+    #[tokio::test]
+    async fn test_daily_open_close_text() {
+        let csv = daily_open_close(&setup("daily_open_close"), "AAPL", "2023-01-09")
+            .as_csv()
+            .get()
+            .await
+            .expect("Failed to fetch daily open/close");
+        assert_eq!(
+            csv,
+            "afterHours,close,from,high,low,open,preMarket,status,symbol,volume\n\
+             322.1,325.12,2023-01-09,326.2,322.3,324.66,324.5,OK,AAPL,26122646\n"
+        );
+
+        let ndjson = daily_open_close(&setup("daily_open_close"), "AAPL", "2023-01-09")
+            .as_ndjson()
+            .get()
+            .await
+            .expect("Failed to fetch daily open/close");
+        assert_eq!(ndjson.lines().count(), 1);
+        assert!(ndjson.contains(r#""symbol":"AAPL""#));
     }
 }
@@ -37,47 +37,74 @@ pub fn ratios<'a, Client: Request>(client: &'a Polygon<Client>) -> Financials<'a
     Financials::ratios(client)
 }
 
-#[cfg(all(test, feature = "dotenvy"))]
+#[cfg(all(test, feature = "reqwest", feature = "serde"))]
 mod tests {
     use super::*;
+    use crate::cassette::{Cassette, test_client};
+    use crate::response::financials::{BalanceSheet, CashFlowStatement, FinancialRatio, IncomeStatement};
 
-    fn setup() -> Polygon<reqwest::Client> {
-        Polygon::new().expect("Failed to create client. Make sure POLYGON_API_KEY is set in .env file")
+    /// Replays the synthetic cassette `tests/cassettes/financials/<name>.json`; record a real one with `POLYGON_RECORD=1`
+    fn setup(name: &str) -> Polygon<Cassette<reqwest::Client>> {
+        test_client(&format!("financials/{name}"))
     }
 
     #[tokio::test]
-    #[ignore] // Run with: cargo test -- --ignored --test-threads=1
     async fn test_balance_sheets() {
-        let client = setup();
-        let result = balance_sheets(&client)
+        let client = setup("balance_sheets");
+        let sheets = balance_sheets(&client)
             .cik("0000320193") // Apple Inc.
             .limit("1")
+            .typed::<Vec<BalanceSheet>>()
             .get()
-            .await;
-        assert!(result.is_ok(), "Failed to fetch balance sheets: {result:?}");
+            .await
+            .expect("Failed to fetch balance sheets");
+        assert_eq!(sheets.len(), 1);
+        assert_eq!(sheets[0].period_end.as_deref(), Some("2024-09-28"));
+        assert_eq!(sheets[0].total_assets, Some(364980000000.0));
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_cash_flow_statements() {
-        let client = setup();
-        let result = cash_flow_statements(&client).cik("0000320193").limit("1").get().await;
-        assert!(result.is_ok(), "Failed to fetch cash flow statements: {result:?}");
+        let client = setup("cash_flow_statements");
+        let statements = cash_flow_statements(&client)
+            .cik("0000320193")
+            .limit("1")
+            .typed::<Vec<CashFlowStatement>>()
+            .get()
+            .await
+            .expect("Failed to fetch cash flow statements");
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].net_income, Some(14736000000.0));
+        assert_eq!(statements[0].change_in_cash_and_equivalents, Some(3308000000.0));
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_income_statements() {
-        let client = setup();
-        let result = income_statements(&client).cik("0000320193").limit("1").get().await;
-        assert!(result.is_ok(), "Failed to fetch income statements: {result:?}");
+        let client = setup("income_statements");
+        let statements = income_statements(&client)
+            .cik("0000320193")
+            .limit("1")
+            .typed::<Vec<IncomeStatement>>()
+            .get()
+            .await
+            .expect("Failed to fetch income statements");
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].revenue, Some(94930000000.0));
+        assert_eq!(statements[0].cost_of_revenue, Some(51051000000.0));
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_ratios() {
-        let client = setup();
-        let result = ratios(&client).ticker("AAPL").limit("1").get().await;
-        assert!(result.is_ok(), "Failed to fetch ratios: {result:?}");
+        let client = setup("ratios");
+        let ratios = ratios(&client)
+            .ticker("AAPL")
+            .limit("1")
+            .typed::<Vec<FinancialRatio>>()
+            .get()
+            .await
+            .expect("Failed to fetch ratios");
+        assert_eq!(ratios.len(), 1);
+        assert_eq!(ratios[0].ticker.as_deref(), Some("AAPL"));
+        assert_eq!(ratios[0].price_to_earnings, Some(37.83));
     }
 }
@@ -8,7 +8,8 @@ use crate::request::tickers::{All, Details, Events, News, Related, Types};
 /// Get a list of all tickers
 ///
 /// Returns a request builder that will return results as raw JSON string.
-/// Use builder methods like `.ticker()`, `.ticker_type()`, `.market()`, `.limit()` to customize the request.
+/// Use builder methods like `.ticker()`, `.ticker_type()`, `.market()`, `.date()`, `.active()`, `.limit()` to customize
+/// the request.
 pub fn all<'a, Client: Request>(client: &'a Polygon<Client>) -> All<'a, Client, Raw> {
     All::new(client)
 }
@@ -83,51 +84,138 @@ pub fn news<'a, Client: Request>(client: &'a Polygon<Client>) -> News<'a, Client
     News::new(client)
 }
 
-#[cfg(all(test, feature = "dotenvy"))]
+#[cfg(all(test, feature = "reqwest", feature = "serde"))]
 mod tests {
     use super::*;
+    use crate::cassette::{Cassette, test_client};
 
-    fn setup() -> Polygon<reqwest::Client> {
-        Polygon::new().expect("Failed to create client. Make sure POLYGON_API_KEY is set in .env file")
+    /// Replays the synthetic cassette `tests/cassettes/tickers/<name>.json`; record a real one with `POLYGON_RECORD=1`
+    fn setup(name: &str) -> Polygon<Cassette<reqwest::Client>> {
+        test_client(&format!("tickers/{name}"))
     }
 
     #[tokio::test]
-    #[ignore] // Run with: cargo test -- --ignored --test-threads=1
     async fn test_all_tickers() {
-        let client = setup();
-        let result = all(&client).limit("5").get().await;
-        assert!(result.is_ok(), "Failed to fetch all tickers: {result:?}");
+        let client = setup("all_tickers");
+        let tickers = all(&client)
+            .limit("5")
+            .typed()
+            .get()
+            .await
+            .expect("Failed to fetch all tickers");
+        assert_eq!(tickers.len(), 2);
+        assert_eq!(tickers[0].name.as_deref(), Some("Agilent Technologies Inc."));
+        assert_eq!(tickers[1].active, Some(false));
+        assert_eq!(tickers[1].delisted_utc.as_deref(), Some("2012-03-08T05:00:00Z"));
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_ticker_details() {
-        let client = setup();
-        let result = details(&client, "AAPL").get().await;
-        assert!(result.is_ok(), "Failed to fetch ticker details: {result:?}");
+        let client = setup("ticker_details");
+        let ticker = details(&client, "AAPL")
+            .typed()
+            .get()
+            .await
+            .expect("Failed to fetch ticker details");
+        assert_eq!(ticker.name.as_deref(), Some("Apple Inc."));
+        assert_eq!(ticker.primary_exchange.as_deref(), Some("XNAS"));
+        assert_eq!(ticker.type_.as_deref(), Some("CS"));
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_related_tickers() {
-        let client = setup();
-        let result = related(&client, "AAPL").get().await;
-        assert!(result.is_ok(), "Failed to fetch related tickers: {result:?}");
+        let client = setup("related_tickers");
+        let body = related(&client, "AAPL")
+            .get()
+            .await
+            .expect("Failed to fetch related tickers");
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let tickers: Vec<_> = json["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| &r["ticker"])
+            .collect();
+        assert_eq!(tickers, ["MSFT", "GOOGL", "AMZN"]);
+        assert_eq!(json["stock_symbol"], "AAPL");
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_ticker_types() {
-        let client = setup();
-        let result = types(&client).get().await;
-        assert!(result.is_ok(), "Failed to fetch ticker types: {result:?}");
+        let client = setup("ticker_types");
+        let body = types(&client).get().await.expect("Failed to fetch ticker types");
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["count"], 2);
+        assert_eq!(json["results"][0]["code"], "CS");
+        assert_eq!(json["results"][1]["description"], "Exchange Traded Fund");
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_ticker_events() {
-        let client = setup();
-        let result = events(&client, "AAPL").get().await;
-        assert!(result.is_ok(), "Failed to fetch ticker events: {result:?}");
+        let client = setup("ticker_events");
+        let events = events(&client, "AAPL")
+            .typed()
+            .get()
+            .await
+            .expect("Failed to fetch ticker events");
+        assert_eq!(events.cik, "0001326801");
+        let changes: Vec<_> = events
+            .events
+            .unwrap()
+            .into_iter()
+            .map(|e| e.ticker_change.ticker)
+            .collect();
+        assert_eq!(changes, ["META", "FB"]);
+    }
+
+    #[tokio::test]
+    async fn test_ticker_events_text() {
+        let csv = events(&setup("ticker_events"), "AAPL")
+            .as_csv()
+            .get()
+            .await
+            .expect("Failed to fetch ticker events");
+        assert_eq!(
+            csv,
+            "date,ticker_change.ticker,type\n\
+             2022-06-09,META,ticker_change\n\
+             2012-05-18,FB,ticker_change\n"
+        );
+
+        let ndjson = events(&setup("ticker_events"), "AAPL")
+            .as_ndjson()
+            .get()
+            .await
+            .expect("Failed to fetch ticker events");
+        assert_eq!(
+            ndjson.lines().next(),
+            Some(r#"{"date":"2022-06-09","ticker_change.ticker":"META","type":"ticker_change"}"#)
+        );
+        assert_eq!(ndjson.lines().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_news() {
+        let client = setup("news");
+        let news = news(&client)
+            .ticker("UBS")
+            .limit("1")
+            .typed()
+            .get()
+            .await
+            .expect("Failed to fetch news");
+        assert_eq!(news.len(), 1);
+        assert_eq!(
+            news[0].title.as_deref(),
+            Some("Markets are underestimating Fed cuts: UBS")
+        );
+        assert_eq!(
+            news[0].publisher.as_ref().and_then(|p| p.name.as_deref()),
+            Some("Investing.com")
+        );
+        assert_eq!(
+            news[0].insight("UBS").and_then(|i| i.sentiment.as_deref()),
+            Some("positive")
+        );
     }
 }
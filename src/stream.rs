@@ -0,0 +1,768 @@
+//! WebSocket streaming client for real-time and delayed feeds
+//!
+//! Connects to one of Polygon's WebSocket clusters, authenticates with the API key held by
+//! [`Polygon`], and yields decoded [`Event`]s as an async [`Stream`](futures_util::Stream).
+//! If the connection drops it is re-established automatically and every active subscription
+//! is restored. Events are buffered up to [`Builder::capacity`], and [`Overflow`] decides what
+//! happens when the consumer falls behind.
+//!
+//! This module is only available when the `stream` feature is enabled.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use polygon::Polygon;
+//! use polygon::stream::{self, Cluster, Event};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Polygon::default().with_key("your_api_key");
+//!
+//! let mut conn = stream::cluster(&client, Cluster::Stocks)
+//!     .subscribe(["T.AAPL", "Q.AAPL", "AM.*"])
+//!     .connect()
+//!     .await?;
+//!
+//! while let Some(event) = conn.next().await {
+//!     if let Event::Trade(trade) = event? {
+//!         println!("{:?} {:?}", trade.symbol, trade.price);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod event;
+pub mod subscription;
+
+use std::collections::BTreeSet;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::client::Polygon;
+use crate::error::{Error, Result};
+use crate::request::Request;
+
+pub use event::Event;
+pub use subscription::{Channel, Diff, Subscription};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// WebSocket cluster (asset class)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cluster {
+    /// US stocks
+    Stocks,
+    /// US options
+    Options,
+    /// Forex
+    Forex,
+    /// Crypto
+    Crypto,
+    /// Indices
+    Indices,
+    /// Futures
+    Futures,
+}
+
+impl AsRef<str> for Cluster {
+    fn as_ref(&self) -> &str {
+        match self {
+            Cluster::Stocks => "stocks",
+            Cluster::Options => "options",
+            Cluster::Forex => "forex",
+            Cluster::Crypto => "crypto",
+            Cluster::Indices => "indices",
+            Cluster::Futures => "futures",
+        }
+    }
+}
+
+impl std::fmt::Display for Cluster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+/// Feed to connect to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Feed {
+    /// Real-time data (`wss://socket.polygon.io`)
+    #[default]
+    RealTime,
+    /// 15-minute delayed data (`wss://delayed.polygon.io`)
+    Delayed,
+}
+
+impl Feed {
+    /// Base URL of this feed
+    pub fn url(&self) -> &'static str {
+        match self {
+            Feed::RealTime => "wss://socket.polygon.io",
+            Feed::Delayed => "wss://delayed.polygon.io",
+        }
+    }
+}
+
+/// What a connection does when its event buffer is full because the consumer is not keeping up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Stop reading the socket until there is room, so no event is lost (default)
+    ///
+    /// The server may disconnect a client that stays behind for too long, in which case the
+    /// connection is re-established.
+    #[default]
+    Wait,
+    /// Drop new events until there is room, then yield an [`Event::Lagged`] with the number dropped
+    Drop,
+}
+
+/// Create a connection builder for a cluster
+pub fn cluster<Client: Request>(client: &Polygon<Client>, cluster: Cluster) -> Builder {
+    Builder {
+        api_key: client.api_key().map(str::to_string),
+        cluster,
+        feed: Feed::default(),
+        url: None,
+        subscriptions: BTreeSet::new(),
+        max_reconnects: Some(10),
+        reconnect_delay: Duration::from_secs(1),
+        capacity: 10_000,
+        overflow: Overflow::default(),
+    }
+}
+
+/// Create a connection builder for the stocks cluster
+pub fn stocks<Client: Request>(client: &Polygon<Client>) -> Builder {
+    cluster(client, Cluster::Stocks)
+}
+
+/// Create a connection builder for the options cluster
+pub fn options<Client: Request>(client: &Polygon<Client>) -> Builder {
+    cluster(client, Cluster::Options)
+}
+
+/// Create a connection builder for the forex cluster
+pub fn forex<Client: Request>(client: &Polygon<Client>) -> Builder {
+    cluster(client, Cluster::Forex)
+}
+
+/// Create a connection builder for the crypto cluster
+pub fn crypto<Client: Request>(client: &Polygon<Client>) -> Builder {
+    cluster(client, Cluster::Crypto)
+}
+
+/// Create a connection builder for the indices cluster
+pub fn indices<Client: Request>(client: &Polygon<Client>) -> Builder {
+    cluster(client, Cluster::Indices)
+}
+
+/// Create a connection builder for the futures cluster
+pub fn futures<Client: Request>(client: &Polygon<Client>) -> Builder {
+    cluster(client, Cluster::Futures)
+}
+
+/// WebSocket connection builder
+#[derive(Clone)]
+pub struct Builder {
+    api_key: Option<String>,
+    /// Cluster to connect to
+    pub cluster: Cluster,
+    /// Real-time or delayed feed
+    pub feed: Feed,
+    /// Base URL overriding the feed (e.g., a local test server)
+    pub url: Option<String>,
+    /// Subscriptions sent right after authenticating (e.g., "T.AAPL", "AM.*")
+    pub subscriptions: BTreeSet<String>,
+    /// Reconnect attempts before giving up, per disconnect (`None` retries forever)
+    pub max_reconnects: Option<u32>,
+    /// Delay before the first reconnect attempt, doubled on each failure (capped at 30s)
+    pub reconnect_delay: Duration,
+    /// Number of events buffered for the consumer
+    pub capacity: usize,
+    /// What to do when the buffer is full
+    pub overflow: Overflow,
+}
+
+impl std::fmt::Debug for Builder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Builder")
+            .field("api_key", &self.api_key.as_ref().map(|_| "[REDACTED]"))
+            .field("cluster", &self.cluster)
+            .field("feed", &self.feed)
+            .field("url", &self.url)
+            .field("subscriptions", &self.subscriptions)
+            .field("max_reconnects", &self.max_reconnects)
+            .field("reconnect_delay", &self.reconnect_delay)
+            .field("capacity", &self.capacity)
+            .field("overflow", &self.overflow)
+            .finish()
+    }
+}
+
+impl Builder {
+    /// Set the feed
+    pub fn feed(mut self, feed: Feed) -> Self {
+        self.feed = feed;
+        self
+    }
+
+    /// Set a custom base URL (the cluster name is appended as the path)
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Add subscriptions to send once connected
+    pub fn subscribe(mut self, params: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.subscriptions.extend(params.into_iter().map(Into::into));
+        self
+    }
+
+    /// Add every param of a typed [`Subscription`]
+    pub fn subscription(mut self, subscription: &Subscription) -> Self {
+        self.subscriptions.extend(subscription.params());
+        self
+    }
+
+    /// Set the maximum number of reconnect attempts per disconnect
+    pub fn max_reconnects(mut self, max: impl Into<Option<u32>>) -> Self {
+        self.max_reconnects = max.into();
+        self
+    }
+
+    /// Set the initial reconnect delay
+    pub fn reconnect_delay(mut self, delay: Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+
+    /// Set the number of events buffered for the consumer (default 10,000, at least 1)
+    ///
+    /// A firehose subscription such as `T.*` can outpace a slow consumer, so the buffer is
+    /// bounded and `overflow` decides what happens when it is full.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Set what happens when the event buffer is full (default [`Overflow::Wait`])
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Connect, authenticate and subscribe
+    ///
+    /// Must be called from within a Tokio runtime; the connection is driven by a spawned task.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no API key is set, the connection cannot be established, or
+    /// authentication is rejected.
+    pub async fn connect(self) -> Result<Connection> {
+        let api_key = self.api_key.clone().ok_or(Error::MissingApiKey)?;
+        let url = format!(
+            "{}/{}",
+            self.url.as_deref().unwrap_or(self.feed.url()).trim_end_matches('/'),
+            self.cluster
+        );
+
+        let socket = handshake(&url, &api_key, &self.subscriptions).await?;
+
+        let (events_tx, events) = mpsc::channel(self.capacity.max(1));
+        let (commands, commands_rx) = mpsc::unbounded_channel();
+        let worker = Worker {
+            url,
+            api_key,
+            subscriptions: self.subscriptions,
+            max_reconnects: self.max_reconnects,
+            reconnect_delay: self.reconnect_delay,
+            events: events_tx,
+            overflow: self.overflow,
+            dropped: 0,
+            commands: commands_rx,
+        };
+        tokio::spawn(worker.run(socket));
+
+        Ok(Connection { events, commands })
+    }
+}
+
+/// Commands sent from a [`Connection`] to its background task
+#[derive(Debug)]
+enum Command {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+    Update(BTreeSet<String>),
+    Close,
+}
+
+/// An authenticated WebSocket connection
+///
+/// Yields decoded events as a [`Stream`](futures_util::Stream). The stream ends once the
+/// connection is closed or reconnecting gives up, in which case the last item is the error.
+#[derive(Debug)]
+pub struct Connection {
+    events: mpsc::Receiver<Result<Event>>,
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+impl Connection {
+    /// Subscribe to additional channels (e.g., "T.MSFT")
+    pub fn subscribe(&self, params: impl IntoIterator<Item = impl Into<String>>) -> Result<()> {
+        self.send(Command::Subscribe(params.into_iter().map(Into::into).collect()))
+    }
+
+    /// Unsubscribe from channels
+    pub fn unsubscribe(&self, params: impl IntoIterator<Item = impl Into<String>>) -> Result<()> {
+        self.send(Command::Unsubscribe(params.into_iter().map(Into::into).collect()))
+    }
+
+    /// Replace the active subscriptions with `desired`
+    ///
+    /// Only the difference is sent, so unchanged channels keep streaming without interruption.
+    pub fn update(&self, desired: &Subscription) -> Result<()> {
+        self.send(Command::Update(desired.params()))
+    }
+
+    /// Close the connection
+    pub fn close(&self) -> Result<()> {
+        self.send(Command::Close)
+    }
+
+    fn send(&self, command: Command) -> Result<()> {
+        self.commands
+            .send(command)
+            .map_err(|_| Error::Custom("WebSocket connection is closed".to_string()))
+    }
+}
+
+impl futures_util::Stream for Connection {
+    type Item = Result<Event>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+/// Background task owning the socket
+struct Worker {
+    url: String,
+    api_key: String,
+    subscriptions: BTreeSet<String>,
+    max_reconnects: Option<u32>,
+    reconnect_delay: Duration,
+    events: mpsc::Sender<Result<Event>>,
+    overflow: Overflow,
+    /// Events dropped since the last [`Event::Lagged`]
+    dropped: u64,
+    commands: mpsc::UnboundedReceiver<Command>,
+}
+
+impl Worker {
+    async fn run(mut self, mut socket: Socket) {
+        loop {
+            tokio::select! {
+                message = socket.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        for event in decode(&text) {
+                            if !self.emit(event).await {
+                                return;
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => match self.reconnect().await {
+                        Ok(s) => socket = s,
+                        Err(e) => {
+                            let _ = self.events.send(Err(e)).await;
+                            return;
+                        }
+                    },
+                    Some(Ok(_)) => {}
+                },
+                command = self.commands.recv() => {
+                    let result = match command {
+                        Some(Command::Subscribe(params)) => {
+                            self.subscriptions.extend(params.iter().cloned());
+                            send_action(&mut socket, "subscribe", &params).await
+                        }
+                        Some(Command::Unsubscribe(params)) => {
+                            for p in &params {
+                                self.subscriptions.remove(p);
+                            }
+                            send_action(&mut socket, "unsubscribe", &params).await
+                        }
+                        Some(Command::Update(desired)) => {
+                            let diff = Diff::between(&desired, &self.subscriptions);
+                            self.subscriptions = desired;
+                            send_diff(&mut socket, &diff).await
+                        }
+                        Some(Command::Close) | None => {
+                            let _ = socket.close(None).await;
+                            return;
+                        }
+                    };
+                    // A failed write means the socket is gone; the next read triggers a reconnect
+                    let _ = result;
+                }
+            }
+        }
+    }
+
+    /// Pass an event to the consumer following the overflow policy, `false` once it is gone
+    async fn emit(&mut self, event: Result<Event>) -> bool {
+        use mpsc::error::TrySendError;
+
+        if self.overflow == Overflow::Wait {
+            return self.events.send(event).await.is_ok();
+        }
+        if self.dropped > 0 {
+            match self.events.try_send(Ok(Event::Lagged(self.dropped))) {
+                Ok(()) => self.dropped = 0,
+                Err(TrySendError::Full(_)) => {
+                    self.dropped += 1;
+                    return true;
+                }
+                Err(TrySendError::Closed(_)) => return false,
+            }
+        }
+        match self.events.try_send(event) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                self.dropped += 1;
+                true
+            }
+            Err(TrySendError::Closed(_)) => false,
+        }
+    }
+
+    async fn reconnect(&mut self) -> Result<Socket> {
+        let mut delay = self.reconnect_delay;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            tokio::time::sleep(delay).await;
+            match handshake(&self.url, &self.api_key, &self.subscriptions).await {
+                Ok(socket) => return Ok(socket),
+                Err(e) if self.max_reconnects.is_some_and(|max| attempt >= max) => return Err(e),
+                Err(_) => delay = (delay * 2).min(Duration::from_secs(30)),
+            }
+        }
+    }
+}
+
+/// Connect, authenticate and send the given subscriptions
+async fn handshake(url: &str, api_key: &str, subscriptions: &BTreeSet<String>) -> Result<Socket> {
+    let (mut socket, _) = tokio_tungstenite::connect_async(url).await?;
+
+    socket
+        .send(Message::text(
+            serde_json::json!({ "action": "auth", "params": api_key }).to_string(),
+        ))
+        .await?;
+
+    // Wait for the auth result, skipping the initial "connected" status
+    loop {
+        let text = match socket.next().await {
+            Some(Ok(Message::Text(text))) => text,
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(e.into()),
+            None => return Err(Error::Custom("WebSocket closed during authentication".to_string())),
+        };
+        for event in decode(&text) {
+            if let Ok(Event::Status(status)) = event {
+                match status.status.as_deref() {
+                    Some("auth_success") => {
+                        send_action(&mut socket, "subscribe", subscriptions).await?;
+                        return Ok(socket);
+                    }
+                    Some("auth_failed") => {
+                        return Err(Error::Custom(format!(
+                            "WebSocket authentication failed: {}",
+                            status.message.unwrap_or_default()
+                        )));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Send a subscribe/unsubscribe action for a set of channels
+async fn send_action<'a>(
+    socket: &mut Socket,
+    action: &str,
+    params: impl IntoIterator<Item = &'a String>,
+) -> Result<()> {
+    if let Some(frame) = subscription::frame(action, params) {
+        socket.send(Message::text(frame)).await?;
+    }
+    Ok(())
+}
+
+/// Send the frames of a subscription diff
+async fn send_diff(socket: &mut Socket, diff: &Diff) -> Result<()> {
+    for frame in diff.frames() {
+        socket.send(Message::text(frame)).await?;
+    }
+    Ok(())
+}
+
+/// Decode a text frame into events
+fn decode(text: &str) -> Vec<Result<Event>> {
+    match decoder::run(serde_json::from_str, event::decode::events, text) {
+        Ok(events) => events.into_iter().map(Ok).collect(),
+        Err(e) => vec![Err(e.into())],
+    }
+}
+
+#[cfg(all(test, feature = "reqwest"))]
+mod tests {
+    use super::*;
+    use crate::Polygon;
+    use tokio::net::TcpListener;
+
+    /// Accept one client, authenticate it and return the socket with its subscribe params
+    async fn accept(listener: &TcpListener) -> (WebSocketStream<TcpStream>, String) {
+        let (tcp, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+        let text = |s: &str| Message::text(s.to_string());
+
+        ws.send(text(
+            r#"[{"ev":"status","status":"connected","message":"Connected Successfully"}]"#,
+        ))
+        .await
+        .unwrap();
+        let auth = ws.next().await.unwrap().unwrap().into_text().unwrap();
+        assert_eq!(auth.as_str(), r#"{"action":"auth","params":"test_key"}"#);
+        ws.send(text(
+            r#"[{"ev":"status","status":"auth_success","message":"authenticated"}]"#,
+        ))
+        .await
+        .unwrap();
+
+        let subscribe: serde_json::Value =
+            serde_json::from_str(&ws.next().await.unwrap().unwrap().into_text().unwrap()).unwrap();
+        assert_eq!(subscribe["action"], "subscribe");
+        (ws, subscribe["params"].as_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_stream_reconnects_and_resubscribes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (mut ws, params) = accept(&listener).await;
+            assert_eq!(params, "Q.AAPL,T.AAPL");
+            ws.send(Message::text(
+                r#"[{"ev":"T","sym":"AAPL","x":4,"i":"52983525029461","z":3,"p":114.125,"s":100,"c":[0,12],"t":1536036818784,"q":3681328}]"#,
+            ))
+            .await
+            .unwrap();
+
+            // The client subscribes to more channels, then the server drops the connection
+            let more = ws.next().await.unwrap().unwrap().into_text().unwrap();
+            assert_eq!(more.as_str(), r#"{"action":"subscribe","params":"AM.*"}"#);
+            drop(ws);
+
+            let (mut ws, params) = accept(&listener).await;
+            assert_eq!(params, "AM.*,Q.AAPL,T.AAPL");
+            ws.send(Message::text(
+                r#"[{"ev":"Q","sym":"MSFT","bx":4,"bp":114.125,"bs":100,"ax":7,"ap":114.128,"as":160,"c":0,"i":[604],"t":1536036818784,"q":50385480,"z":3},{"ev":"XQ","pair":"BTC-USD","bp":60000.5},{"ev":"NOI","T":"AAPL"}]"#,
+            ))
+            .await
+            .unwrap();
+            ws.next().await;
+        });
+
+        let client = Polygon::default().with_key("test_key");
+        let mut conn = cluster(&client, Cluster::Stocks)
+            .url(format!("{url}/"))
+            .subscribe(["T.AAPL", "Q.AAPL"])
+            .reconnect_delay(Duration::from_millis(10))
+            .connect()
+            .await
+            .unwrap();
+
+        let Event::Trade(trade) = conn.next().await.unwrap().unwrap() else {
+            panic!("expected trade");
+        };
+        assert_eq!(trade.symbol.as_deref(), Some("AAPL"));
+        assert_eq!(trade.price, Some(114.125));
+        assert_eq!(trade.conditions, Some(vec![0, 12]));
+
+        conn.subscribe(["AM.*"]).unwrap();
+
+        let Event::Quote(quote) = conn.next().await.unwrap().unwrap() else {
+            panic!("expected quote");
+        };
+        assert_eq!(quote.symbol.as_deref(), Some("MSFT"));
+        assert_eq!(quote.ask_size, Some(160.0));
+        let Event::CryptoQuote(quote) = conn.next().await.unwrap().unwrap() else {
+            panic!("expected crypto quote");
+        };
+        assert_eq!(quote.pair.as_deref(), Some("BTC-USD"));
+        assert_eq!(quote.bid_price, Some(60000.5));
+        assert!(matches!(conn.next().await, Some(Ok(Event::Unknown(ev))) if ev == "NOI"));
+
+        conn.close().unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stream_update_diffs_subscription() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (mut ws, params) = accept(&listener).await;
+            assert_eq!(params, "Q.AAPL,T.AAPL");
+
+            // Only the difference to the current subscription is sent
+            let more = ws.next().await.unwrap().unwrap().into_text().unwrap();
+            assert_eq!(more.as_str(), r#"{"action":"subscribe","params":"AM.*"}"#);
+            let fewer = ws.next().await.unwrap().unwrap().into_text().unwrap();
+            assert_eq!(fewer.as_str(), r#"{"action":"unsubscribe","params":"Q.AAPL"}"#);
+            drop(ws);
+
+            // Reconnecting subscribes to the updated set
+            let (mut ws, params) = accept(&listener).await;
+            assert_eq!(params, "AM.*,T.AAPL");
+            ws.send(Message::text(
+                r#"[{"ev":"status","status":"success","message":"subscribed to: AM.*"}]"#,
+            ))
+            .await
+            .unwrap();
+            ws.next().await;
+        });
+
+        let client = Polygon::default().with_key("test_key");
+        let watchlist = Subscription::new()
+            .channels([Channel::Trades, Channel::Quotes])
+            .ticker("AAPL");
+        let mut conn = cluster(&client, Cluster::Stocks)
+            .url(format!("{url}/"))
+            .subscription(&watchlist)
+            .reconnect_delay(Duration::from_millis(10))
+            .connect()
+            .await
+            .unwrap();
+
+        conn.update(
+            &Subscription::new()
+                .channel(Channel::Trades)
+                .ticker("AAPL")
+                .param("AM.*"),
+        )
+        .unwrap();
+
+        let Event::Status(status) = conn.next().await.unwrap().unwrap() else {
+            panic!("expected status");
+        };
+        assert_eq!(status.status.as_deref(), Some("success"));
+
+        conn.close().unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stream_drops_when_full() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let trades: Vec<String> = (0..5)
+            .map(|i| format!(r#"{{"ev":"T","sym":"AAPL","p":{i}}}"#))
+            .collect();
+
+        let server = tokio::spawn(async move {
+            let (mut ws, _) = accept(&listener).await;
+            ws.send(Message::text(format!("[{}]", trades.join(",")))).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            ws.send(Message::text(
+                r#"[{"ev":"status","status":"success","message":"subscribed to: T.AAPL"}]"#,
+            ))
+            .await
+            .unwrap();
+            ws.next().await;
+        });
+
+        let client = Polygon::default().with_key("test_key");
+        let mut conn = cluster(&client, Cluster::Stocks)
+            .url(format!("{url}/"))
+            .subscribe(["T.AAPL"])
+            .capacity(2)
+            .overflow(Overflow::Drop)
+            .connect()
+            .await
+            .unwrap();
+
+        // Only two trades fit while the consumer is away
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        for price in [0.0, 1.0] {
+            let Event::Trade(trade) = conn.next().await.unwrap().unwrap() else {
+                panic!("expected trade");
+            };
+            assert_eq!(trade.price, Some(price));
+        }
+        assert!(matches!(conn.next().await, Some(Ok(Event::Lagged(3)))));
+        assert!(matches!(conn.next().await, Some(Ok(Event::Status(_)))));
+
+        conn.close().unwrap();
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn test_decode_cluster_events() {
+        let events = decoder::run(
+            serde_json::from_str,
+            event::decode::events,
+            r#"[
+                {"ev":"XT","pair":"BTC-USD","p":33021.9,"t":1610462007425,"s":0.01,"c":[2],"i":"14272084","x":1,"r":1610462007576},
+                {"ev":"XL2","pair":"BTC-USD","t":1598918220000,"x":1,"r":1598918220461,"b":[[11785.2,0.1]],"a":[[11786.1,0.5]]},
+                {"ev":"XA","pair":"BCD-BTC","v":951.6112,"o":0.0020348,"c":0.0020348,"h":0.0020353,"l":0.0020348,"s":1610463240000,"e":1610463300000},
+                {"ev":"C","p":"USD/CNH","x":44,"a":6.83366,"b":6.83363,"t":1536036818784},
+                {"ev":"CAS","pair":"USD/EUR","o":0.8687,"c":0.8688,"h":0.8688,"l":0.8687,"v":4,"s":1539145740000},
+                {"ev":"V","val":3988.5,"T":"I:SPX","t":1678220098130}
+            ]"#,
+        )
+        .unwrap();
+
+        assert!(
+            matches!(&events[0], Event::CryptoTrade(t) if t.price == Some(33021.9) && t.received == Some(1610462007576))
+        );
+        assert!(matches!(&events[1], Event::CryptoBook(b) if b.asks == Some(vec![vec![11786.1, 0.5]])));
+        assert!(matches!(&events[2], Event::MinuteAggregate(a) if a.symbol.as_deref() == Some("BCD-BTC")));
+        assert!(
+            matches!(&events[3], Event::ForexQuote(q) if q.pair.as_deref() == Some("USD/CNH") && q.bid_price == Some(6.83363))
+        );
+        assert!(matches!(&events[4], Event::SecondAggregate(a) if a.symbol.as_deref() == Some("USD/EUR")));
+        assert!(
+            matches!(&events[5], Event::IndexValue(v) if v.symbol.as_deref() == Some("I:SPX") && v.value == Some(3988.5))
+        );
+    }
+
+    #[test]
+    fn test_builder_debug_redacts_key() {
+        let client = Polygon::default().with_key("secret_key");
+        let debug = format!("{:?}", crypto(&client));
+        assert!(!debug.contains("secret_key"));
+        assert!(debug.contains("[REDACTED]"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_requires_api_key() {
+        let client = Polygon::default();
+        let result = stocks(&client).url("ws://127.0.0.1:1").connect().await;
+        assert!(matches!(result, Err(Error::MissingApiKey)));
+    }
+}
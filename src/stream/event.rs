@@ -0,0 +1,463 @@
+//! Event types delivered over the WebSocket feeds
+
+#[cfg(feature = "serde")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A decoded message from a WebSocket feed
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub enum Event {
+    /// Trade (`T`)
+    Trade(Trade),
+    /// Quote (`Q`)
+    Quote(Quote),
+    /// Per-second aggregate bar (`A`, and `XAS`/`CAS` for crypto and forex)
+    SecondAggregate(Aggregate),
+    /// Per-minute aggregate bar (`AM`, and `XA`/`CA` for crypto and forex)
+    MinuteAggregate(Aggregate),
+    /// Limit up/limit down band (`LULD`)
+    Luld(Luld),
+    /// Fair market value (`FMV`)
+    FairMarketValue(FairMarketValue),
+    /// Crypto trade (`XT`)
+    CryptoTrade(CryptoTrade),
+    /// Crypto quote (`XQ`)
+    CryptoQuote(CryptoQuote),
+    /// Crypto level 2 book snapshot (`XL2`)
+    CryptoBook(CryptoBook),
+    /// Forex quote (`C`)
+    ForexQuote(ForexQuote),
+    /// Index value (`V`)
+    IndexValue(IndexValue),
+    /// Connection, auth or subscription status (`status`)
+    Status(Status),
+    /// Event type this client does not decode, carrying its `ev` value
+    Unknown(String),
+    /// Number of events dropped because the consumer fell behind, with
+    /// [`Overflow::Drop`](super::Overflow::Drop); never sent by the server
+    Lagged(u64),
+}
+
+/// Trade event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct Trade {
+    /// Ticker symbol
+    pub symbol: Option<String>,
+    /// Exchange ID
+    pub exchange: Option<i64>,
+    /// Trade ID
+    pub id: Option<String>,
+    /// Tape (1 = NYSE, 2 = AMEX, 3 = Nasdaq)
+    pub tape: Option<i64>,
+    /// Trade price
+    pub price: Option<f64>,
+    /// Trade size
+    pub size: Option<f64>,
+    /// Trade conditions
+    pub conditions: Option<Vec<i64>>,
+    /// SIP timestamp (Unix milliseconds)
+    pub timestamp: Option<i64>,
+    /// Sequence number
+    pub sequence: Option<i64>,
+}
+
+/// Quote event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct Quote {
+    /// Ticker symbol
+    pub symbol: Option<String>,
+    /// Bid exchange ID
+    pub bid_exchange: Option<i64>,
+    /// Bid price
+    pub bid_price: Option<f64>,
+    /// Bid size
+    pub bid_size: Option<f64>,
+    /// Ask exchange ID
+    pub ask_exchange: Option<i64>,
+    /// Ask price
+    pub ask_price: Option<f64>,
+    /// Ask size
+    pub ask_size: Option<f64>,
+    /// Quote condition
+    pub condition: Option<i64>,
+    /// Quote indicators
+    pub indicators: Option<Vec<i64>>,
+    /// SIP timestamp (Unix milliseconds)
+    pub timestamp: Option<i64>,
+    /// Sequence number
+    pub sequence: Option<i64>,
+    /// Tape (1 = NYSE, 2 = AMEX, 3 = Nasdaq)
+    pub tape: Option<i64>,
+}
+
+/// Aggregate bar event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct Aggregate {
+    /// Ticker symbol, or the pair for crypto and forex (e.g., "BTC-USD", "USD/EUR")
+    pub symbol: Option<String>,
+    /// Volume in this bar
+    pub volume: Option<f64>,
+    /// Accumulated volume for the day
+    pub accumulated_volume: Option<f64>,
+    /// Official opening price for the day
+    pub official_open: Option<f64>,
+    /// Volume weighted average price for this bar
+    pub vwap: Option<f64>,
+    /// Opening price
+    pub open: Option<f64>,
+    /// Closing price
+    pub close: Option<f64>,
+    /// High price
+    pub high: Option<f64>,
+    /// Low price
+    pub low: Option<f64>,
+    /// Volume weighted average price for the day
+    pub day_vwap: Option<f64>,
+    /// Average trade size in this bar
+    pub average_size: Option<f64>,
+    /// Bar start (Unix milliseconds)
+    pub start: Option<i64>,
+    /// Bar end (Unix milliseconds)
+    pub end: Option<i64>,
+    /// Whether this is OTC
+    pub otc: Option<bool>,
+}
+
+/// Limit up/limit down event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct Luld {
+    /// Ticker symbol
+    pub symbol: Option<String>,
+    /// Upper price band
+    pub high_price: Option<f64>,
+    /// Lower price band
+    pub low_price: Option<f64>,
+    /// Indicators
+    pub indicators: Option<Vec<i64>>,
+    /// Tape (1 = NYSE, 2 = AMEX, 3 = Nasdaq)
+    pub tape: Option<i64>,
+    /// Timestamp (Unix milliseconds)
+    pub timestamp: Option<i64>,
+    /// Sequence number
+    pub sequence: Option<i64>,
+}
+
+/// Fair market value event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct FairMarketValue {
+    /// Ticker symbol
+    pub symbol: Option<String>,
+    /// Fair market value
+    pub fmv: Option<f64>,
+    /// Timestamp (Unix nanoseconds)
+    pub timestamp: Option<i64>,
+}
+
+/// Crypto trade event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct CryptoTrade {
+    /// Crypto pair (e.g., "BTC-USD")
+    pub pair: Option<String>,
+    /// Exchange ID
+    pub exchange: Option<i64>,
+    /// Trade ID
+    pub id: Option<String>,
+    /// Trade price
+    pub price: Option<f64>,
+    /// Trade size
+    pub size: Option<f64>,
+    /// Trade conditions
+    pub conditions: Option<Vec<i64>>,
+    /// Exchange timestamp (Unix milliseconds)
+    pub timestamp: Option<i64>,
+    /// Time Polygon received the trade (Unix milliseconds)
+    pub received: Option<i64>,
+}
+
+/// Crypto quote event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct CryptoQuote {
+    /// Crypto pair (e.g., "BTC-USD")
+    pub pair: Option<String>,
+    /// Exchange ID
+    pub exchange: Option<i64>,
+    /// Bid price
+    pub bid_price: Option<f64>,
+    /// Bid size
+    pub bid_size: Option<f64>,
+    /// Ask price
+    pub ask_price: Option<f64>,
+    /// Ask size
+    pub ask_size: Option<f64>,
+    /// Exchange timestamp (Unix milliseconds)
+    pub timestamp: Option<i64>,
+    /// Time Polygon received the quote (Unix milliseconds)
+    pub received: Option<i64>,
+}
+
+/// Crypto level 2 book snapshot event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct CryptoBook {
+    /// Crypto pair (e.g., "BTC-USD")
+    pub pair: Option<String>,
+    /// Exchange ID
+    pub exchange: Option<i64>,
+    /// Bid levels as `[price, size]`
+    pub bids: Option<Vec<Vec<f64>>>,
+    /// Ask levels as `[price, size]`
+    pub asks: Option<Vec<Vec<f64>>>,
+    /// Exchange timestamp (Unix milliseconds)
+    pub timestamp: Option<i64>,
+    /// Time Polygon received the snapshot (Unix milliseconds)
+    pub received: Option<i64>,
+}
+
+/// Forex quote event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct ForexQuote {
+    /// Currency pair (e.g., "USD/EUR")
+    pub pair: Option<String>,
+    /// Exchange ID
+    pub exchange: Option<i64>,
+    /// Ask price
+    pub ask_price: Option<f64>,
+    /// Bid price
+    pub bid_price: Option<f64>,
+    /// Timestamp (Unix milliseconds)
+    pub timestamp: Option<i64>,
+}
+
+/// Index value event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct IndexValue {
+    /// Index ticker (e.g., "I:SPX")
+    pub symbol: Option<String>,
+    /// Index value
+    pub value: Option<f64>,
+    /// Timestamp (Unix milliseconds)
+    pub timestamp: Option<i64>,
+}
+
+/// Status message sent by the server
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize, JsonSchema))]
+pub struct Status {
+    /// Status code (e.g., "connected", "auth_success", "auth_failed", "success")
+    pub status: Option<String>,
+    /// Human readable message
+    pub message: Option<String>,
+}
+
+pub mod decode {
+    //! Decode functions for WebSocket messages
+    use super::*;
+    use decoder::decode::{bool, f64, i64, map, sequence, string};
+
+    /// Decode a WebSocket text frame (a JSON array of events)
+    pub fn events(value: decoder::Value) -> decoder::Result<Vec<Event>> {
+        sequence(event)(value)
+    }
+
+    /// Decode a single event, dispatching on its `ev` field
+    pub fn event(value: decoder::Value) -> decoder::Result<Event> {
+        let ev = map(value.clone())?.required("ev", string)?;
+
+        Ok(match ev.as_str() {
+            "T" => Event::Trade(trade(value)?),
+            "Q" => Event::Quote(quote(value)?),
+            "A" | "XAS" | "CAS" => Event::SecondAggregate(aggregate(value)?),
+            "AM" | "XA" | "CA" => Event::MinuteAggregate(aggregate(value)?),
+            "LULD" => Event::Luld(luld(value)?),
+            "FMV" => Event::FairMarketValue(fair_market_value(value)?),
+            "XT" => Event::CryptoTrade(crypto_trade(value)?),
+            "XQ" => Event::CryptoQuote(crypto_quote(value)?),
+            "XL2" => Event::CryptoBook(crypto_book(value)?),
+            "C" => Event::ForexQuote(forex_quote(value)?),
+            "V" => Event::IndexValue(index_value(value)?),
+            "status" => Event::Status(status(value)?),
+            _ => Event::Unknown(ev),
+        })
+    }
+
+    /// Decode a trade event
+    pub fn trade(value: decoder::Value) -> decoder::Result<Trade> {
+        let mut trade = map(value)?;
+
+        Ok(Trade {
+            symbol: trade.optional("sym", string)?,
+            exchange: trade.optional("x", i64)?,
+            id: trade.optional("i", string)?,
+            tape: trade.optional("z", i64)?,
+            price: trade.optional("p", f64)?,
+            size: trade.optional("s", f64)?,
+            conditions: trade.optional("c", sequence(i64))?,
+            timestamp: trade.optional("t", i64)?,
+            sequence: trade.optional("q", i64)?,
+        })
+    }
+
+    /// Decode a quote event
+    pub fn quote(value: decoder::Value) -> decoder::Result<Quote> {
+        let mut quote = map(value)?;
+
+        Ok(Quote {
+            symbol: quote.optional("sym", string)?,
+            bid_exchange: quote.optional("bx", i64)?,
+            bid_price: quote.optional("bp", f64)?,
+            bid_size: quote.optional("bs", f64)?,
+            ask_exchange: quote.optional("ax", i64)?,
+            ask_price: quote.optional("ap", f64)?,
+            ask_size: quote.optional("as", f64)?,
+            condition: quote.optional("c", i64)?,
+            indicators: quote.optional("i", sequence(i64))?,
+            timestamp: quote.optional("t", i64)?,
+            sequence: quote.optional("q", i64)?,
+            tape: quote.optional("z", i64)?,
+        })
+    }
+
+    /// Decode an aggregate bar event
+    pub fn aggregate(value: decoder::Value) -> decoder::Result<Aggregate> {
+        let mut agg = map(value)?;
+
+        let symbol = match agg.optional("sym", string)? {
+            Some(symbol) => Some(symbol),
+            None => agg.optional("pair", string)?,
+        };
+
+        Ok(Aggregate {
+            symbol,
+            volume: agg.optional("v", f64)?,
+            accumulated_volume: agg.optional("av", f64)?,
+            official_open: agg.optional("op", f64)?,
+            vwap: agg.optional("vw", f64)?,
+            open: agg.optional("o", f64)?,
+            close: agg.optional("c", f64)?,
+            high: agg.optional("h", f64)?,
+            low: agg.optional("l", f64)?,
+            day_vwap: agg.optional("a", f64)?,
+            average_size: agg.optional("z", f64)?,
+            start: agg.optional("s", i64)?,
+            end: agg.optional("e", i64)?,
+            otc: agg.optional("otc", bool)?,
+        })
+    }
+
+    /// Decode a limit up/limit down event
+    pub fn luld(value: decoder::Value) -> decoder::Result<Luld> {
+        let mut luld = map(value)?;
+
+        Ok(Luld {
+            symbol: luld.optional("T", string)?,
+            high_price: luld.optional("h", f64)?,
+            low_price: luld.optional("l", f64)?,
+            indicators: luld.optional("i", sequence(i64))?,
+            tape: luld.optional("z", i64)?,
+            timestamp: luld.optional("t", i64)?,
+            sequence: luld.optional("q", i64)?,
+        })
+    }
+
+    /// Decode a fair market value event
+    pub fn fair_market_value(value: decoder::Value) -> decoder::Result<FairMarketValue> {
+        let mut fmv = map(value)?;
+
+        Ok(FairMarketValue {
+            symbol: fmv.optional("sym", string)?,
+            fmv: fmv.optional("fmv", f64)?,
+            timestamp: fmv.optional("t", i64)?,
+        })
+    }
+
+    /// Decode a crypto trade event
+    pub fn crypto_trade(value: decoder::Value) -> decoder::Result<CryptoTrade> {
+        let mut trade = map(value)?;
+
+        Ok(CryptoTrade {
+            pair: trade.optional("pair", string)?,
+            exchange: trade.optional("x", i64)?,
+            id: trade.optional("i", string)?,
+            price: trade.optional("p", f64)?,
+            size: trade.optional("s", f64)?,
+            conditions: trade.optional("c", sequence(i64))?,
+            timestamp: trade.optional("t", i64)?,
+            received: trade.optional("r", i64)?,
+        })
+    }
+
+    /// Decode a crypto quote event
+    pub fn crypto_quote(value: decoder::Value) -> decoder::Result<CryptoQuote> {
+        let mut quote = map(value)?;
+
+        Ok(CryptoQuote {
+            pair: quote.optional("pair", string)?,
+            exchange: quote.optional("x", i64)?,
+            bid_price: quote.optional("bp", f64)?,
+            bid_size: quote.optional("bs", f64)?,
+            ask_price: quote.optional("ap", f64)?,
+            ask_size: quote.optional("as", f64)?,
+            timestamp: quote.optional("t", i64)?,
+            received: quote.optional("r", i64)?,
+        })
+    }
+
+    /// Decode a crypto level 2 book snapshot event
+    pub fn crypto_book(value: decoder::Value) -> decoder::Result<CryptoBook> {
+        let mut book = map(value)?;
+
+        Ok(CryptoBook {
+            pair: book.optional("pair", string)?,
+            exchange: book.optional("x", i64)?,
+            bids: book.optional("b", sequence(sequence(f64)))?,
+            asks: book.optional("a", sequence(sequence(f64)))?,
+            timestamp: book.optional("t", i64)?,
+            received: book.optional("r", i64)?,
+        })
+    }
+
+    /// Decode a forex quote event
+    pub fn forex_quote(value: decoder::Value) -> decoder::Result<ForexQuote> {
+        let mut quote = map(value)?;
+
+        Ok(ForexQuote {
+            pair: quote.optional("p", string)?,
+            exchange: quote.optional("x", i64)?,
+            ask_price: quote.optional("a", f64)?,
+            bid_price: quote.optional("b", f64)?,
+            timestamp: quote.optional("t", i64)?,
+        })
+    }
+
+    /// Decode an index value event
+    pub fn index_value(value: decoder::Value) -> decoder::Result<IndexValue> {
+        let mut index = map(value)?;
+
+        Ok(IndexValue {
+            symbol: index.optional("T", string)?,
+            value: index.optional("val", f64)?,
+            timestamp: index.optional("t", i64)?,
+        })
+    }
+
+    /// Decode a status message
+    pub fn status(value: decoder::Value) -> decoder::Result<Status> {
+        let mut status = map(value)?;
+
+        Ok(Status {
+            status: status.optional("status", string)?,
+            message: status.optional("message", string)?,
+        })
+    }
+}
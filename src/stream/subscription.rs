@@ -0,0 +1,234 @@
+//! Typed subscription management
+//!
+//! A [`Subscription`] describes the desired set of channels as channel × ticker sets. When the
+//! watchlist changes, [`Subscription::diff`] compares it against what is currently active and
+//! yields only the subscribe/unsubscribe frames needed to get there, so tickers can be added or
+//! removed without reconnecting. The frames are plain JSON strings and can be sent over any
+//! WebSocket transport; [`Connection::update`](super::Connection::update) does this for the
+//! built-in client.
+//!
+//! # Example
+//!
+//! ```
+//! use polygon::stream::{Channel, Subscription};
+//!
+//! let mut watchlist = Subscription::new()
+//!     .channels([Channel::Trades, Channel::Quotes])
+//!     .tickers(["AAPL", "MSFT"]);
+//! let active = watchlist.params();
+//!
+//! watchlist.remove_ticker("MSFT");
+//! watchlist.insert_ticker("NVDA");
+//!
+//! let diff = watchlist.diff(&active);
+//! assert_eq!(
+//!     diff.frames(),
+//!     vec![
+//!         r#"{"action":"subscribe","params":"Q.NVDA,T.NVDA"}"#,
+//!         r#"{"action":"unsubscribe","params":"Q.MSFT,T.MSFT"}"#,
+//!     ]
+//! );
+//! ```
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+/// Event channel of a subscription (the part before the `.` in `T.AAPL`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Channel {
+    /// Trades (`T`)
+    Trades,
+    /// Quotes (`Q`)
+    Quotes,
+    /// Per-second aggregates (`A`)
+    SecondAggregates,
+    /// Per-minute aggregates (`AM`)
+    MinuteAggregates,
+    /// Limit up/limit down bands (`LULD`)
+    Luld,
+    /// Fair market value (`FMV`)
+    FairMarketValue,
+    /// Any other channel prefix (e.g., `XT` for crypto trades)
+    Other(String),
+}
+
+impl AsRef<str> for Channel {
+    /// Channel prefix (e.g., `AM`)
+    fn as_ref(&self) -> &str {
+        match self {
+            Channel::Trades => "T",
+            Channel::Quotes => "Q",
+            Channel::SecondAggregates => "A",
+            Channel::MinuteAggregates => "AM",
+            Channel::Luld => "LULD",
+            Channel::FairMarketValue => "FMV",
+            Channel::Other(prefix) => prefix,
+        }
+    }
+}
+
+impl std::fmt::Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl FromStr for Channel {
+    type Err = crate::error::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "T" => Ok(Channel::Trades),
+            "Q" => Ok(Channel::Quotes),
+            "A" => Ok(Channel::SecondAggregates),
+            "AM" => Ok(Channel::MinuteAggregates),
+            "LULD" => Ok(Channel::Luld),
+            "FMV" => Ok(Channel::FairMarketValue),
+            "" => Err(crate::error::Error::Custom("Invalid channel: empty".to_string())),
+            other => Ok(Channel::Other(other.to_string())),
+        }
+    }
+}
+
+/// Desired set of subscriptions, built from channel × ticker sets
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Subscription {
+    /// Channels subscribed for every ticker
+    pub channels: BTreeSet<Channel>,
+    /// Tickers subscribed on every channel (`*` means all tickers)
+    pub tickers: BTreeSet<String>,
+    /// Additional raw params outside the channel × ticker product (e.g., "AM.*")
+    pub extra: BTreeSet<String>,
+}
+
+impl Subscription {
+    /// Create an empty subscription
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a channel
+    pub fn channel(mut self, channel: Channel) -> Self {
+        self.channels.insert(channel);
+        self
+    }
+
+    /// Add channels
+    pub fn channels(mut self, channels: impl IntoIterator<Item = Channel>) -> Self {
+        self.channels.extend(channels);
+        self
+    }
+
+    /// Add a ticker
+    pub fn ticker(mut self, ticker: impl Into<String>) -> Self {
+        self.tickers.insert(ticker.into());
+        self
+    }
+
+    /// Add tickers
+    pub fn tickers(mut self, tickers: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.tickers.extend(tickers.into_iter().map(Into::into));
+        self
+    }
+
+    /// Subscribe every channel to all tickers (`*`)
+    pub fn all_tickers(self) -> Self {
+        self.ticker("*")
+    }
+
+    /// Add a raw param (e.g., "AM.*") outside the channel × ticker product
+    pub fn param(mut self, param: impl Into<String>) -> Self {
+        self.extra.insert(param.into());
+        self
+    }
+
+    /// Add a ticker to the watchlist
+    pub fn insert_ticker(&mut self, ticker: impl Into<String>) -> bool {
+        self.tickers.insert(ticker.into())
+    }
+
+    /// Remove a ticker from the watchlist
+    pub fn remove_ticker(&mut self, ticker: &str) -> bool {
+        self.tickers.remove(ticker)
+    }
+
+    /// All subscription params (e.g., "T.AAPL")
+    pub fn params(&self) -> BTreeSet<String> {
+        let product = self
+            .channels
+            .iter()
+            .flat_map(|c| self.tickers.iter().map(move |t| format!("{c}.{t}")));
+        product.chain(self.extra.iter().cloned()).collect()
+    }
+
+    /// Changes needed to go from the `active` params to this subscription
+    pub fn diff(&self, active: &BTreeSet<String>) -> Diff {
+        Diff::between(&self.params(), active)
+    }
+}
+
+/// Subscribe/unsubscribe changes between two sets of params
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diff {
+    /// Params to subscribe to
+    pub subscribe: BTreeSet<String>,
+    /// Params to unsubscribe from
+    pub unsubscribe: BTreeSet<String>,
+}
+
+impl Diff {
+    /// Changes needed to go from `active` to `desired`
+    pub fn between(desired: &BTreeSet<String>, active: &BTreeSet<String>) -> Self {
+        Self {
+            subscribe: desired.difference(active).cloned().collect(),
+            unsubscribe: active.difference(desired).cloned().collect(),
+        }
+    }
+
+    /// Whether nothing needs to change
+    pub fn is_empty(&self) -> bool {
+        self.subscribe.is_empty() && self.unsubscribe.is_empty()
+    }
+
+    /// JSON frames to send, subscribe first
+    pub fn frames(&self) -> Vec<String> {
+        [("subscribe", &self.subscribe), ("unsubscribe", &self.unsubscribe)]
+            .into_iter()
+            .filter_map(|(action, params)| frame(action, params))
+            .collect()
+    }
+}
+
+/// Build a subscribe/unsubscribe frame, or `None` if there are no params
+pub fn frame<'a>(action: &str, params: impl IntoIterator<Item = &'a String>) -> Option<String> {
+    let params = params.into_iter().map(String::as_str).collect::<Vec<_>>().join(",");
+    if params.is_empty() {
+        return None;
+    }
+    Some(serde_json::json!({ "action": action, "params": params }).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscription_diff() {
+        let mut sub = Subscription::new()
+            .channel(Channel::MinuteAggregates)
+            .tickers(["AAPL", "MSFT"])
+            .param("LULD.*");
+        let active = sub.params();
+        assert_eq!(
+            active.iter().map(String::as_str).collect::<Vec<_>>(),
+            ["AM.AAPL", "AM.MSFT", "LULD.*"]
+        );
+        assert!(sub.diff(&active).is_empty());
+        assert!(sub.diff(&active).frames().is_empty());
+
+        sub.remove_ticker("AAPL");
+        sub.channels.insert("XT".parse().unwrap());
+        let diff = sub.diff(&active);
+        assert_eq!(diff.subscribe, BTreeSet::from(["XT.MSFT".to_string()]));
+        assert_eq!(diff.unsubscribe, BTreeSet::from(["AM.AAPL".to_string()]));
+    }
+}
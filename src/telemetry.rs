@@ -0,0 +1,308 @@
+//! Instrumentation hooks for requests, compiled to no-ops unless the `tracing` or `metrics` feature
+//! is enabled
+//!
+//! Metrics are labelled with the endpoint name (e.g., `aggs.aggregates`, or `other` for requests
+//! not made by an endpoint builder):
+//!
+//! - `polygon_requests_total` (counter): requests made through an endpoint builder by `endpoint`
+//!   and `status` class (`2xx`, `4xx`, `5xx`, ... or `error` when no response was received),
+//!   counted once whatever the retries and including responses served by `cache::Cached`
+//! - `polygon_http_requests_total` (counter): HTTP requests actually sent by the `reqwest` client
+//!   (every retry included, cache hits excluded) by `endpoint` and `status` class
+//! - `polygon_request_duration_seconds` (histogram): time to receive the response, by `endpoint`
+//! - `polygon_decode_duration_seconds` (histogram): time to convert the response, by `endpoint`
+//! - `polygon_retries_total` (counter): retries made by `layer::Retry`, by `endpoint`
+//! - `polygon_cache_requests_total` (counter): lookups by `cache::Cached`, by `endpoint` and
+//!   `result` (`hit`, `miss` or `bypass`)
+//! - `polygon_rate_limit_wait_seconds` (histogram): time spent waiting for `layer::RateLimit`,
+//!   by `endpoint`
+
+// Each hook uses only some of its arguments unless both features are enabled
+#![cfg_attr(not(all(feature = "tracing", feature = "metrics")), allow(unused_variables))]
+
+use std::time::Duration;
+
+use crate::error::Result;
+#[cfg(feature = "tracing")]
+use crate::request::HttpRequest;
+use crate::response::Response;
+
+/// Endpoint label for requests not made by an endpoint builder
+#[cfg(feature = "metrics")]
+const OTHER: &str = "other";
+
+/// Span covering one `Execute::get`, with the response fields recorded as they become known
+#[cfg(feature = "tracing")]
+pub(crate) fn span(request: &HttpRequest) -> tracing::Span {
+    use tracing::field::Empty;
+
+    tracing::info_span!(
+        "polygon.request",
+        endpoint = request.endpoint.unwrap_or_default(),
+        method = %request.method,
+        url = %crate::request::redact_url(&request.url),
+        status = Empty,
+        request_id = Empty,
+        latency_ms = Empty,
+        bytes = Empty,
+        retries = Empty,
+        cache = Empty,
+        rate_limit_wait_ms = Empty,
+        decode_ms = Empty,
+    )
+}
+
+/// Record a response, or the error that replaced it
+pub(crate) fn response<R: Response>(endpoint: Option<&'static str>, response: &Result<R>, latency: Duration) {
+    #[cfg(feature = "tracing")]
+    {
+        let span = tracing::Span::current();
+        span.record("latency_ms", latency.as_secs_f64() * 1000.0);
+        match response {
+            Ok(response) => {
+                span.record("status", response.status());
+                span.record("bytes", response.bytes());
+                if let Some(id) = response.request_id() {
+                    span.record("request_id", id.as_str());
+                }
+                if response.status() >= 400 {
+                    tracing::warn!(status = response.status(), "polygon request failed");
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "polygon request failed"),
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    {
+        let endpoint = endpoint.unwrap_or(OTHER);
+        metrics::counter!("polygon_requests_total", "endpoint" => endpoint, "status" => status_class(response))
+            .increment(1);
+        metrics::histogram!("polygon_request_duration_seconds", "endpoint" => endpoint).record(latency);
+    }
+}
+
+/// Record an HTTP request sent over the network, called by the innermost client so that retries
+/// are counted and cache hits are not
+#[cfg_attr(not(any(feature = "reqwest", feature = "testing")), allow(dead_code))]
+pub(crate) fn http_request<R: Response>(endpoint: Option<&'static str>, response: &Result<R>) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(
+        "polygon_http_requests_total",
+        "endpoint" => endpoint.unwrap_or(OTHER),
+        "status" => status_class(response)
+    )
+    .increment(1);
+}
+
+#[cfg(feature = "metrics")]
+fn status_class<R: Response>(response: &Result<R>) -> String {
+    match response {
+        Ok(response) => format!("{}xx", response.status() / 100),
+        Err(_) => "error".to_string(),
+    }
+}
+
+/// Record the time spent converting the response
+pub(crate) fn decoded<T>(endpoint: Option<&'static str>, output: &Result<T>, elapsed: Duration) {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::Span::current().record("decode_ms", elapsed.as_secs_f64() * 1000.0);
+        if let Err(e) = output {
+            tracing::debug!(error = %e, "polygon response not processed");
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    metrics::histogram!("polygon_decode_duration_seconds", "endpoint" => endpoint.unwrap_or(OTHER)).record(elapsed);
+}
+
+/// Record that a request is retried after `delay`
+#[cfg_attr(not(feature = "reqwest"), allow(dead_code))]
+pub(crate) fn retry(endpoint: Option<&'static str>, retry: u32, delay: Duration) {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::Span::current().record("retries", retry);
+        tracing::info!(retry, delay_ms = delay.as_millis() as u64, "retrying polygon request");
+    }
+
+    #[cfg(feature = "metrics")]
+    metrics::counter!("polygon_retries_total", "endpoint" => endpoint.unwrap_or(OTHER)).increment(1);
+}
+
+/// Record a cache lookup, `result` being `hit`, `miss` or `bypass`
+pub(crate) fn cache(endpoint: Option<&'static str>, result: &'static str) {
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record("cache", result);
+
+    #[cfg(feature = "metrics")]
+    metrics::counter!(
+        "polygon_cache_requests_total",
+        "endpoint" => endpoint.unwrap_or(OTHER),
+        "result" => result
+    )
+    .increment(1);
+}
+
+/// Record time spent waiting for the rate limiter
+#[cfg_attr(not(feature = "reqwest"), allow(dead_code))]
+pub(crate) fn rate_limit_wait(endpoint: Option<&'static str>, wait: Duration) {
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record("rate_limit_wait_ms", wait.as_secs_f64() * 1000.0);
+
+    #[cfg(feature = "metrics")]
+    metrics::histogram!("polygon_rate_limit_wait_seconds", "endpoint" => endpoint.unwrap_or(OTHER)).record(wait);
+}
+
+#[cfg(all(test, feature = "testing", feature = "reqwest"))]
+mod tests {
+    use std::time::Duration;
+
+    use crate::cache::Cache;
+    use crate::client::Polygon;
+    use crate::layer::Retry;
+    use crate::rest;
+    use crate::testing::{Mock, MockClient};
+
+    /// A client whose first `previous_close` request is retried after a `503`, with later ones
+    /// served from the cache
+    fn client() -> Polygon<impl crate::request::Request> {
+        let mock = MockClient::new();
+        mock.register(
+            Mock::get("/v2/aggs/ticker/AAPL/prev")
+                .respond(503, "")
+                .header("Retry-After", "0")
+                .times(1),
+        )
+        .register(
+            Mock::get("/v2/aggs/ticker/AAPL/prev")
+                .respond(200, "{}")
+                .request_id("req"),
+        );
+        Polygon::default()
+            .with_client(mock)
+            .with_key("test")
+            .layer(Retry::new(1).backoff(Duration::from_millis(1)))
+            .layer(Cache::default())
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn test_span_fields() {
+        use std::collections::HashMap;
+        use std::sync::{Arc, Mutex};
+
+        use tracing::field::{Field, Visit};
+        use tracing_subscriber::layer::{Context, SubscriberExt};
+
+        /// Collects the fields of `polygon.request` spans, one map per span
+        #[derive(Clone, Default)]
+        struct Spans(Arc<Mutex<Vec<HashMap<String, String>>>>);
+
+        struct Fields<'a>(&'a mut HashMap<String, String>);
+
+        impl Visit for Fields<'_> {
+            fn record_str(&mut self, field: &Field, value: &str) {
+                self.0.insert(field.name().to_string(), value.to_string());
+            }
+
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0.insert(field.name().to_string(), format!("{value:?}"));
+            }
+        }
+
+        impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Spans {
+            fn on_new_span(&self, attrs: &tracing::span::Attributes<'_>, _: &tracing::Id, _: Context<'_, S>) {
+                let mut fields = HashMap::new();
+                attrs.record(&mut Fields(&mut fields));
+                self.0.lock().unwrap().push(fields);
+            }
+
+            fn on_record(&self, _: &tracing::Id, values: &tracing::span::Record<'_>, _: Context<'_, S>) {
+                // Spans are not nested, so the last one is the one being recorded
+                if let Some(fields) = self.0.lock().unwrap().last_mut() {
+                    values.record(&mut Fields(fields));
+                }
+            }
+        }
+
+        let spans = Spans::default();
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(spans.clone()));
+        let client = client();
+        rest::aggs::previous_close(&client, "AAPL").get().await.unwrap();
+        rest::aggs::previous_close(&client, "AAPL").get().await.unwrap();
+
+        let spans = spans.0.lock().unwrap();
+        assert_eq!(spans.len(), 2);
+        let first = &spans[0];
+        assert_eq!(first["endpoint"], "aggs.previous_close");
+        assert_eq!(first["url"], "https://api.polygon.io/v2/aggs/ticker/AAPL/prev");
+        assert_eq!(first["status"], "200");
+        assert_eq!(first["request_id"], "req");
+        assert_eq!(first["retries"], "1");
+        assert_eq!(first["cache"], "miss");
+        assert!(first.contains_key("decode_ms"));
+        assert_eq!(spans[1]["cache"], "hit");
+        assert!(!spans[1].contains_key("retries"));
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_metrics() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            runtime.block_on(async {
+                let client = client();
+                rest::aggs::previous_close(&client, "AAPL").get().await.unwrap();
+                rest::aggs::previous_close(&client, "AAPL").get().await.unwrap();
+            });
+        });
+
+        let snapshot = snapshotter.snapshot().into_vec();
+        let value = |name: &str, labels: &[(&str, &str)]| {
+            snapshot.iter().find_map(|(key, _, _, value)| {
+                let key = key.key();
+                let matches = key.name() == name
+                    && labels
+                        .iter()
+                        .all(|(k, v)| key.labels().any(|l| l.key() == *k && l.value() == *v));
+                matches.then_some(value)
+            })
+        };
+        let counter = |name: &str, labels: &[(&str, &str)]| match value(name, labels) {
+            Some(DebugValue::Counter(n)) => *n,
+            other => panic!("expected counter {name} {labels:?}, got {other:?}"),
+        };
+        let endpoint = ("endpoint", "aggs.previous_close");
+
+        assert_eq!(counter("polygon_requests_total", &[endpoint, ("status", "2xx")]), 2);
+        assert_eq!(
+            counter("polygon_http_requests_total", &[endpoint, ("status", "5xx")]),
+            1
+        );
+        assert_eq!(
+            counter("polygon_http_requests_total", &[endpoint, ("status", "2xx")]),
+            1
+        );
+        assert_eq!(counter("polygon_retries_total", &[endpoint]), 1);
+        assert_eq!(
+            counter("polygon_cache_requests_total", &[endpoint, ("result", "miss")]),
+            1
+        );
+        assert_eq!(
+            counter("polygon_cache_requests_total", &[endpoint, ("result", "hit")]),
+            1
+        );
+        let Some(DebugValue::Histogram(durations)) = value("polygon_request_duration_seconds", &[endpoint]) else {
+            panic!("expected request duration histogram");
+        };
+        assert_eq!(durations.len(), 2);
+    }
+}
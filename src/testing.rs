@@ -0,0 +1,428 @@
+//! Mock HTTP client for unit tests
+//!
+//! [`MockClient`] implements [`Request`] without touching the network. Register [`Mock`]s that
+//! match on method, path and query params, and each matching request receives the canned
+//! status and body, an injected error, or either after a delay. Every request is recorded so
+//! tests can assert on what was sent.
+//!
+//! `MockClient` is cheaply cloneable and clones share state, so keep one handle for assertions
+//! and give another to the [`Polygon`](crate::client::Polygon) client.
+//!
+//! This module is only available when the `testing` feature is enabled.
+//!
+//! # Example
+//!
+//! ```
+//! use polygon::client::Polygon;
+//! use polygon::execute::Execute as _;
+//! use polygon::rest;
+//! use polygon::testing::{Mock, MockClient};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mock = MockClient::new();
+//! mock.register(Mock::get("/v2/aggs/ticker/*/prev").respond(200, r#"{"results": []}"#));
+//!
+//! let client = Polygon::<MockClient>::default().with_client(mock.clone()).with_key("test");
+//! let json = rest::aggs::previous_close(&client, "AAPL").adjusted(true).get().await?;
+//!
+//! assert_eq!(json, r#"{"results": []}"#);
+//! assert_eq!(mock.requests()[0].query("adjusted"), Some("true"));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::request::{HttpRequest, Request};
+use crate::response::Response;
+
+/// Response returned by [`MockClient`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Response body
+    pub body: String,
+    /// Request ID
+    pub request_id: Option<String>,
+    /// Response headers
+    pub headers: Vec<(String, String)>,
+    /// Injected latency
+    pub latency: Option<Duration>,
+}
+
+impl Response for MockResponse {
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn body(&self) -> &str {
+        &self.body
+    }
+
+    fn request_id(&self) -> &Option<String> {
+        &self.request_id
+    }
+
+    fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+}
+
+/// A request received by [`MockClient`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Received {
+    /// HTTP method (`GET` or `POST`)
+    pub method: String,
+    /// Full request URL
+    pub url: String,
+    /// URL path (e.g., `/v3/reference/tickers`)
+    pub path: String,
+    /// Query params in order
+    pub params: Vec<(String, String)>,
+    /// Request headers in order
+    pub headers: Vec<(String, String)>,
+    /// Request body for POST requests
+    pub body: Option<String>,
+    /// Request timeout
+    pub timeout: Option<Duration>,
+    /// Whether the request asked to skip caches
+    pub bypass_cache: bool,
+}
+
+impl Received {
+    fn new(request: HttpRequest) -> Self {
+        let url = request.url.as_str();
+        let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let path_and_query = without_scheme.find('/').map_or("/", |i| &without_scheme[i..]);
+        let (path, query) = path_and_query.split_once('?').unwrap_or((path_and_query, ""));
+        let params = query
+            .split('&')
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                let (k, v) = p.split_once('=').unwrap_or((p, ""));
+                (k.to_string(), v.to_string())
+            })
+            .collect();
+
+        Self {
+            method: request.method.to_string(),
+            path: path.to_string(),
+            params,
+            url: request.url,
+            headers: request.headers,
+            body: request.body,
+            timeout: request.timeout,
+            bypass_cache: request.bypass_cache,
+        }
+    }
+
+    /// Value of a query param
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    /// Value of a header, matching the name case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// What a [`Mock`] replies with
+#[derive(Debug, Clone)]
+enum Reply {
+    Response(MockResponse),
+    Error(Error),
+}
+
+/// A canned reply for requests matching method, path and query params
+#[derive(Debug, Clone)]
+pub struct Mock {
+    method: Option<String>,
+    path: Option<String>,
+    params: Vec<(String, String)>,
+    reply: Reply,
+    latency: Option<Duration>,
+    times: Option<usize>,
+}
+
+impl Mock {
+    fn new(method: Option<&str>, path: Option<&str>) -> Self {
+        Self {
+            method: method.map(str::to_string),
+            path: path.map(str::to_string),
+            params: Vec::new(),
+            reply: Reply::Response(MockResponse {
+                status: 200,
+                body: String::new(),
+                request_id: None,
+                headers: Vec::new(),
+                latency: None,
+            }),
+            latency: None,
+            times: None,
+        }
+    }
+
+    /// Match GET requests to `path`, where `*` matches any single path segment
+    pub fn get(path: &str) -> Self {
+        Self::new(Some("GET"), Some(path))
+    }
+
+    /// Match POST requests to `path`, where `*` matches any single path segment
+    pub fn post(path: &str) -> Self {
+        Self::new(Some("POST"), Some(path))
+    }
+
+    /// Match every request
+    pub fn any() -> Self {
+        Self::new(None, None)
+    }
+
+    /// Only match requests with this query param
+    pub fn query(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((name.into(), value.into()));
+        self
+    }
+
+    /// Reply with a status code and body
+    pub fn respond(mut self, status: u16, body: impl Into<String>) -> Self {
+        match &mut self.reply {
+            Reply::Response(response) => {
+                response.status = status;
+                response.body = body.into();
+            }
+            Reply::Error(_) => {
+                self.reply = Reply::Response(MockResponse {
+                    status,
+                    body: body.into(),
+                    request_id: None,
+                    headers: Vec::new(),
+                    latency: None,
+                })
+            }
+        }
+        self
+    }
+
+    /// Add a header to the reply (e.g., `Retry-After`)
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        if let Reply::Response(response) = &mut self.reply {
+            response.headers.push((name.into(), value.into()));
+        }
+        self
+    }
+
+    /// Set the request ID of the reply
+    pub fn request_id(mut self, request_id: impl Into<String>) -> Self {
+        if let Reply::Response(response) = &mut self.reply {
+            response.request_id = Some(request_id.into());
+        }
+        self
+    }
+
+    /// Fail with an error instead of replying (e.g., to simulate a network failure)
+    pub fn error(mut self, error: Error) -> Self {
+        self.reply = Reply::Error(error);
+        self
+    }
+
+    /// Wait before replying
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Stop matching after `n` requests, so later mocks can take over
+    pub fn times(mut self, n: usize) -> Self {
+        self.times = Some(n);
+        self
+    }
+
+    fn matches(&self, request: &Received) -> bool {
+        self.times != Some(0)
+            && self.method.as_ref().is_none_or(|m| *m == request.method)
+            && self.path.as_ref().is_none_or(|p| path_matches(p, &request.path))
+            && self.params.iter().all(|(k, v)| request.query(k) == Some(v))
+    }
+}
+
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+    pattern.len() == path.len() && pattern.iter().zip(&path).all(|(p, s)| *p == "*" || p == s)
+}
+
+#[derive(Debug, Default)]
+struct State {
+    mocks: Vec<Mock>,
+    requests: Vec<Received>,
+}
+
+/// Mock HTTP client returning canned responses
+#[derive(Debug, Clone, Default)]
+pub struct MockClient {
+    state: Arc<Mutex<State>>,
+}
+
+impl MockClient {
+    /// Create a client with no mocks registered
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a mock; earlier mocks take precedence
+    pub fn register(&self, mock: Mock) -> &Self {
+        self.lock().mocks.push(mock);
+        self
+    }
+
+    /// Requests received so far
+    pub fn requests(&self) -> Vec<Received> {
+        self.lock().requests.clone()
+    }
+
+    /// Number of requests received for paths matching `path`
+    pub fn received(&self, path: &str) -> usize {
+        self.lock()
+            .requests
+            .iter()
+            .filter(|r| path_matches(path, &r.path))
+            .count()
+    }
+
+    /// Remove all mocks and recorded requests
+    pub fn reset(&self) {
+        *self.lock() = State::default();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("mock lock poisoned")
+    }
+
+    async fn handle(&self, request: Received) -> Result<MockResponse> {
+        let (reply, latency) = {
+            let mut state = self.lock();
+            state.requests.push(request.clone());
+            let mock = state
+                .mocks
+                .iter_mut()
+                .find(|m| m.matches(&request))
+                .ok_or_else(|| Error::Custom(format!("No mock matches {} {}", request.method, request.url)))?;
+            if let Some(n) = &mut mock.times {
+                *n -= 1;
+            }
+            (mock.reply.clone(), mock.latency)
+        };
+
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+        match reply {
+            Reply::Response(response) => Ok(MockResponse { latency, ..response }),
+            Reply::Error(error) => Err(error),
+        }
+    }
+}
+
+impl Request for MockClient {
+    type Response = MockResponse;
+
+    fn new() -> Self {
+        Self::default()
+    }
+
+    async fn send(&self, request: HttpRequest) -> Result<MockResponse> {
+        let endpoint = request.endpoint;
+        let response = self.handle(Received::new(request)).await;
+        crate::telemetry::http_request(endpoint, &response);
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Polygon;
+    use crate::rest;
+
+    fn client(mock: &MockClient) -> Polygon<MockClient> {
+        Polygon::default().with_client(mock.clone()).with_key("test")
+    }
+
+    #[tokio::test]
+    async fn test_mock_matching() {
+        let mock = MockClient::new();
+        mock.register(
+            Mock::get("/v3/reference/tickers")
+                .query("market", "crypto")
+                .respond(200, "crypto"),
+        )
+        .register(Mock::get("/v3/reference/tickers").respond(200, "stocks").times(1))
+        .register(
+            Mock::any()
+                .respond(503, "unavailable")
+                .request_id("req")
+                .header("Retry-After", "2"),
+        );
+        let client = client(&mock);
+
+        let crypto = rest::tickers::all(&client)
+            .market("crypto")
+            .timeout(Duration::from_secs(1))
+            .get()
+            .await
+            .unwrap();
+        assert_eq!(crypto, "crypto");
+        assert_eq!(rest::tickers::all(&client).get().await.unwrap(), "stocks");
+
+        // `times(1)` is used up, so the catch-all replies
+        let Err(Error::ApiError { status, request_id, .. }) = rest::tickers::all(&client).get().await else {
+            panic!("expected API error");
+        };
+        assert_eq!((status, request_id.as_deref()), (503, Some("req")));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].header("authorization"), Some("Bearer test"));
+        assert_eq!(requests[0].query("apiKey"), None);
+        assert_eq!(requests[0].timeout, Some(Duration::from_secs(1)));
+        assert_eq!(requests[1].timeout, None);
+        assert_eq!(mock.received("/v3/reference/*"), 3);
+
+        let response = mock
+            .send(HttpRequest::get("https://api.polygon.io/v1/x"))
+            .await
+            .unwrap();
+        assert_eq!(response.retry_after(), Some(Duration::from_secs(2)));
+    }
+
+    #[tokio::test]
+    async fn test_mock_errors_and_latency() {
+        let mock = MockClient::new();
+        mock.register(
+            Mock::get("/v2/aggs/ticker/*/prev")
+                .latency(Duration::from_millis(20))
+                .error(Error::Custom("connection reset".to_string())),
+        );
+        let client = client(&mock);
+
+        let started = std::time::Instant::now();
+        let result = rest::aggs::previous_close(&client, "AAPL").get().await;
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert!(matches!(result, Err(Error::Custom(e)) if e == "connection reset"));
+
+        let unmatched = rest::tickers::types(&client).get().await;
+        assert!(matches!(unmatched, Err(Error::Custom(e)) if e.starts_with("No mock matches GET")));
+    }
+}
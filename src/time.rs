@@ -0,0 +1,168 @@
+//! Date and time helpers for session-aware work
+//!
+//! Polygon reports bar timestamps as Unix milliseconds in UTC, while US equity sessions are
+//! defined in Eastern Time. These helpers convert between the two and classify a timestamp
+//! into its trading session.
+//!
+//! This module is only available when the `chrono` feature is enabled. Without it the crate still
+//! uses its plain `YYYY-MM-DD` date arithmetic internally.
+//!
+//! # Example
+//!
+//! ```
+//! use polygon::time::{self, Session};
+//!
+//! // 2024-01-02 14:30:00 UTC is 09:30 in New York
+//! let dt = time::from_millis(1704205800000).unwrap();
+//! assert_eq!(time::session(dt), Session::Regular);
+//! ```
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+#[cfg(feature = "chrono")]
+use chrono_tz::Tz;
+
+#[cfg(feature = "chrono")]
+pub use chrono_tz::America::New_York;
+
+/// US equity trading session for a point in time
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Session {
+    /// 04:00 - 09:30 Eastern Time
+    PreMarket,
+    /// 09:30 - 16:00 Eastern Time
+    Regular,
+    /// 16:00 - 20:00 Eastern Time
+    AfterHours,
+    /// Outside extended hours or on a weekend
+    Closed,
+}
+
+/// Convert a Unix millisecond timestamp to a UTC datetime
+#[cfg(feature = "chrono")]
+pub fn from_millis(ms: i64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_millis_opt(ms).single()
+}
+
+/// Convert a datetime to America/New_York local time
+#[cfg(feature = "chrono")]
+pub fn to_new_york<T: TimeZone>(dt: &DateTime<T>) -> DateTime<Tz> {
+    dt.with_timezone(&New_York)
+}
+
+/// Classify a point in time into its US equity trading session
+///
+/// Market holidays and early closes are not taken into account.
+#[cfg(feature = "chrono")]
+pub fn session<T: TimeZone>(dt: DateTime<T>) -> Session {
+    let local = to_new_york(&dt);
+    if matches!(local.weekday(), Weekday::Sat | Weekday::Sun) {
+        return Session::Closed;
+    }
+
+    let t = local.time();
+    let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).expect("valid time");
+    if t < at(4, 0) || t >= at(20, 0) {
+        Session::Closed
+    } else if t < at(9, 30) {
+        Session::PreMarket
+    } else if t < at(16, 0) {
+        Session::Regular
+    } else {
+        Session::AfterHours
+    }
+}
+
+/// Parse a `YYYY-MM-DD` date
+#[cfg(feature = "chrono")]
+pub(crate) fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Parse an RFC 3339 timestamp (e.g., `2024-06-24T18:33:53Z`)
+#[cfg(feature = "chrono")]
+pub(crate) fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s).ok().map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Days since 1970-01-01 of a `YYYY-MM-DD` date, without needing `chrono`
+pub(crate) fn parse_days(s: &str) -> Option<i64> {
+    let mut parts = s.split('-').map(|part| part.parse::<i64>().ok());
+    match (s.len(), parts.next(), parts.next(), parts.next(), parts.next()) {
+        (10, Some(Some(year)), Some(Some(month)), Some(Some(day)), None)
+            if (1..=12).contains(&month) && (1..=31).contains(&day) =>
+        {
+            Some(days_from_civil(year, month, day))
+        }
+        _ => None,
+    }
+}
+
+/// `YYYY-MM-DD` of days since 1970-01-01
+pub(crate) fn format_days(days: i64) -> String {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date, see <http://howardhinnant.github.io/date_algorithms.html>
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days() {
+        for date in ["1970-01-01", "2000-02-29", "2024-12-31", "1969-07-20"] {
+            assert_eq!(format_days(parse_days(date).unwrap()), date);
+        }
+        assert_eq!(parse_days("2024-01-01"), Some(19723));
+        assert_eq!(parse_days("2024-1-1"), None);
+        assert_eq!(parse_days("2024-13-01"), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_session() {
+        let at = |s: &str| parse_datetime(s).unwrap();
+
+        // Winter (EST, UTC-5)
+        assert_eq!(session(at("2024-01-02T08:59:00Z")), Session::Closed);
+        assert_eq!(session(at("2024-01-02T09:00:00Z")), Session::PreMarket);
+        assert_eq!(session(at("2024-01-02T14:30:00Z")), Session::Regular);
+        assert_eq!(session(at("2024-01-02T21:00:00Z")), Session::AfterHours);
+        // Summer (EDT, UTC-4)
+        assert_eq!(session(at("2024-07-01T13:30:00Z")), Session::Regular);
+        assert_eq!(session(at("2024-07-01T20:00:00Z")), Session::AfterHours);
+        // Weekend
+        assert_eq!(session(at("2024-01-06T15:00:00Z")), Session::Closed);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_date_params() {
+        use crate::request::common::{DateLike, DateParam};
+
+        let date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        assert_eq!(date.into_date_param(), "2024-01-31");
+        assert_eq!(date.into_date(), "2024-01-31");
+        assert_eq!(from_millis(1704205800000).unwrap().into_date_param(), "1704205800000");
+        assert_eq!(parse_date("2024-01-31T00:00:00Z"), Some(date));
+    }
+}
@@ -471,6 +471,18 @@ async fn call_tickers<Client: Request>(client: &Polygon<Client>, endpoint: Ticke
             if let Some(t) = p.ticker {
                 q = q.ticker(t);
             }
+            if let Some(t) = p.ticker_gte {
+                q = q.ticker_gte(t);
+            }
+            if let Some(t) = p.ticker_gt {
+                q = q.ticker_gt(t);
+            }
+            if let Some(t) = p.ticker_lte {
+                q = q.ticker_lte(t);
+            }
+            if let Some(t) = p.ticker_lt {
+                q = q.ticker_lt(t);
+            }
             if let Some(tt) = p.ticker_type {
                 q = q.ticker_type(tt);
             }
@@ -480,6 +492,21 @@ async fn call_tickers<Client: Request>(client: &Polygon<Client>, endpoint: Ticke
             if let Some(e) = p.exchange {
                 q = q.exchange(e);
             }
+            if let Some(c) = p.cusip {
+                q = q.cusip(c);
+            }
+            if let Some(c) = p.cik {
+                q = q.cik(c);
+            }
+            if let Some(d) = p.date {
+                q = q.date(d);
+            }
+            if let Some(s) = p.search {
+                q = q.search(s);
+            }
+            if let Some(a) = p.active {
+                q = q.active(a);
+            }
             if let Some(l) = p.limit {
                 q = q.limit(l);
             }
@@ -489,6 +516,9 @@ async fn call_tickers<Client: Request>(client: &Polygon<Client>, endpoint: Ticke
             if let Some(o) = p.order {
                 q = q.order(o);
             }
+            if let Some(c) = p.cursor {
+                q = q.cursor(c);
+            }
             q.get().await
         }
         Tickers::Details(p) => {
@@ -512,12 +542,33 @@ async fn call_tickers<Client: Request>(client: &Polygon<Client>, endpoint: Ticke
             if let Some(t) = p.ticker {
                 q = q.ticker(t);
             }
+            if let Some(d) = p.published_utc {
+                q = q.published_utc(d);
+            }
+            if let Some(d) = p.published_utc_gte {
+                q = q.published_utc_gte(d);
+            }
+            if let Some(d) = p.published_utc_gt {
+                q = q.published_utc_gt(d);
+            }
+            if let Some(d) = p.published_utc_lte {
+                q = q.published_utc_lte(d);
+            }
+            if let Some(d) = p.published_utc_lt {
+                q = q.published_utc_lt(d);
+            }
             if let Some(l) = p.limit {
                 q = q.limit(l);
             }
             if let Some(o) = p.order {
                 q = q.order(o);
             }
+            if let Some(s) = p.sort {
+                q = q.sort(s);
+            }
+            if let Some(c) = p.cursor {
+                q = q.cursor(c);
+            }
             q.get().await
         }
     }
@@ -533,7 +584,7 @@ async fn call_aggs<Client: Request>(client: &Polygon<Client>, endpoint: Aggs) ->
                 q = q.adjusted(a);
             }
             if let Some(s) = p.sort {
-                q = q.sort(format!("{s:?}").to_lowercase());
+                q = q.sort(s);
             }
             if let Some(l) = p.limit {
                 q = q.limit(l);